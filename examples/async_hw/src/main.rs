@@ -6,20 +6,20 @@ use lrvm_aux::storage::BootRom;
 use lrvm_aux::volatile_mem::Ram;
 use lrvm_tools::debug::{exec_vm, RunConfig};
 use lrvm_tools::lasm::assemble_words;
-use rand::Rng;
+use lrvm_tools::metadata::HwIdAllocator;
 
 fn main() {
     let program = assemble_words(include_str!("source.lasm"))
         .unwrap_or_else(|err| panic!("Failed to assemble demo program: {}", err));
 
-    let mut rng = rand::thread_rng();
+    let mut hw_ids = HwIdAllocator::new(0);
 
     exec_vm(
         vec![
-            Box::new(BootRom::with_size(program, 0x1000, rng.gen()).unwrap()),
-            Box::new(Ram::new(0x1000, rng.gen()).unwrap()),
-            Box::new(AsyncCounter::new(rng.gen())),
-            Box::new(NumberDisplay::new_print(rng.gen())),
+            Box::new(BootRom::with_size(program, 0x1000, hw_ids.allocate()).unwrap()),
+            Box::new(Ram::new(0x1000, hw_ids.allocate()).unwrap()),
+            Box::new(AsyncCounter::new(hw_ids.allocate())),
+            Box::new(NumberDisplay::new_print(hw_ids.allocate())),
         ],
         RunConfig::halt_on_ex(),
     );
@@ -6,25 +6,25 @@ use lrvm_aux::storage::BootRom;
 use lrvm_aux::volatile_mem::Ram;
 use lrvm_tools::debug::{exec_vm, RunConfig};
 use lrvm_tools::lasm::assemble_words;
-use rand::Rng;
+use lrvm_tools::metadata::HwIdAllocator;
 
 fn main() {
     let program = assemble_words(include_str!("source.lasm"))
         .unwrap_or_else(|err| panic!("Failed to assemble demo program: {}", err));
 
-    let mut rng = rand::thread_rng();
+    let mut hw_ids = HwIdAllocator::new(0);
 
     // BootROM containing the program
-    let bootrom = BootRom::with_size(program, 0x1000, rng.gen()).unwrap();
+    let bootrom = BootRom::with_size(program, 0x1000, hw_ids.allocate()).unwrap();
 
     // The first RAM, for the stack (calls & the brainfuck program's loops)
-    let stack = Ram::new(0x1000, rng.gen()).unwrap();
+    let stack = Ram::new(0x1000, hw_ids.allocate()).unwrap();
 
     // The second RAM, for the brainfuck program's memory
-    let bf_memory = Ram::new(0x1000, rng.gen()).unwrap();
+    let bf_memory = Ram::new(0x1000, hw_ids.allocate()).unwrap();
 
     // A buffered display, to allow the brainfuck program to display messages
-    let display = BufferedDisplay::new_print_lossy(0x1000, rng.gen()).unwrap();
+    let display = BufferedDisplay::new_print_lossy(0x1000, hw_ids.allocate()).unwrap();
 
     // A synchronous line keyboard, to allow the brainfuck program to input strings
     let line_keyboard = SyncLineKeyboard::new(
@@ -34,7 +34,7 @@ fn main() {
             std::io::stdin().read_line(&mut input).unwrap();
             input
         }),
-        rng.gen(),
+        hw_ids.allocate(),
     )
     .unwrap();
 
@@ -57,14 +57,14 @@ fn main() {
 
             c
         }),
-        rng.gen(),
+        hw_ids.allocate(),
     );
 
     // A character display, to allow the brainfuck program to display single characters
-    let char_display = CharDisplay::new_print_lossy(rng.gen());
+    let char_display = CharDisplay::new_print_lossy(hw_ids.allocate());
 
     // A number display, to allow the brainfuck program to display numbers without performing the number <=> string conversin
-    let num_display = NumberDisplay::new_print(rng.gen());
+    let num_display = NumberDisplay::new_print(hw_ids.allocate());
 
     exec_vm(
         vec![
@@ -1,9 +1,6 @@
-use lrvm::board::{Bus, MotherBoard};
-use lrvm_aux::display::BufferedDisplay;
-use lrvm_aux::storage::BootRom;
-use lrvm_aux::volatile_mem::Ram;
+use lrvm::board::MotherBoard;
+use lrvm_aux::BoardBuilder;
 use lrvm_tools::lasm::assemble_words;
-use rand::Rng;
 
 fn main() {
     println!("> Assembling LASM code...");
@@ -13,27 +10,22 @@ fn main() {
 
     println!("> Preparing components and motherboard...");
 
-    let mut rng = rand::thread_rng();
-
-    let components: Vec<Box<dyn Bus>> = vec![
-        Box::new(BootRom::with_size(program, 0x1000, rng.gen()).unwrap()),
-        Box::new(Ram::new(0x1000, rng.gen()).unwrap()),
-        Box::new(
-            BufferedDisplay::new(
-                0x100,
-                Box::new(|string| {
-                    print!("[Display] {}", string.unwrap_or("<invalid input received>"))
-                }),
-                rng.gen(),
-            )
-            .unwrap(),
-        ),
-    ];
+    let (components, index) = BoardBuilder::new()
+        .boot_rom_with_size(program, 0x1000)
+        .ram(0x1000)
+        .display(
+            0x100,
+            Box::new(|string| print!("[Display] {}", string.unwrap_or("<invalid input received>"))),
+        )
+        .build()
+        .unwrap();
 
     let mut motherboard = MotherBoard::new(components);
 
     motherboard.map(|mem| {
-        mem.map_contiguous(0x0000_0000, [0, 1, 2]).mapping.unwrap();
+        mem.map_contiguous(0x0000_0000, index.ids(&["boot_rom", "ram", "display"]))
+            .mapping
+            .unwrap();
     });
 
     motherboard.reset();
@@ -6,7 +6,7 @@ use lrvm_tools::debug::{exec_vm, RunConfig};
 use lrvm_tools::exceptions::AuxHwException;
 use lrvm_tools::lasm::assemble_words;
 use lrvm_tools::metadata::DeviceCategory;
-use rand::Rng;
+use lrvm_tools::metadata::HwIdAllocator;
 
 struct Component {
     uid: u64,
@@ -105,16 +105,16 @@ fn main() {
 
     println!("> Setting up and booting the VM...");
 
-    let mut rng = rand::thread_rng();
+    let mut hw_ids = HwIdAllocator::new(0);
 
     let mut motherboard = exec_vm(
         vec![
             // BootROM containing the program's machine code
-            Box::new(BootRom::with_size(program, 0x1000, rng.gen()).unwrap()),
+            Box::new(BootRom::with_size(program, 0x1000, hw_ids.allocate()).unwrap()),
             // RAM that will contain informations about each detected components
-            Box::new(Ram::new(0x1000, rng.gen()).unwrap()),
+            Box::new(Ram::new(0x1000, hw_ids.allocate()).unwrap()),
             // RAM that will be used for the stack
-            Box::new(Ram::new(0x20, rng.gen()).unwrap()),
+            Box::new(Ram::new(0x20, hw_ids.allocate()).unwrap()),
         ],
         RunConfig::halt_on_ex(),
     )
@@ -1,6 +1,7 @@
 //! In order to be able to connect to the motherboard, auxiliary components must implement the [`Bus`] trait.
 //!
-//! This trait describes how the component handles NAME, METADATA, READ, WRITE and RESET requests from the motherboard.
+//! This trait describes how the component handles NAME, METADATA, READ, WRITE and RESET requests from the motherboard,
+//! as well as the latency of its READ and WRITE requests.
 
 /// Bus of an auxiliary component.
 /// All components must implement this type in order to be connected to the motherboard.
@@ -26,4 +27,10 @@ pub trait Bus {
     /// Handle a RESET signal sent by the motherboard.
     /// All volatile data from the component must be reset.
     fn reset(&mut self);
+
+    /// Get the number of additional cycles each READ or WRITE request to the component takes.
+    /// This value is queried only once, when the component is connected to the motherboard.
+    fn latency(&self) -> u32 {
+        0
+    }
 }
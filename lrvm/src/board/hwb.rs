@@ -23,6 +23,8 @@ pub struct AuxCache {
     pub metadata: [u32; 8],
     /// Auxiliary component's size
     pub size: u32,
+    /// Auxiliary component's latency (additional cycles for each READ or WRITE request)
+    pub latency: u32,
 }
 
 impl AuxWithCache {
@@ -39,6 +41,7 @@ impl AuxWithCache {
         let metadata = bus.metadata();
         let hw_id = ((metadata[0] as u64) << 32) + metadata[1] as u64;
         let size = metadata[2];
+        let latency = bus.latency();
 
         std::mem::drop(bus);
 
@@ -50,6 +53,7 @@ impl AuxWithCache {
                 name,
                 metadata,
                 size,
+                latency,
             },
         }
     }
@@ -110,6 +114,11 @@ impl HardwareBridge {
         self.cache_of(aux_id).map(|cache| cache.size)
     }
 
+    /// Get the latency of an auxiliary component from its ID
+    pub fn latency_of(&self, aux_id: usize) -> Option<u32> {
+        self.cache_of(aux_id).map(|cache| cache.latency)
+    }
+
     /// Send a READ signal to a component.  
     /// If the `ex` reference contains a non-zero value when this function returns, the component raised an exception
     /// with the provided code and data.
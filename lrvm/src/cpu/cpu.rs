@@ -99,7 +99,7 @@ impl Cpu {
         self.halted
     }
 
-    /// Get the number of cycles the CPU run so far
+    /// Get the number of cycles the CPU run so far, including the latency of the accessed components (see [`crate::board::Bus::latency`])
     /// Note that this number goes back to 0 after reaching its maximum (overflow).
     pub fn cycles(&self) -> u128 {
         self.cycles
@@ -739,6 +739,9 @@ impl Cpu {
                 let mut ex = 0;
                 let ret = handler(&mut self.mem, p_addr, &mut ex);
 
+                // Account for the latency of the accessed component
+                self.cycles = self.cycles.wrapping_add(self.mem.last_latency().into());
+
                 if ex != 0 {
                     self.exception(0xA0, Some(ex));
                     Err(())
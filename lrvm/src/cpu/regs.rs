@@ -1,7 +1,7 @@
 use std::mem;
 
 /// CPU registers
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone)]
 pub struct Registers {
     /// Arithmetic registers
     pub a: [u32; 8],
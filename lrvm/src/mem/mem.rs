@@ -7,6 +7,8 @@ pub struct MappedMemory {
     bridge: HardwareBridge,
     /// Components mappings
     mappings: Vec<Mapping>,
+    /// Latency of the component contacted by the last READ or WRITE request
+    last_latency: u32,
 }
 
 impl MappedMemory {
@@ -15,6 +17,7 @@ impl MappedMemory {
         Self {
             bridge: hwb,
             mappings: vec![],
+            last_latency: 0,
         }
     }
 
@@ -101,10 +104,14 @@ impl MappedMemory {
             .iter()
             .find(|mapping| mapping.addr <= addr && addr <= mapping.end_addr())
         {
+            self.last_latency = self.bridge.latency_of(mapping.aux_id).unwrap_or(0);
+
             self.bridge
                 .read(mapping.aux_id, addr - mapping.addr, ex)
                 .unwrap()
         } else {
+            self.last_latency = 0;
+
             if cfg!(debug_assertions) {
                 eprintln!(
                     "Warning: tried to read non-mapped memory at address {:#010X}",
@@ -131,17 +138,34 @@ impl MappedMemory {
             .iter()
             .find(|mapping| mapping.addr <= addr && addr <= mapping.end_addr())
         {
+            self.last_latency = self.bridge.latency_of(mapping.aux_id).unwrap_or(0);
+
             self.bridge
                 .write(mapping.aux_id, addr - mapping.addr, word, ex)
                 .unwrap()
-        } else if cfg!(debug_assertions) {
-            eprintln!(
-                "Warning: tried to write non-mapped memory at address {:#010X}",
-                addr
-            );
+        } else {
+            self.last_latency = 0;
+
+            if cfg!(debug_assertions) {
+                eprintln!(
+                    "Warning: tried to write non-mapped memory at address {:#010X}",
+                    addr
+                );
+            }
         }
     }
 
+    /// Get the latency of the component contacted by the last READ or WRITE request (see [`Bus::latency`]).
+    /// If no component was mapped at the requested address, `0` will be returned.
+    pub fn last_latency(&self) -> u32 {
+        self.last_latency
+    }
+
+    /// Get the hardware bridge used by the memory
+    pub fn bridge(&self) -> &HardwareBridge {
+        &self.bridge
+    }
+
     /// Get the mapping of a given component
     pub fn get_mapping(&self, aux_id: usize) -> Option<&Mapping> {
         self.mappings
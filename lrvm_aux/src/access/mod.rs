@@ -0,0 +1,5 @@
+mod read_only;
+mod write_only;
+
+pub use read_only::ReadOnly;
+pub use write_only::WriteOnly;
@@ -0,0 +1,57 @@
+//! The read-only wrapper exposes any component as a read-only view.
+//! See [`ReadOnly`] for more details.
+
+use lrvm::board::Bus;
+use lrvm_tools::exceptions::AuxHwException;
+
+/// The read-only wrapper forwards read requests to the inner component, while all write requests are rejected
+/// with a hardware exception (`MemoryNotWritable` by default, see [`ReadOnly::with_exception`]).
+///
+/// Reset requests are forwarded to the inner component, and its latency is kept.
+/// The inner component's name and metadata are kept as they are, so its additional data can still be decoded.
+pub struct ReadOnly {
+    inner: Box<dyn Bus>,
+    ex: u16,
+}
+
+impl ReadOnly {
+    /// Wrap a component in a read-only view
+    pub fn wrap(inner: Box<dyn Bus>) -> Self {
+        Self {
+            inner,
+            ex: AuxHwException::MemoryNotWritable.into(),
+        }
+    }
+
+    /// Set the hardware exception raised on write requests
+    pub fn with_exception(mut self, ex: impl Into<u16>) -> Self {
+        self.ex = ex.into();
+        self
+    }
+}
+
+impl Bus for ReadOnly {
+    fn name(&self) -> &'static str {
+        self.inner.name()
+    }
+
+    fn metadata(&self) -> [u32; 8] {
+        self.inner.metadata()
+    }
+
+    fn read(&mut self, addr: u32, ex: &mut u16) -> u32 {
+        self.inner.read(addr, ex)
+    }
+
+    fn write(&mut self, _addr: u32, _word: u32, ex: &mut u16) {
+        *ex = self.ex;
+    }
+
+    fn reset(&mut self) {
+        self.inner.reset()
+    }
+
+    fn latency(&self) -> u32 {
+        self.inner.latency()
+    }
+}
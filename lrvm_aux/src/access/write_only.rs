@@ -0,0 +1,58 @@
+//! The write-only wrapper exposes any component as a write-only view.
+//! See [`WriteOnly`] for more details.
+
+use lrvm::board::Bus;
+use lrvm_tools::exceptions::AuxHwException;
+
+/// The write-only wrapper forwards write requests to the inner component, while all read requests are rejected
+/// with a hardware exception (`MemoryNotReadable` by default, see [`WriteOnly::with_exception`]) and return `0x00000000`.
+///
+/// Reset requests are forwarded to the inner component, and its latency is kept.
+/// The inner component's name and metadata are kept as they are, so its additional data can still be decoded.
+pub struct WriteOnly {
+    inner: Box<dyn Bus>,
+    ex: u16,
+}
+
+impl WriteOnly {
+    /// Wrap a component in a write-only view
+    pub fn wrap(inner: Box<dyn Bus>) -> Self {
+        Self {
+            inner,
+            ex: AuxHwException::MemoryNotReadable.into(),
+        }
+    }
+
+    /// Set the hardware exception raised on read requests
+    pub fn with_exception(mut self, ex: impl Into<u16>) -> Self {
+        self.ex = ex.into();
+        self
+    }
+}
+
+impl Bus for WriteOnly {
+    fn name(&self) -> &'static str {
+        self.inner.name()
+    }
+
+    fn metadata(&self) -> [u32; 8] {
+        self.inner.metadata()
+    }
+
+    fn read(&mut self, _addr: u32, ex: &mut u16) -> u32 {
+        *ex = self.ex;
+        0
+    }
+
+    fn write(&mut self, addr: u32, word: u32, ex: &mut u16) {
+        self.inner.write(addr, word, ex)
+    }
+
+    fn reset(&mut self) {
+        self.inner.reset()
+    }
+
+    fn latency(&self) -> u32 {
+        self.inner.latency()
+    }
+}
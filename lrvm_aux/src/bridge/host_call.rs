@@ -0,0 +1,90 @@
+//! The host call component allows the guest to call functions implemented by the host.
+//! See [`HostCall`] for more details.
+
+use lrvm::board::Bus;
+use lrvm_tools::exceptions::AuxHwException;
+use lrvm_tools::metadata::{DeviceCategory, DeviceMetadata};
+
+/// Host function handler, receiving the function selector and the arguments block, and returning the result word.
+pub type HostCallHandler = Box<dyn FnMut(u32, &mut [u32; 8]) -> u32>;
+
+/// Fallible host function handler, returning either the result word or a hardware exception to raise.
+pub type FallibleHostCallHandler = Box<dyn FnMut(u32, &mut [u32; 8]) -> Result<u32, u16>>;
+
+/// The host call component is a bridge allowing the guest to call functions implemented by the host.
+/// It is made of 10 words:
+///
+/// * Words 0-7: arguments block (readable and writable)
+/// * Word 8: result of the last call (read-only)
+/// * Word 9: trigger (write-only)
+///
+/// Writing a function selector to the trigger calls the handler exactly once with the selector and the arguments block,
+/// which the handler may mutate. The returned word is then put in the result word.
+/// If the handler fails, the provided exception is raised by the write request and the result word is left untouched.
+///
+/// When it receives a RESET request from the motherboard, the arguments block and the result word are zeroed.
+pub struct HostCall {
+    args: [u32; 8],
+    result: u32,
+    handler: FallibleHostCallHandler,
+    hw_id: u64,
+}
+
+impl HostCall {
+    /// Create a host call component with an infaillible handler
+    pub fn new(mut handler: HostCallHandler, hw_id: u64) -> Self {
+        Self::new_fallible(
+            Box::new(move |selector, args| Ok(handler(selector, args))),
+            hw_id,
+        )
+    }
+
+    /// Create a host call component with a handler that may raise hardware exceptions
+    pub fn new_fallible(handler: FallibleHostCallHandler, hw_id: u64) -> Self {
+        Self {
+            args: [0; 8],
+            result: 0,
+            handler,
+            hw_id,
+        }
+    }
+}
+
+impl Bus for HostCall {
+    fn name(&self) -> &'static str {
+        "Host Call"
+    }
+
+    fn metadata(&self) -> [u32; 8] {
+        DeviceMetadata::new(self.hw_id, 40, DeviceCategory::Uncategorized(), None, None).encode()
+    }
+
+    fn read(&mut self, addr: u32, ex: &mut u16) -> u32 {
+        match addr / 4 {
+            0..=7 => self.args[addr as usize / 4],
+            8 => self.result,
+            9 => {
+                *ex = AuxHwException::MemoryNotReadable.into();
+                0
+            }
+            _ => unreachable!(), // Safety guarantee
+        }
+    }
+
+    fn write(&mut self, addr: u32, word: u32, ex: &mut u16) {
+        match addr / 4 {
+            0..=7 => self.args[addr as usize / 4] = word,
+            8 => *ex = AuxHwException::MemoryNotWritable.into(),
+            9 => match (self.handler)(word, &mut self.args) {
+                Ok(result) => self.result = result,
+                Err(err) => *ex = err,
+            },
+            _ => unreachable!(), // Safety guarantee
+        }
+    }
+
+    fn reset(&mut self) {
+        self.args = [0; 8];
+        self.result = 0;
+    }
+}
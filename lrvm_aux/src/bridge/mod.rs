@@ -0,0 +1,3 @@
+mod host_call;
+
+pub use host_call::{FallibleHostCallHandler, HostCall, HostCallHandler};
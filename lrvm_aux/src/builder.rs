@@ -0,0 +1,216 @@
+//! The board builder allows to declare the components of a motherboard in a chained fashion.
+//! See [`BoardBuilder`] for more details.
+
+use crate::display::{BufferedDisplay, DecodedStr};
+use crate::storage::BootRom;
+use crate::volatile_mem::Ram;
+use lrvm::board::{Bus, MotherBoard};
+use lrvm_tools::asm::Program;
+use lrvm_tools::metadata::HwIdAllocator;
+use std::collections::BTreeMap;
+use std::ops::Index;
+
+/// The board builder creates a list of components ready to be plugged into a motherboard, along with an index
+/// mapping friendly names to the components' position in the list.
+///
+/// Hardware identifiers are generated deterministically from a seed (`0` by default, see [`BoardBuilder::auto_hw_ids`]),
+/// so a given board always gets the same identifiers. They are guaranteed to be unique, see [`HwIdAllocator`].
+///
+/// Each component gets a default name (`boot_rom`, `ram`, `display` or the component's generic name for custom devices),
+/// which can be changed using [`BoardBuilder::named`]. Names must be unique.
+///
+/// The components can either be retrieved as a list (see [`BoardBuilder::build`]) or directly plugged into a motherboard
+/// (see [`BoardBuilder::build_motherboard`]).
+pub struct BoardBuilder {
+    components: Vec<(String, Box<dyn Bus>)>,
+    hw_ids: HwIdAllocator,
+    error: Option<&'static str>,
+}
+
+/// List of components built by a [`BoardBuilder`]
+pub type BoardComponents = Vec<Box<dyn Bus>>;
+
+/// Index of a board's components, by name
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ComponentIndex {
+    names: BTreeMap<String, usize>,
+}
+
+impl BoardBuilder {
+    /// Create a board builder with no component
+    pub fn new() -> Self {
+        Self {
+            components: vec![],
+            hw_ids: HwIdAllocator::new(0),
+            error: None,
+        }
+    }
+
+    /// Set the seed used to generate hardware identifiers of the components added afterwards.
+    /// Identifiers of the components added before are kept and are still never handed out again.
+    pub fn auto_hw_ids(mut self, seed: u64) -> Self {
+        self.hw_ids.reseed(seed);
+        self
+    }
+
+    /// Add a BootROM component containing the provided words
+    pub fn boot_rom(self, words: Vec<u32>) -> Self {
+        self.try_add("boot_rom", |hw_id| BootRom::new(words, hw_id))
+    }
+
+    /// Add a BootROM component containing the provided words, with a larger size than its storage
+    pub fn boot_rom_with_size(self, words: Vec<u32>, size: u32) -> Self {
+        self.try_add("boot_rom", |hw_id| BootRom::with_size(words, size, hw_id))
+    }
+
+    /// Add a BootROM component containing the provided program
+    pub fn rom(self, program: &Program) -> Self {
+        self.boot_rom(program.encode_words())
+    }
+
+    /// Add a RAM component
+    pub fn ram(self, size: u32) -> Self {
+        self.try_add("ram", |hw_id| Ram::new(size, hw_id))
+    }
+
+    /// Add a buffered display component
+    pub fn display(self, capacity: u32, handler: Box<dyn FnMut(DecodedStr)>) -> Self {
+        self.try_add("display", |hw_id| {
+            BufferedDisplay::new(capacity, handler, hw_id)
+        })
+    }
+
+    /// Add a custom component (its hardware identifier is left untouched but reserved, so no other component gets it)
+    pub fn device(mut self, device: Box<dyn Bus>) -> Self {
+        let metadata = device.metadata();
+
+        if self
+            .hw_ids
+            .reserve((u64::from(metadata[0]) << 32) | u64::from(metadata[1]))
+            .is_err()
+        {
+            self.fail("Two components cannot have the same hardware identifier");
+        }
+
+        self.components.push((device.name().to_string(), device));
+        self
+    }
+
+    /// Rename the last added component
+    pub fn named(mut self, name: impl Into<String>) -> Self {
+        match self.components.last_mut() {
+            Some((last_name, _)) => *last_name = name.into(),
+            None => self.fail("Cannot name a component before adding one"),
+        }
+
+        self
+    }
+
+    /// Build the list of components and its index
+    /// Returns an error message if a component failed to be created or if two components have the same name.
+    pub fn build(self) -> Result<(BoardComponents, ComponentIndex), &'static str> {
+        if let Some(err) = self.error {
+            return Err(err);
+        }
+
+        let mut names = BTreeMap::new();
+        let mut components = Vec::with_capacity(self.components.len());
+
+        for (i, (name, component)) in self.components.into_iter().enumerate() {
+            if names.insert(name, i).is_some() {
+                return Err("Two components cannot have the same name");
+            }
+
+            components.push(component);
+        }
+
+        Ok((components, ComponentIndex { names }))
+    }
+
+    /// Build a motherboard with all the components mapped contiguously from address `0x00000000`, in the order they were added.
+    /// The motherboard is reset and ready to run.
+    /// Returns an error message if the components could not be built (see [`BoardBuilder::build`]) or mapped.
+    pub fn build_motherboard(self) -> Result<(MotherBoard, ComponentIndex), &'static str> {
+        let (components, index) = self.build()?;
+        let aux_ids: Vec<usize> = (0..components.len()).collect();
+
+        let mut motherboard = MotherBoard::new(components);
+
+        motherboard
+            .map(|mem| mem.map_contiguous(0x0000_0000, &aux_ids).mapping)
+            .map_err(|_| "Failed to map the components contiguously")?;
+
+        motherboard.reset();
+
+        Ok((motherboard, index))
+    }
+
+    /// Add a component created with the next hardware identifier
+    fn try_add<B: Bus + 'static>(
+        mut self,
+        name: &str,
+        create: impl FnOnce(u64) -> Result<B, &'static str>,
+    ) -> Self {
+        let hw_id = self.hw_ids.allocate();
+
+        match create(hw_id) {
+            Ok(component) => self
+                .components
+                .push((name.to_string(), Box::new(component))),
+            Err(err) => self.fail(err),
+        }
+
+        self
+    }
+
+    /// Register an error (only the first one is kept)
+    fn fail(&mut self, err: &'static str) {
+        if self.error.is_none() {
+            self.error = Some(err);
+        }
+    }
+}
+
+impl Default for BoardBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ComponentIndex {
+    /// Get the position of a component from its name
+    pub fn get(&self, name: &str) -> Option<usize> {
+        self.names.get(name).copied()
+    }
+
+    /// Get the positions of several components from their names
+    /// Panics if one of the names is unknown.
+    pub fn ids(&self, names: &[&str]) -> Vec<usize> {
+        names.iter().map(|name| self[*name]).collect()
+    }
+
+    /// Iterate over the components' names and positions (sorted by name)
+    pub fn iter(&self) -> impl Iterator<Item = (&str, usize)> {
+        self.names.iter().map(|(name, id)| (name.as_str(), *id))
+    }
+
+    /// Get the number of components
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    /// Check if there is no component
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+}
+
+impl Index<&str> for ComponentIndex {
+    type Output = usize;
+
+    fn index(&self, name: &str) -> &usize {
+        self.names
+            .get(name)
+            .unwrap_or_else(|| panic!("Unknown component name: {}", name))
+    }
+}
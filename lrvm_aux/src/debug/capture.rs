@@ -0,0 +1,98 @@
+//! Headless runner capturing the output of a buffered display.
+//! See [`run_and_capture`] for more details.
+
+use crate::display::BufferedDisplay;
+use crate::storage::BootRom;
+use crate::volatile_mem::Ram;
+use lrvm::board::{Bus, MotherBoard};
+use lrvm::cpu::Registers;
+use lrvm_tools::debug::{run_vm, RunConfig, StoppedState};
+use lrvm_tools::metadata::HwIdAllocator;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// Capacity of the buffered display used by [`run_and_capture`], in bytes
+pub const CAPTURE_DISPLAY_CAPACITY: u32 = 0x100;
+
+/// Result of a program run by [`run_and_capture`]
+#[derive(Debug)]
+pub struct CaptureResult {
+    /// Messages flushed by the display, in order
+    pub lines: Vec<String>,
+    /// CPU registers when the VM stopped
+    pub regs: Registers,
+    /// Reason the VM stopped for
+    pub state: StoppedState,
+}
+
+/// Run a program without displaying anything and capture the output of a buffered display.
+///
+/// The following components are mapped contiguously from address `0x00000000`:
+///
+/// * A BootROM containing the program (with the exact size of the program)
+/// * A RAM of `ram_size` bytes
+/// * A buffered display of [`CAPTURE_DISPLAY_CAPACITY`] bytes
+///
+/// So the display is located at address `program_words.len() * 4 + ram_size`.
+/// The components' hardware identifiers are allocated through the provided allocator, so they don't collide with the caller's ones.
+///
+/// Each message flushed by the display becomes a line, with its trailing null bytes removed.
+/// Invalid UTF-8 messages are converted to lossy strings.
+///
+/// Returns an error message if a component could not be created or mapped.
+pub fn run_and_capture(
+    program_words: Vec<u32>,
+    ram_size: u32,
+    config: RunConfig,
+    hw_ids: &mut HwIdAllocator,
+) -> Result<CaptureResult, &'static str> {
+    let lines = Rc::new(RefCell::new(vec![]));
+    let lines_sink = Rc::clone(&lines);
+
+    let components: Vec<Box<dyn Bus>> = vec![
+        Box::new(BootRom::new(program_words, hw_ids.allocate())?),
+        Box::new(Ram::new(ram_size, hw_ids.allocate())?),
+        Box::new(BufferedDisplay::new(
+            CAPTURE_DISPLAY_CAPACITY,
+            Box::new(move |message| {
+                let line = match message {
+                    Ok(message) => message.to_string(),
+                    Err((_, bytes)) => String::from_utf8_lossy(bytes).into_owned(),
+                };
+
+                lines_sink
+                    .borrow_mut()
+                    .push(line.trim_end_matches(char::from(0)).to_string());
+            }),
+            hw_ids.allocate(),
+        )?),
+    ];
+
+    let mut motherboard = MotherBoard::new(components);
+    let mut mapped = true;
+
+    motherboard.map(|mem| {
+        mapped = mem
+            .map_contiguous(0x0000_0000, vec![0, 1, 2])
+            .mapping
+            .is_ok();
+    });
+
+    if !mapped {
+        return Err("Failed to map the components");
+    }
+
+    motherboard.reset();
+
+    let cpu = motherboard.cpu();
+    let state = run_vm(cpu, config);
+    let regs = cpu.regs.clone();
+
+    drop(motherboard);
+
+    let lines = Rc::try_unwrap(lines)
+        .expect("Display's handler was not dropped")
+        .into_inner();
+
+    Ok(CaptureResult { lines, regs, state })
+}
@@ -0,0 +1,65 @@
+//! The delayed bus wraps any auxiliary component to simulate a slow peripheral.
+//! See [`DelayedBus`] for more details.
+
+use lrvm::board::Bus;
+
+/// The delayed bus is a transparent wrapper around another component, which adds a fixed number of cycles to each
+/// read and write request made to it (see [`Bus::latency`]).
+///
+/// The latency is only accounted for in the CPU's cycles count: no real time is spent waiting.
+/// The inner component's name and metadata are kept, and reset requests are forwarded to it.
+pub struct DelayedBus<B: Bus> {
+    inner: B,
+    cycles_per_access: u32,
+}
+
+impl<B: Bus> DelayedBus<B> {
+    /// Wrap a component, adding the provided number of cycles to each access
+    pub fn wrap(inner: B, cycles_per_access: u32) -> Self {
+        Self {
+            inner,
+            cycles_per_access,
+        }
+    }
+
+    /// Get the inner component
+    pub fn inner(&self) -> &B {
+        &self.inner
+    }
+
+    /// Get the inner component, mutably
+    pub fn inner_mut(&mut self) -> &mut B {
+        &mut self.inner
+    }
+
+    /// Unwrap the inner component
+    pub fn into_inner(self) -> B {
+        self.inner
+    }
+}
+
+impl<B: Bus> Bus for DelayedBus<B> {
+    fn name(&self) -> &'static str {
+        self.inner.name()
+    }
+
+    fn metadata(&self) -> [u32; 8] {
+        self.inner.metadata()
+    }
+
+    fn read(&mut self, addr: u32, ex: &mut u16) -> u32 {
+        self.inner.read(addr, ex)
+    }
+
+    fn write(&mut self, addr: u32, word: u32, ex: &mut u16) {
+        self.inner.write(addr, word, ex)
+    }
+
+    fn reset(&mut self) {
+        self.inner.reset()
+    }
+
+    fn latency(&self) -> u32 {
+        self.inner.latency().saturating_add(self.cycles_per_access)
+    }
+}
@@ -0,0 +1,152 @@
+//! The bus logger wraps any auxiliary component to trace all accesses made to it.
+//! See [`BusLogger`] for more details.
+
+use lrvm::board::Bus;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+/// The bus logger is a transparent wrapper around another component.  
+/// Every read and write request is forwarded to the inner component, then reported to a sink as a [`BusAccess`].
+///
+/// The component's name and metadata are passed through unchanged, unless a custom name is provided with [`BusLogger::with_name`].
+/// Reset requests are forwarded to the inner component but are not logged, and the inner component's latency is kept.
+pub struct BusLogger {
+    inner: Box<dyn Bus>,
+    sink: Box<dyn FnMut(BusAccess)>,
+    seq: u64,
+    name: Option<&'static str>,
+}
+
+/// A single access made to a logged component
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BusAccess {
+    /// Sequence number (starts at 0 and increases with each access)
+    pub seq: u64,
+    /// Access type
+    pub kind: BusAccessKind,
+    /// Address, relative to the component's mapping
+    pub addr: u32,
+    /// Value read or written
+    pub value: u32,
+    /// Hardware exception after the access (0 if none occurred)
+    pub ex: u16,
+}
+
+/// Type of a bus access
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BusAccessKind {
+    Read,
+    Write,
+}
+
+impl BusLogger {
+    /// Wrap a component, reporting all accesses to the provided sink
+    pub fn wrap(inner: Box<dyn Bus>, sink: impl FnMut(BusAccess) + 'static) -> Self {
+        Self {
+            inner,
+            sink: Box::new(sink),
+            seq: 0,
+            name: None,
+        }
+    }
+
+    /// Wrap a component, recording all accesses into a shared log
+    pub fn recording(inner: Box<dyn Bus>) -> (Self, Arc<Mutex<Vec<BusAccess>>>) {
+        let log = Arc::new(Mutex::new(vec![]));
+        let sink_log = Arc::clone(&log);
+
+        let logger = Self::wrap(inner, move |access| {
+            if let Ok(mut log) = sink_log.lock() {
+                log.push(access);
+            }
+        });
+
+        (logger, log)
+    }
+
+    /// Override the name reported by the wrapped component
+    pub fn with_name(mut self, name: &'static str) -> Self {
+        self.name = Some(name);
+        self
+    }
+
+    /// Report an access to the sink
+    fn log(&mut self, kind: BusAccessKind, addr: u32, value: u32, ex: u16) {
+        let seq = self.seq;
+        self.seq += 1;
+
+        (self.sink)(BusAccess {
+            seq,
+            kind,
+            addr,
+            value,
+            ex,
+        });
+    }
+}
+
+impl Bus for BusLogger {
+    fn name(&self) -> &'static str {
+        self.name.unwrap_or_else(|| self.inner.name())
+    }
+
+    fn metadata(&self) -> [u32; 8] {
+        self.inner.metadata()
+    }
+
+    fn read(&mut self, addr: u32, ex: &mut u16) -> u32 {
+        let value = self.inner.read(addr, ex);
+        self.log(BusAccessKind::Read, addr, value, *ex);
+        value
+    }
+
+    fn write(&mut self, addr: u32, word: u32, ex: &mut u16) {
+        self.inner.write(addr, word, ex);
+        self.log(BusAccessKind::Write, addr, word, *ex);
+    }
+
+    fn reset(&mut self) {
+        self.inner.reset()
+    }
+
+    fn latency(&self) -> u32 {
+        self.inner.latency()
+    }
+}
+
+impl BusAccess {
+    /// Get the CSV header matching [`BusAccess::to_csv_line`]
+    pub fn csv_header() -> &'static str {
+        "seq,kind,addr,value,ex"
+    }
+
+    /// Convert the access to a CSV line (without trailing newline)
+    pub fn to_csv_line(&self) -> String {
+        format!(
+            "{},{},{:#010X},{:#010X},{:#06X}",
+            self.seq, self.kind, self.addr, self.value, self.ex
+        )
+    }
+}
+
+impl fmt::Display for BusAccessKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Read => write!(f, "read"),
+            Self::Write => write!(f, "write"),
+        }
+    }
+}
+
+/// Convert a list of accesses to a CSV document, header included
+pub fn accesses_to_csv<'a>(accesses: impl IntoIterator<Item = &'a BusAccess>) -> String {
+    let mut csv = String::from(BusAccess::csv_header());
+    csv.push('\n');
+
+    for access in accesses {
+        csv.push_str(&access.to_csv_line());
+        csv.push('\n');
+    }
+
+    csv
+}
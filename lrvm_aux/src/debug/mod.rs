@@ -1,3 +1,9 @@
 mod basic;
+mod capture;
+mod delayed;
+mod logger;
 
 pub use basic::{BasicDebug, DebugInfo};
+pub use capture::{run_and_capture, CaptureResult, CAPTURE_DISPLAY_CAPACITY};
+pub use delayed::DelayedBus;
+pub use logger::{accesses_to_csv, BusAccess, BusAccessKind, BusLogger};
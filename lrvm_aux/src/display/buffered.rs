@@ -59,6 +59,12 @@ impl BufferedDisplay {
         })
     }
 
+    /// Set the display's hardware identifier
+    pub fn with_hw_id(mut self, hw_id: u64) -> Self {
+        self.hw_id = hw_id;
+        self
+    }
+
     /// Create a print!-based buffered display component, converting invalid UTF-8 strings to lossy ones
     pub fn new_print_lossy(capacity: u32, hw_id: u64) -> Result<Self, &'static str> {
         Self::new(
@@ -0,0 +1,123 @@
+//! The color display component offers a simple pixel-based display system.
+//! See [`ColorDisplay`] for more details.
+
+use lrvm::board::Bus;
+use lrvm_tools::exceptions::AuxHwException;
+use lrvm_tools::metadata::{DeviceMetadata, DisplayType};
+
+/// Handler called with the framebuffer's content, the display's width and its height
+pub type FrameHandler = Box<dyn FnMut(&[u32], u16, u16)>;
+
+/// The color display works with a framebuffer and a handler. Each word of the framebuffer is a pixel, encoded as `0x00RRGGBB`,
+/// stored row after row starting from the top-left corner. The word following the framebuffer is interpreted as:
+///
+/// * `0xAA`: render the framebuffer's content
+/// * `0xFF`: clear the framebuffer's content (all pixels become black)
+///
+/// The framebuffer can be read back. When a render request is received, the handler is called with the framebuffer's content
+/// as well as the display's width and height. The display's dimensions are advertised through its metadata
+/// (see [`DeviceMetadata::with_dimensions`]).
+pub struct ColorDisplay {
+    framebuffer: Vec<u32>,
+    width: u16,
+    height: u16,
+    handler: FrameHandler,
+    hw_id: u64,
+}
+
+impl ColorDisplay {
+    /// Create a color display component.
+    /// Returns an error message if one of the dimensions is 0 or if the framebuffer would not fit in the address space.
+    pub fn new(
+        width: u16,
+        height: u16,
+        handler: FrameHandler,
+        hw_id: u64,
+    ) -> Result<Self, &'static str> {
+        if width == 0 || height == 0 {
+            return Err("Color display's dimensions cannot be 0");
+        }
+
+        (width as u32)
+            .checked_mul(height as u32)
+            .and_then(|pixels| pixels.checked_add(1))
+            .and_then(|words| words.checked_mul(4))
+            .ok_or("Color display's framebuffer is too large for the address space")?;
+
+        Ok(Self {
+            framebuffer: vec![0; width as usize * height as usize],
+            width,
+            height,
+            handler,
+            hw_id,
+        })
+    }
+
+    /// Set the display's hardware identifier
+    pub fn with_hw_id(mut self, hw_id: u64) -> Self {
+        self.hw_id = hw_id;
+        self
+    }
+
+    /// Get the display's width, in pixels
+    pub fn width(&self) -> u16 {
+        self.width
+    }
+
+    /// Get the display's height, in pixels
+    pub fn height(&self) -> u16 {
+        self.height
+    }
+
+    /// Get the framebuffer's content
+    pub fn framebuffer(&self) -> &[u32] {
+        &self.framebuffer
+    }
+}
+
+impl Bus for ColorDisplay {
+    fn name(&self) -> &'static str {
+        "Color Display"
+    }
+
+    fn metadata(&self) -> [u32; 8] {
+        DeviceMetadata::new(
+            self.hw_id,
+            self.framebuffer.len() as u32 * 4 + 4,
+            DisplayType::Color.into(),
+            None,
+            None,
+        )
+        .with_dimensions(self.width, self.height)
+        .encode()
+    }
+
+    fn read(&mut self, addr: u32, ex: &mut u16) -> u32 {
+        match self.framebuffer.get((addr / 4) as usize) {
+            Some(pixel) => *pixel,
+            None => {
+                *ex = AuxHwException::MemoryNotReadable.into();
+                0
+            }
+        }
+    }
+
+    fn write(&mut self, addr: u32, word: u32, ex: &mut u16) {
+        let addr = (addr / 4) as usize;
+
+        if addr < self.framebuffer.len() {
+            self.framebuffer[addr] = word;
+            return;
+        }
+
+        match word {
+            0xAA => (self.handler)(&self.framebuffer, self.width, self.height),
+            0xFF => self.reset(),
+            code => *ex = AuxHwException::UnknownOperation(code as u8).into(),
+        }
+    }
+
+    fn reset(&mut self) {
+        self.framebuffer = vec![0; self.framebuffer.len()];
+    }
+}
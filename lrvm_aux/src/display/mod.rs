@@ -1,7 +1,9 @@
 mod buffered;
 mod character;
+mod color;
 mod number;
 
-pub use buffered::BufferedDisplay;
+pub use buffered::{BufferedDisplay, DecodedStr};
 pub use character::CharDisplay;
+pub use color::{ColorDisplay, FrameHandler};
 pub use number::{NumberDisplay, NumberDisplayFormat};
@@ -0,0 +1,80 @@
+//! The asynchronous keyboard component offers a non-blocking UTF-8 input system.
+//! See [`AsyncKeyboard`] for more details.
+
+use lrvm::board::Bus;
+use lrvm_tools::exceptions::AuxHwException;
+use lrvm_tools::metadata::{DeviceMetadata, KeyboardType};
+use std::collections::VecDeque;
+use std::sync::mpsc::Receiver;
+
+/// The keyboard receives inputs from a channel, without ever blocking the VM. Inputs are queued character by character.
+/// It is made of two words:
+///
+/// * Word 0 (status register): number of characters waiting in the queue (`0` if no input is available).
+///   Writing `0xFF` to it clears the queue, other codes are invalid.
+/// * Word 1 (data register): reading it pops the next character from the queue, or returns `0` if the queue is empty.
+///   Writing to it is forbidden.
+///
+/// Each read from either register first polls the channel (without blocking) to queue all pending inputs.
+pub struct AsyncKeyboard {
+    receiver: Receiver<String>,
+    queue: VecDeque<char>,
+    hw_id: u64,
+}
+
+impl AsyncKeyboard {
+    /// Create an asynchronous keyboard component receiving its inputs from the provided channel.
+    pub fn new(receiver: Receiver<String>, hw_id: u64) -> Self {
+        Self {
+            receiver,
+            queue: VecDeque::new(),
+            hw_id,
+        }
+    }
+
+    /// Queue all inputs waiting in the channel
+    fn poll(&mut self) {
+        while let Ok(input) = self.receiver.try_recv() {
+            self.queue.extend(input.chars());
+        }
+    }
+}
+
+impl Bus for AsyncKeyboard {
+    fn name(&self) -> &'static str {
+        "Asynchronous Keyboard"
+    }
+
+    fn metadata(&self) -> [u32; 8] {
+        DeviceMetadata::new(self.hw_id, 8, KeyboardType::Asynchronous.into(), None, None).encode()
+    }
+
+    fn read(&mut self, addr: u32, _ex: &mut u16) -> u32 {
+        self.poll();
+
+        if addr == 0 {
+            self.queue.len() as u32
+        } else if addr == 4 {
+            self.queue.pop_front().map(u32::from).unwrap_or(0)
+        } else {
+            unreachable!() // Safety guarantee
+        }
+    }
+
+    fn write(&mut self, addr: u32, word: u32, ex: &mut u16) {
+        if addr == 0 {
+            match word {
+                0xFF => self.queue.clear(),
+                code => *ex = AuxHwException::UnknownOperation(code as u8).into(),
+            }
+        } else if addr == 4 {
+            *ex = AuxHwException::MemoryNotWritable.into();
+        } else {
+            unreachable!() // Safety guarantee
+        }
+    }
+
+    fn reset(&mut self) {
+        self.queue.clear();
+    }
+}
@@ -1,5 +1,7 @@
+mod asynchronous;
 mod sync_char;
 mod sync_line;
 
+pub use asynchronous::AsyncKeyboard;
 pub use sync_char::SyncCharKeyboard;
 pub use sync_line::SyncLineKeyboard;
@@ -7,6 +7,10 @@
 // Re-export the LRVM crate
 pub use lrvm;
 
+mod builder;
+
+pub mod access;
+pub mod bridge;
 pub mod debug;
 pub mod display;
 pub mod keyboard;
@@ -14,5 +18,7 @@ pub mod storage;
 pub mod time;
 pub mod volatile_mem;
 
+pub use builder::{BoardBuilder, BoardComponents, ComponentIndex};
+
 #[cfg(test)]
 mod tests;
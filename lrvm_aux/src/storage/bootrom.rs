@@ -2,6 +2,7 @@
 //! See [`BootROM`] for more details.
 
 use lrvm::board::Bus;
+use lrvm_tools::asm::Program;
 use lrvm_tools::exceptions::AuxHwException;
 use lrvm_tools::metadata::{DeviceMetadata, StorageType};
 use std::convert::TryInto;
@@ -61,6 +62,21 @@ impl BootRom {
         })
     }
 
+    /// Create a new BootROM component of the provided size (in bytes) containing a program
+    /// Returns an error message if the program doesn't fit in the BootROM, or if the size is invalid (see [`BootRom::with_size`])
+    pub fn with_program(prog: &Program, size: u32, hw_id: u64) -> Result<Self, &'static str> {
+        prog.check_size(size)
+            .map_err(|_| "Program cannot be larger than the BootROM's size")?;
+
+        Self::with_size(prog.encode_words(), size, hw_id)
+    }
+
+    /// Set the BootROM's hardware identifier
+    pub fn with_hw_id(mut self, hw_id: u64) -> Self {
+        self.hw_id = hw_id;
+        self
+    }
+
     /// Get the BootROM's real storage's length
     pub fn len(&self) -> u32 {
         self.len
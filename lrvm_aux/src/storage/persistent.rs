@@ -2,9 +2,11 @@
 //! See [`PersistentMem`] for more details.
 
 use lrvm::board::Bus;
+use lrvm_tools::bytes::{bytes_be_to_u32, u32_to_bytes_be};
 use lrvm_tools::exceptions::AuxHwException;
 use lrvm_tools::metadata::{DeviceMetadata, StorageType};
 use std::cmp::Ordering;
+use std::collections::BTreeSet;
 use std::convert::TryInto;
 use std::fs::{File, OpenOptions};
 use std::io::{Read, Result as IOResult, Seek, SeekFrom, Write};
@@ -12,11 +14,14 @@ use std::path::Path;
 
 /// The persistent memory component contains a read-only or writable, persistent storage that does not reset with the motherboard.
 /// It uses a real file to store its data and is perfect for storing data that persists after the VM is destroyed.
+///
+/// To test how programs deal with faulty storages, words can be marked as bad: reading or writing them always raises an exception.
 pub struct PersistentMem {
     handler: File,
     size: u32,
     real_size: u32,
     writable: bool,
+    bad: BTreeSet<u32>,
     hw_id: u64,
 }
 
@@ -46,6 +51,7 @@ impl PersistentMem {
             real_size,
             handler,
             writable,
+            bad: BTreeSet::new(),
             hw_id,
         })
     }
@@ -79,6 +85,21 @@ impl PersistentMem {
         mem.size = size;
         Ok(mem)
     }
+
+    /// Mark the word at the provided address as bad, so reading or writing it raises a physical read or write exception
+    pub fn mark_bad(&mut self, addr: u32) {
+        self.bad.insert(addr / 4 * 4);
+    }
+
+    /// Mark the word at the provided address as working again
+    pub fn clear_bad(&mut self, addr: u32) {
+        self.bad.remove(&(addr / 4 * 4));
+    }
+
+    /// Check if the word at the provided address is marked as bad
+    pub fn is_bad(&self, addr: u32) -> bool {
+        self.bad.contains(&(addr / 4 * 4))
+    }
 }
 
 impl Bus for PersistentMem {
@@ -98,6 +119,11 @@ impl Bus for PersistentMem {
     }
 
     fn read(&mut self, addr: u32, ex: &mut u16) -> u32 {
+        if self.is_bad(addr) {
+            *ex = AuxHwException::GenericPhysicalReadError.into();
+            return 0;
+        }
+
         if addr >= self.real_size {
             return 0;
         }
@@ -114,15 +140,17 @@ impl Bus for PersistentMem {
             return 0;
         }
 
-        u32::from_be_bytes(buffer)
+        bytes_be_to_u32(buffer)
     }
 
     fn write(&mut self, addr: u32, word: u32, ex: &mut u16) {
-        if !self.writable {
+        if self.is_bad(addr) {
+            *ex = AuxHwException::GenericPhysicalWriteError.into();
+        } else if !self.writable {
             *ex = AuxHwException::MemoryNotWritable.into();
         } else if addr < self.real_size {
             self.handler.seek(SeekFrom::Start(addr.into())).unwrap();
-            self.handler.write_all(&word.to_be_bytes()).unwrap();
+            self.handler.write_all(&u32_to_bytes_be(word)).unwrap();
         }
     }
 
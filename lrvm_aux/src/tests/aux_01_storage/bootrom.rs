@@ -52,3 +52,57 @@ fn bootrom_write() {
         Err(_) => panic!("Unknown exception occurred while writing BootROM: {:?}", ex),
     }
 }
+
+#[test]
+fn bootrom_entry_jump() {
+    let mut prog = Program::from_instr(vec![
+        Instr::Cpy(Reg::a0, 0xDEAD_u16.into()),
+        Instr::Halt(),
+        Instr::Cpy(Reg::a0, 0x1234_u16.into()),
+        Instr::Cpy(Reg::pc, 0x14_u16.into()),
+        Instr::Halt(),
+        Instr::Cpy(Reg::a1, 0x5678_u16.into()),
+        Instr::Halt(),
+    ]);
+
+    prog.with_entry_jump(2);
+
+    let mut vm = prepare_vm(vec![Box::new(
+        BootRom::with_size(prog.encode_words(), 0x1000, 0x0).unwrap(),
+    )]);
+
+    let cpu = &mut vm.cpu();
+
+    run_vm(cpu, RunConfig::halt_on_ex());
+
+    assert_eq!(
+        cpu.regs.a[0], 0x1234,
+        "Registry a0 was expected to contain 0x00001234, contains {:#010X} instead",
+        cpu.regs.a[0]
+    );
+    assert_eq!(
+        cpu.regs.a[1], 0x5678,
+        "Registry a1 was expected to contain 0x00005678, contains {:#010X} instead",
+        cpu.regs.a[1]
+    );
+}
+
+#[test]
+fn bootrom_with_program() {
+    let prog = Program::from_instr(vec![Instr::Cpy(Reg::a0, 0xABCD_u16.into()), Instr::Halt()]);
+
+    assert!(BootRom::with_program(&prog, 4, 0x0).is_err());
+
+    let mut vm = prepare_vm(vec![Box::new(
+        BootRom::with_program(&prog, 8, 0x0).unwrap(),
+    )]);
+    let cpu = &mut vm.cpu();
+
+    run_vm(cpu, RunConfig::halt_on_ex());
+
+    assert_eq!(
+        cpu.regs.a[0], 0xABCD,
+        "Registry a0 was expected to contain 0x0000ABCD, contains {:#010X} instead",
+        cpu.regs.a[0]
+    );
+}
@@ -1,2 +1,3 @@
 pub mod bootrom;
 pub mod flash;
+pub mod persistent;
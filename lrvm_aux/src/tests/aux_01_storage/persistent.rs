@@ -0,0 +1,35 @@
+use crate::storage::PersistentMem;
+use lrvm::board::Bus;
+use lrvm_tools::exceptions::AuxHwException;
+use std::fs;
+
+#[test]
+fn persistent_mem_bad_words() {
+    let path = std::env::temp_dir().join(format!("lrvm_bad_words_{}.bin", std::process::id()));
+    fs::write(&path, [0; 16]).unwrap();
+
+    let mut mem = PersistentMem::writable(&path, 0x1).unwrap();
+    mem.mark_bad(0x4);
+    assert!(mem.is_bad(0x4));
+
+    let mut ex = 0;
+    mem.write(0x4, 0x01234567, &mut ex);
+    assert_eq!(ex, AuxHwException::GenericPhysicalWriteError.encode());
+
+    let mut ex = 0;
+    assert_eq!(mem.read(0x4, &mut ex), 0);
+    assert_eq!(ex, AuxHwException::GenericPhysicalReadError.encode());
+
+    // Adjacent words still work
+    let mut ex = 0;
+    mem.write(0x8, 0x89ABCDEF, &mut ex);
+    assert_eq!(mem.read(0x8, &mut ex), 0x89ABCDEF);
+    assert_eq!(ex, 0);
+
+    mem.clear_bad(0x4);
+    mem.write(0x4, 0x01234567, &mut ex);
+    assert_eq!(mem.read(0x4, &mut ex), 0x01234567);
+    assert_eq!(ex, 0);
+
+    fs::remove_file(&path).unwrap();
+}
@@ -1 +1,2 @@
 pub mod ram;
+pub mod shared;
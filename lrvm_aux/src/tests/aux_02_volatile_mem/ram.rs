@@ -1,6 +1,7 @@
 use crate::storage::BootRom;
 use crate::volatile_mem::Ram;
-use lrvm_tools::asm::{ExtInstr, Instr, Program};
+use lrvm::board::Bus;
+use lrvm_tools::asm::{ExtInstr, Instr, Program, Reg};
 use lrvm_tools::debug::{exec_vm, RunConfig};
 
 #[test]
@@ -51,3 +52,58 @@ fn ram() {
     assert_eq!(word_b, 0x89ABCDEF, "Expected word at address 0x00001008 to contain 0x89ABCDEF but it actually contains {:#010X}", word_b);
     assert_eq!(word_c, 0x00000000, "Expected word at address 0x00001010 to contain 0x01234567 but it actually contains {:#010X}", word_c);
 }
+
+#[test]
+fn ram_copy_reg() {
+    let mut program = Program::from_instr(ExtInstr::SetReg(Reg::a2, 0x01234567).to_instr());
+    program.append_all(ExtInstr::CopyRegToMem(Reg::a2, 0x1004).to_prog_words());
+    program.append_all(ExtInstr::CopyMemToReg(Reg::a1, 0x1004).to_prog_words());
+    program.append(Instr::Halt().into());
+
+    let (mut vm, state) = exec_vm(
+        vec![
+            Box::new(BootRom::with_size(program.encode_words(), 0x1000, 0x0).unwrap()),
+            Box::new(Ram::new(0x1000, 0x1).unwrap()),
+        ],
+        RunConfig::halt_on_ex(),
+    );
+
+    if state.ex.is_some() {
+        panic!("Unexpected exception occurred while running the VM!");
+    }
+
+    let mut err = 0;
+    let word = vm.map(|mem| mem.read(0x1004, &mut err));
+
+    assert_eq!(err, 0);
+    assert_eq!(word, 0x01234567);
+    assert_eq!(vm.cpu().regs.a[1], 0x01234567);
+}
+
+#[test]
+fn ram_with_hw_id() {
+    let ram = Ram::new(0x100, 0x0)
+        .unwrap()
+        .with_hw_id(0x0123_4567_89AB_CDEF);
+    assert_eq!(ram.metadata()[0..2], [0x0123_4567, 0x89AB_CDEF]);
+
+    let rom = BootRom::new(vec![0], 0x0).unwrap().with_hw_id(0x2);
+    assert_eq!(rom.metadata()[0..2], [0x0, 0x2]);
+}
+
+#[test]
+fn ram_search_words() {
+    let mut ram = Ram::from_with_size(vec![0x10, 0xAB, 0x20, 0xAB], 0x100, 0x1).unwrap();
+
+    assert_eq!(ram.search_word(0xAB), Some(0x4));
+    assert_eq!(ram.search_all_words(0xAB), vec![0x4, 0xC]);
+    assert_eq!(ram.search_word(0x30), None);
+    assert!(ram.search_all_words(0x30).is_empty());
+
+    let mut ex = 0;
+    ram.write(0xFC, 0x30, &mut ex);
+    assert_eq!(ram.search_word(0x30), Some(0xFC));
+
+    // Padding words are searched too
+    assert_eq!(ram.search_word(0), Some(0x10));
+}
@@ -0,0 +1,116 @@
+use crate::storage::BootRom;
+use crate::volatile_mem::{Ram, SharedMem};
+use lrvm_tools::asm::{cst, ExtInstr, Instr, Program, Reg};
+use lrvm_tools::debug::{exec_vm, RunConfig};
+use std::sync::mpsc;
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+#[test]
+fn shared_mem() {
+    // Shared buffer layout: word 0 is the "ready" flag, words 1 to 4 are the data, word 5 is written before the VM is prepared
+    let buffer = Arc::new(Mutex::new(vec![0; 8]));
+    buffer.lock().unwrap()[5] = 0xDEAD_BEEF;
+
+    let mut program = Program::from(ExtInstr::SetReg(Reg::a0, 0x1000).to_prog_words());
+    program.append_all(ExtInstr::SetReg(Reg::a2, 0x1020).to_prog_words());
+
+    // Poll the ready flag
+    for instr in &[
+        Instr::Lsa(Reg::a1, Reg::a0.into(), 0u8.into()),
+        Instr::Cmp(Reg::a1, 0u16.into()),
+        Instr::If(cst::ZF.into()),
+        Instr::Jpr((-12i16).into()),
+    ] {
+        program.append((*instr).into());
+    }
+
+    // Copy the data to RAM
+    for i in 0..4u8 {
+        program.append(Instr::Lsa(Reg::a1, Reg::a0.into(), ((i + 1) * 4).into()).into());
+        program.append(Instr::Wsa(Reg::a2.into(), (i * 4).into(), Reg::a1.into()).into());
+    }
+
+    program.append(Instr::Halt().into());
+
+    let host_buffer = Arc::clone(&buffer);
+
+    // The producer starts writing once the VM is prepared, so the data is only available while the program polls it
+    let (ready_tx, ready_rx) = mpsc::channel();
+
+    let producer = thread::spawn(move || {
+        ready_rx.recv().unwrap();
+
+        for i in 1..=4 {
+            host_buffer.lock().unwrap()[i] = 0x1111_1111 * i as u32;
+        }
+
+        host_buffer.lock().unwrap()[0] = 1;
+    });
+
+    let (mut vm, state) = exec_vm(
+        vec![
+            Box::new(BootRom::with_size(program.encode_words(), 0x1000, 0x0).unwrap()),
+            Box::new(SharedMem::new(Arc::clone(&buffer), 0x1, false).unwrap()),
+            Box::new(Ram::new(0x1000, 0x2).unwrap()),
+        ],
+        RunConfig::halt_on_ex().on_reset(move |_| ready_tx.send(()).unwrap()),
+    );
+
+    producer.join().unwrap();
+
+    if state.ex.is_some() {
+        panic!("Unexpected exception occurred while running the VM!");
+    }
+
+    for i in 0..4 {
+        let mut ex = 0;
+        let word = vm.map(|mem| mem.read(0x1020 + i * 4, &mut ex));
+
+        assert_eq!(
+            ex, 0,
+            "Hardware exception occurred while reading RAM word {}: {:#006X}",
+            i, ex
+        );
+        assert_eq!(
+            word,
+            0x1111_1111 * (i + 1),
+            "Invalid word copied from the shared buffer at index {}",
+            i
+        );
+    }
+
+    // Host-side view must be left untouched by the reset performed during preparation
+    assert_eq!(buffer.lock().unwrap()[0], 1);
+    assert_eq!(buffer.lock().unwrap()[5], 0xDEAD_BEEF);
+}
+
+#[test]
+fn shared_mem_mirror() {
+    let alias_a = SharedMem::new(Arc::new(Mutex::new(vec![0; 4])), 0x1, true).unwrap();
+    let alias_b = alias_a.mirror(0x2);
+
+    // Write through alias A (0x1000) and read back through alias B (0x1010)
+    let mut program = Program::from_instr(ExtInstr::WriteAddrLit(0x1004, 0x01234567).to_instr());
+    program.append_all(ExtInstr::ReadAddr(0x1014).to_prog_words());
+    program.append(Instr::Halt().into());
+
+    let (mut vm, state) = exec_vm(
+        vec![
+            Box::new(BootRom::with_size(program.encode_words(), 0x1000, 0x0).unwrap()),
+            Box::new(alias_a),
+            Box::new(alias_b),
+        ],
+        RunConfig::halt_on_ex(),
+    );
+
+    if state.ex.is_some() {
+        panic!("Unexpected exception occurred while running the VM!");
+    }
+
+    assert_eq!(
+        vm.cpu().regs.avr,
+        0x01234567,
+        "Word written through the first alias was not visible through the second one"
+    );
+}
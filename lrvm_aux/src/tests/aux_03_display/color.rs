@@ -0,0 +1,66 @@
+use crate::display::ColorDisplay;
+use crate::storage::BootRom;
+use lrvm::board::Bus;
+use lrvm_tools::asm::{ExtInstr, Instr, Program};
+use lrvm_tools::debug::{exec_vm, RunConfig};
+use lrvm_tools::metadata::{DeviceCategory, DeviceMetadata, DisplayType};
+use std::sync::{Arc, Mutex};
+
+#[test]
+fn color_display() {
+    let mut instr = ExtInstr::WriteAddrLit(0x1000, 0x00FF_0000).to_instr();
+    instr.extend_from_slice(&ExtInstr::WriteAddrLit(0x1000 + 5 * 4, 0x0000_FF00).to_instr());
+    instr.extend_from_slice(&ExtInstr::WriteAddrLit(0x1000 + 3 * 2 * 4, 0xAA).to_instr());
+    instr.push(Instr::Halt());
+
+    let prog = Program::from_instr(instr);
+
+    let rendered = Arc::new(Mutex::new(None));
+    let rendered_closure = Arc::clone(&rendered);
+
+    let (_, state) = exec_vm(
+        vec![
+            Box::new(BootRom::with_size(prog.encode_words(), 0x1000, 0x0).unwrap()),
+            Box::new(
+                ColorDisplay::new(
+                    3,
+                    2,
+                    Box::new(move |framebuffer, width, height| {
+                        let mut rendered = rendered_closure.lock().unwrap();
+                        assert!(rendered.is_none(), "Framebuffer was rendered twice");
+                        *rendered = Some((framebuffer.to_vec(), width, height));
+                    }),
+                    0x1,
+                )
+                .unwrap(),
+            ),
+        ],
+        RunConfig::halt_on_ex(),
+    );
+
+    if state.ex.is_some() {
+        panic!("Unexpected exception occurred while running the VM!");
+    }
+
+    assert_eq!(
+        *rendered.lock().unwrap(),
+        Some((vec![0x00FF_0000, 0, 0, 0, 0, 0x0000_FF00], 3, 2))
+    );
+}
+
+#[test]
+fn color_display_metadata() {
+    let display = ColorDisplay::new(320, 200, Box::new(|_, _, _| {}), 0x1).unwrap();
+    let metadata = DeviceMetadata::decode(display.metadata()).unwrap();
+
+    assert_eq!(
+        metadata.category,
+        DeviceCategory::Display(DisplayType::Color)
+    );
+    assert_eq!(metadata.size, (320 * 200 + 1) * 4);
+    assert_eq!(metadata.data, Some(0x0140_00C8));
+    assert_eq!(metadata.dimensions(), Some((320, 200)));
+
+    assert!(ColorDisplay::new(0, 200, Box::new(|_, _, _| {}), 0x1).is_err());
+    assert!(ColorDisplay::new(0xFFFF, 0xFFFF, Box::new(|_, _, _| {}), 0x1).is_err());
+}
@@ -1,2 +1,3 @@
 pub mod buffered;
 pub mod character;
+pub mod color;
@@ -0,0 +1,97 @@
+use crate::keyboard::AsyncKeyboard;
+use crate::storage::BootRom;
+use crate::volatile_mem::Ram;
+use lrvm::board::Bus;
+use lrvm_tools::asm::{cst, ExtInstr, Instr, Program, Reg};
+use lrvm_tools::debug::{exec_vm, RunConfig};
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+
+#[test]
+fn async_keyboard() {
+    let mut prog = Program::from(ExtInstr::SetReg(Reg::a0, 0x1000).to_prog_words());
+    prog.append_all(ExtInstr::SetReg(Reg::a2, 0x1008).to_prog_words());
+
+    // Poll the status register until an input is available
+    for instr in &[
+        Instr::Lsa(Reg::a1, Reg::a0.into(), 0u8.into()),
+        Instr::Cmp(Reg::a1, 0u16.into()),
+        Instr::If(cst::ZF.into()),
+        Instr::Jpr((-12i16).into()),
+    ] {
+        prog.append((*instr).into());
+    }
+
+    // Copy three characters (the last one should be empty) to RAM
+    for i in 0..3u8 {
+        prog.append(Instr::Lsa(Reg::a1, Reg::a0.into(), 4u8.into()).into());
+        prog.append(Instr::Wsa(Reg::a2.into(), (i * 4).into(), Reg::a1.into()).into());
+    }
+
+    prog.append(Instr::Halt().into());
+
+    let (sender, receiver) = mpsc::channel();
+
+    let producer = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(20));
+        sender.send("Hé".to_string()).unwrap();
+    });
+
+    let (mut vm, state) = exec_vm(
+        vec![
+            Box::new(BootRom::with_size(prog.encode_words(), 0x1000, 0x0).unwrap()),
+            Box::new(AsyncKeyboard::new(receiver, 0x1)),
+            Box::new(Ram::new(0x1000, 0x2).unwrap()),
+        ],
+        RunConfig::halt_on_ex(),
+    );
+
+    producer.join().unwrap();
+
+    if state.ex.is_some() {
+        panic!("Unexpected exception occurred while running the VM!");
+    }
+
+    vm.map(|mem| {
+        for (i, expected) in ['H', 'é', '\0'].iter().enumerate() {
+            let mut ex = 0;
+            let word = mem.read(0x1008 + i as u32 * 4, &mut ex);
+
+            assert_eq!(
+                ex, 0,
+                "Exception occurred while reading character {}: {:#008X}",
+                i, ex
+            );
+            assert_eq!(
+                word, *expected as u32,
+                "Invalid character {} from keyboard: {:#010X}",
+                i, word
+            );
+        }
+    });
+}
+
+#[test]
+fn async_keyboard_no_input() {
+    let (sender, receiver) = mpsc::channel::<String>();
+    let mut keyb = AsyncKeyboard::new(receiver, 0x1);
+
+    let mut ex = 0;
+
+    assert_eq!(keyb.read(0, &mut ex), 0);
+    assert_eq!(keyb.read(4, &mut ex), 0);
+    assert_eq!(ex, 0);
+
+    sender.send("ab".to_string()).unwrap();
+    assert_eq!(keyb.read(0, &mut ex), 2);
+
+    keyb.write(0, 0xFF, &mut ex);
+    assert_eq!(ex, 0);
+    assert_eq!(keyb.read(0, &mut ex), 0);
+
+    // A closed channel must not be considered as an error
+    drop(sender);
+    assert_eq!(keyb.read(4, &mut ex), 0);
+    assert_eq!(ex, 0);
+}
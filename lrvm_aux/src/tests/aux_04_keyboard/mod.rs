@@ -1,2 +1,3 @@
+pub mod asynchronous;
 pub mod sync_char;
 pub mod sync_line;
@@ -1,6 +1,7 @@
 use crate::keyboard::SyncLineKeyboard;
 use crate::storage::BootRom;
 use lrvm_tools::asm::{ExtInstr, Instr, Program, Reg};
+use lrvm_tools::bytes::u32_to_bytes_be;
 use lrvm_tools::debug::{exec_vm, RunConfig};
 use std::sync::{Arc, Mutex};
 
@@ -59,7 +60,7 @@ fn sync_line() {
         let mut ex = 0;
 
         for addr_r in 0x1000 / 4..=(0x1100 - 4) / 4 {
-            bytes.extend(&mem.read(addr_r * 4, &mut ex).to_be_bytes());
+            bytes.extend(&u32_to_bytes_be(mem.read(addr_r * 4, &mut ex)));
             assert_eq!(
                 ex,
                 0,
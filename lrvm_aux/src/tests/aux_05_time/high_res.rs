@@ -0,0 +1,41 @@
+use crate::time::HighResClock;
+use lrvm::board::Bus;
+use lrvm_tools::exceptions::AuxHwException;
+use lrvm_tools::metadata::{ClockType, DeviceMetadata};
+use std::thread;
+use std::time::Duration;
+
+fn read_counter(clock: &mut HighResClock) -> u64 {
+    let mut ex = 0;
+    let high = clock.read(0x00, &mut ex);
+    let low = clock.read(0x04, &mut ex);
+    assert_eq!(ex, 0);
+
+    (u64::from(high) << 32) + u64::from(low)
+}
+
+#[test]
+fn high_res_clock() {
+    let mut clock = HighResClock::new(0x1);
+
+    let metadata = DeviceMetadata::decode(clock.metadata()).unwrap();
+    assert_eq!(metadata.size, 8);
+    assert_eq!(metadata.category, ClockType::HighResolution.wrap());
+
+    let before = read_counter(&mut clock);
+    thread::sleep(Duration::from_millis(2));
+    let after = read_counter(&mut clock);
+
+    assert!(
+        after - before >= 2_000_000,
+        "Counter only increased by {} nanoseconds",
+        after - before
+    );
+
+    let mut ex = 0;
+    clock.write(0x00, 0, &mut ex);
+    assert_eq!(ex, AuxHwException::MemoryNotWritable.encode());
+
+    clock.reset();
+    assert!(read_counter(&mut clock) < after);
+}
@@ -1 +1 @@
-
+pub mod high_res;
@@ -0,0 +1,78 @@
+use crate::display::CharDisplay;
+use crate::storage::BootRom;
+use crate::volatile_mem::Ram;
+use lrvm::board::Bus;
+use lrvm_tools::asm::{ExtInstr, Instr, Program};
+use lrvm_tools::debug::{exec_vm, AccessDecision, RunConfig};
+use lrvm_tools::exceptions::{AuxHwException, NativeException};
+use std::sync::{Arc, Mutex};
+
+/// Build the components of a VM writing to a RAM then to a character display
+fn components(output: &Arc<Mutex<Vec<char>>>) -> Vec<Box<dyn Bus>> {
+    let mut program = Program::new();
+    program
+        .append_ext(ExtInstr::WriteAddrLit(0x1000, 0x1234))
+        .append_ext(ExtInstr::WriteAddrLit(0x1100, 'Z' as u32))
+        .append(Instr::Halt().into());
+
+    let output = Arc::clone(output);
+
+    vec![
+        Box::new(BootRom::with_size(program.encode_words(), 0x1000, 0x0).unwrap()),
+        Box::new(Ram::new(0x100, 0x1).unwrap()),
+        Box::new(CharDisplay::new(
+            Box::new(move |msg| output.lock().unwrap().push(msg.unwrap())),
+            0x2,
+        )),
+    ]
+}
+
+#[test]
+fn deny_display_writes() {
+    let output = Arc::new(Mutex::new(vec![]));
+    let accesses = Arc::new(Mutex::new(vec![]));
+    let policy_accesses = Arc::clone(&accesses);
+
+    let (mut motherboard, state) = exec_vm(
+        components(&output),
+        RunConfig::halt_on_ex()
+            .be_quiet()
+            .with_access_policy(move |access| {
+                policy_accesses.lock().unwrap().push(*access);
+
+                if access.aux_id == 2 {
+                    AccessDecision::Deny
+                } else {
+                    AccessDecision::Allow
+                }
+            }),
+    );
+
+    let ex = state.ex.expect("Denied write did not raise an exception");
+
+    assert!(matches!(
+        NativeException::decode_with_mode(ex.raw),
+        Ok((
+            NativeException::HardwareException(AuxHwException::MemoryNotWritable),
+            _
+        ))
+    ));
+
+    assert!(output.lock().unwrap().is_empty());
+
+    let accesses = accesses.lock().unwrap();
+    assert_eq!(accesses.len(), 2);
+    assert_eq!((accesses[0].aux_name, accesses[0].addr), ("RAM", 0x1000));
+    assert_eq!(accesses[1].addr, 0x1100);
+    assert_eq!(accesses[1].word, 'Z' as u32);
+
+    // Allowed writes are performed
+    let mut ex = 0;
+    assert_eq!(motherboard.map(|mem| mem.read(0x1000, &mut ex)), 0x1234);
+
+    // Without a policy, the display receives the character
+    let (_, state) = exec_vm(components(&output), RunConfig::halt_on_ex().be_quiet());
+
+    assert!(state.ex.is_none());
+    assert_eq!(*output.lock().unwrap(), vec!['Z']);
+}
@@ -0,0 +1,101 @@
+use crate::debug::{accesses_to_csv, BusAccess, BusAccessKind, BusLogger};
+use crate::storage::BootRom;
+use crate::volatile_mem::Ram;
+use lrvm::board::Bus;
+use lrvm_tools::asm::{ExtInstr, Instr, Program};
+use lrvm_tools::debug::{exec_vm, RunConfig};
+use std::sync::{Arc, Mutex};
+
+#[test]
+fn bus_logger() {
+    let mut program = Program::from_instr(ExtInstr::WriteAddrLit(0x1004, 0x01234567).to_instr());
+    program.append_all(ExtInstr::ReadAddr(0x1004).to_prog_words());
+    program.append(Instr::Halt().into());
+
+    let log = Arc::new(Mutex::new(vec![]));
+    let log_sink = Arc::clone(&log);
+
+    let (_, state) = exec_vm(
+        vec![
+            Box::new(BootRom::with_size(program.encode_words(), 0x1000, 0x0).unwrap()),
+            Box::new(BusLogger::wrap(
+                Box::new(Ram::new(0x1000, 0x1).unwrap()),
+                move |access| log_sink.lock().unwrap().push(access),
+            )),
+        ],
+        RunConfig::halt_on_ex(),
+    );
+
+    if state.ex.is_some() {
+        panic!("Unexpected exception occurred while running the VM!");
+    }
+
+    let log = log.lock().unwrap();
+
+    assert_eq!(
+        *log,
+        vec![
+            BusAccess {
+                seq: 0,
+                kind: BusAccessKind::Write,
+                addr: 0x4,
+                value: 0x01234567,
+                ex: 0
+            },
+            BusAccess {
+                seq: 1,
+                kind: BusAccessKind::Read,
+                addr: 0x4,
+                value: 0x01234567,
+                ex: 0
+            },
+        ]
+    );
+
+    assert_eq!(
+        accesses_to_csv(log.iter()),
+        "seq,kind,addr,value,ex\n0,write,0x00000004,0x01234567,0x0000\n1,read,0x00000004,0x01234567,0x0000\n"
+    );
+}
+
+#[test]
+fn bus_logger_passthrough() {
+    let ram = Ram::new(0x100, 0xABCD).unwrap();
+    let metadata = ram.metadata();
+
+    let logger = BusLogger::wrap(Box::new(ram), |_| {});
+    assert_eq!(logger.name(), "RAM");
+    assert_eq!(logger.metadata(), metadata);
+
+    let logger = logger.with_name("Logged RAM");
+    assert_eq!(logger.name(), "Logged RAM");
+    assert_eq!(logger.metadata(), metadata);
+}
+
+#[test]
+fn bus_logger_recording() {
+    let (mut logger, log) = BusLogger::recording(Box::new(Ram::new(0x100, 0x1).unwrap()));
+
+    let mut ex = 0;
+
+    logger.write(0x8, 0xDEADBEEF, &mut ex);
+    assert_eq!(logger.read(0x8, &mut ex), 0xDEADBEEF);
+
+    // Reset must reach the inner component without being logged
+    logger.reset();
+    assert_eq!(logger.read(0x8, &mut ex), 0);
+    assert_eq!(ex, 0);
+
+    let log = log.lock().unwrap();
+
+    assert_eq!(
+        log.iter()
+            .map(|access| (access.seq, access.kind, access.addr, access.value))
+            .collect::<Vec<_>>(),
+        vec![
+            (0, BusAccessKind::Write, 0x8, 0xDEADBEEF),
+            (1, BusAccessKind::Read, 0x8, 0xDEADBEEF),
+            (2, BusAccessKind::Read, 0x8, 0),
+        ]
+    );
+}
@@ -0,0 +1,44 @@
+use crate::debug::{run_and_capture, CAPTURE_DISPLAY_CAPACITY};
+use lrvm_tools::asm::{ExtInstr, Instr, Program, Reg};
+use lrvm_tools::debug::RunConfig;
+use lrvm_tools::metadata::HwIdAllocator;
+
+#[test]
+fn capture() {
+    // The program is 30 words long, so the display is located right after the RAM
+    let display_addr = 30 * 4 + 0x100;
+    let action_addr = display_addr + CAPTURE_DISPLAY_CAPACITY - 4;
+
+    let mut program = Program::new();
+    program
+        .append_ext(ExtInstr::WriteAddrLit(display_addr, 0x4869_2100))
+        .append_ext(ExtInstr::WriteAddrLit(action_addr, 0xAA))
+        .append_ext(ExtInstr::WriteAddrLit(display_addr, 0x4FFF_4B00))
+        .append_ext(ExtInstr::WriteAddrLit(action_addr, 0xBB))
+        .append(Instr::Cpy(Reg::a0, 42u16.into()).into())
+        .append(Instr::Halt().into());
+
+    assert_eq!(program.size(), 30);
+
+    let mut hw_ids = HwIdAllocator::new(0);
+
+    let result = run_and_capture(
+        program.encode_words(),
+        0x100,
+        RunConfig::halt_on_ex().be_quiet(),
+        &mut hw_ids,
+    )
+    .unwrap();
+
+    // The components' identifiers were taken from the allocator
+    let mut expected = HwIdAllocator::new(0);
+    assert!((0..3).all(|_| hw_ids.is_used(expected.allocate())));
+
+    assert!(
+        result.state.ex.is_none(),
+        "Unexpected exception occurred while running the VM!"
+    );
+
+    assert_eq!(result.lines, vec!["Hi!", "O\u{FFFD}K"]);
+    assert_eq!(result.regs.a[0], 42);
+}
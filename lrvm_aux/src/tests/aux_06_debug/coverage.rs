@@ -0,0 +1,53 @@
+use crate::storage::BootRom;
+use crate::volatile_mem::Ram;
+use lrvm_tools::asm::{Instr, Program, Reg};
+use lrvm_tools::debug::{exec_vm, RunConfig};
+
+#[test]
+fn coverage() {
+    let program = Program::from_instr(vec![
+        Instr::Cpy(Reg::a0, 1_u16.into()),
+        Instr::Jpr(8_i16.into()),
+        Instr::Cpy(Reg::a0, 2_u16.into()),
+        Instr::Halt(),
+    ]);
+
+    let (_, state) = exec_vm(
+        vec![
+            Box::new(BootRom::with_size(program.encode_words(), 0x1000, 0x0).unwrap()),
+            Box::new(Ram::new(0x1000, 0x1).unwrap()),
+        ],
+        RunConfig::quiet().with_coverage(true),
+    );
+
+    let coverage = state.coverage.expect("Coverage was not collected");
+
+    assert_eq!(
+        coverage.executed.iter().copied().collect::<Vec<_>>(),
+        vec![0x0, 0x4, 0xC]
+    );
+    assert!(!coverage.was_executed(0x8));
+
+    let by_region = coverage.by_region();
+    assert_eq!(by_region.len(), 2);
+    assert_eq!(by_region[0].0.start_addr, 0x0000);
+    assert_eq!(by_region[0].1, vec![0x0, 0x4, 0xC]);
+    assert_eq!(by_region[1].0.start_addr, 0x1000);
+    assert!(by_region[1].1.is_empty());
+
+    let annotated = coverage.annotate(&program);
+    let lines: Vec<&str> = annotated.lines().collect();
+
+    assert_eq!(lines.len(), 5);
+    assert!(lines[0].starts_with("> 0x00000000: cpy"));
+    assert!(lines[2].starts_with("  0x00000008: cpy"));
+    assert!(lines[3].starts_with("> 0x0000000C: halt"));
+    assert_eq!(lines[4], "=> 3 of 4 instructions executed (75.0%)");
+
+    let (_, state) = exec_vm(
+        vec![Box::new(BootRom::new(program.encode_words(), 0x0).unwrap())],
+        RunConfig::quiet(),
+    );
+
+    assert!(state.coverage.is_none());
+}
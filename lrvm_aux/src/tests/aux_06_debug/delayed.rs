@@ -0,0 +1,61 @@
+use crate::access::{ReadOnly, WriteOnly};
+use crate::debug::{BusLogger, DelayedBus};
+use crate::storage::BootRom;
+use crate::volatile_mem::Ram;
+use lrvm::board::Bus;
+use lrvm_tools::asm::{ExtInstr, Instr, Program, Reg};
+use lrvm_tools::debug::{exec_vm, RunConfig};
+
+fn run(ram: Box<dyn Bus>) -> u128 {
+    let mut program = Program::from_instr(ExtInstr::SetReg(Reg::a0, 0x01234567).to_instr());
+    program.append_all(ExtInstr::CopyRegToMem(Reg::a0, 0x1004).to_prog_words());
+    program.append_all(ExtInstr::CopyMemToReg(Reg::a1, 0x1004).to_prog_words());
+    program.append(Instr::Halt().into());
+
+    let (mut vm, state) = exec_vm(
+        vec![
+            Box::new(BootRom::with_size(program.encode_words(), 0x1000, 0x0).unwrap()),
+            ram,
+        ],
+        RunConfig::halt_on_ex(),
+    );
+
+    assert!(
+        state.ex.is_none(),
+        "Unexpected exception occurred while running the VM!"
+    );
+
+    assert_eq!(vm.cpu().regs.a[1], 0x01234567);
+
+    state.cycles
+}
+
+#[test]
+fn delayed_bus() {
+    let cycles = run(Box::new(Ram::new(0x1000, 0x1).unwrap()));
+    let delayed_cycles = run(Box::new(DelayedBus::wrap(
+        Ram::new(0x1000, 0x1).unwrap(),
+        10,
+    )));
+
+    // One write and one read were made to the RAM
+    assert_eq!(delayed_cycles, cycles + 20);
+}
+
+#[test]
+fn wrapped_delayed_bus() {
+    let delayed = || Box::new(DelayedBus::wrap(Ram::new(0x1000, 0x1).unwrap(), 10));
+
+    assert_eq!(BusLogger::wrap(delayed(), |_| {}).latency(), 10);
+    assert_eq!(ReadOnly::wrap(delayed()).latency(), 10);
+    assert_eq!(WriteOnly::wrap(delayed()).latency(), 10);
+
+    let saturated = DelayedBus::wrap(Ram::new(0x1000, 0x1).unwrap(), u32::MAX);
+    assert_eq!(DelayedBus::wrap(saturated, 10).latency(), u32::MAX);
+
+    let cycles = run(Box::new(Ram::new(0x1000, 0x1).unwrap()));
+    let (logger, log) = BusLogger::recording(delayed());
+
+    assert_eq!(run(Box::new(logger)), cycles + 20);
+    assert_eq!(log.lock().unwrap().len(), 2);
+}
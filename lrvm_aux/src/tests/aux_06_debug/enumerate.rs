@@ -0,0 +1,68 @@
+use crate::storage::BootRom;
+use crate::volatile_mem::Ram;
+use lrvm::board::{Bus, MotherBoard};
+use lrvm_tools::asm::{ExtInstr, Instr};
+use lrvm_tools::debug::{
+    decode_device_table, enumerate_devices, run_vm, RunConfig, DEVICE_TABLE_ENTRY_WORDS,
+};
+use lrvm_tools::metadata::MemoryType;
+
+#[test]
+fn enumerate() {
+    let mut program = ExtInstr::EnumerateDevices(0x1000).to_program();
+    program.append(Instr::Halt().into());
+
+    let components: Vec<Box<dyn Bus>> = vec![
+        Box::new(BootRom::with_size(program.encode_words(), 0x1000, 0x0).unwrap()),
+        Box::new(Ram::new(0x1000, 0x0123_4567_89AB_CDEF).unwrap()),
+        Box::new(Ram::new(0x100, 0x2).unwrap()),
+    ];
+
+    let mut motherboard = MotherBoard::new(components);
+
+    // The last component is not mapped
+    motherboard.map(|mem| {
+        mem.map_contiguous(0x0000_0000, [0, 1])
+            .mapping
+            .expect("Failed to map components")
+    });
+
+    motherboard.reset();
+
+    let state = run_vm(motherboard.cpu(), RunConfig::halt_on_ex());
+
+    if state.ex.is_some() {
+        panic!("Unexpected exception occurred while running the VM!");
+    }
+
+    let table = motherboard.map(|mem| {
+        (0..1 + 3 * DEVICE_TABLE_ENTRY_WORDS as u32)
+            .map(|i| {
+                let mut ex = 0;
+                let word = mem.read(0x1000 + i * 4, &mut ex);
+                assert_eq!(ex, 0, "Exception occurred while reading the device table");
+                word
+            })
+            .collect::<Vec<_>>()
+    });
+
+    let guest = decode_device_table(&table).expect("Failed to decode the device table");
+    let host = enumerate_devices(&mut motherboard);
+
+    assert_eq!(guest, host);
+
+    assert_eq!(host.len(), 3);
+    assert_eq!(host[1].hw_id, 0x0123_4567_89AB_CDEF);
+    assert_eq!(host[1].category, Ok(MemoryType::Ram.wrap()));
+    assert_eq!(host[1].mapping, Some((0x1000, 0x1FFF)));
+    assert_eq!(host[2].size, 0x100);
+    assert_eq!(host[2].mapping, None);
+}
+
+#[test]
+fn decode_invalid_device_table() {
+    assert!(decode_device_table(&[]).is_err());
+    assert!(decode_device_table(&[1; DEVICE_TABLE_ENTRY_WORDS]).is_err());
+    assert!(decode_device_table(&[u32::MAX, 0, 0]).is_err());
+    assert_eq!(decode_device_table(&[0]), Ok(vec![]));
+}
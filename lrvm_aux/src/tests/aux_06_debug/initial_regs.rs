@@ -0,0 +1,34 @@
+use crate::storage::BootRom;
+use lrvm_tools::asm::{Instr, Program, Reg};
+use lrvm_tools::debug::{exec_vm, RunConfig};
+
+#[test]
+fn initial_regs() {
+    // The routine starts at the second instruction
+    let program = Program::from_instr(vec![
+        Instr::Halt(),
+        Instr::Add(Reg::a0, 1u16.into()),
+        Instr::Halt(),
+    ]);
+
+    let (mut motherboard, state) = exec_vm(
+        vec![Box::new(
+            BootRom::with_size(program.encode_words(), 0x1000, 0x0).unwrap(),
+        )],
+        RunConfig::halt_on_ex().with_initial_regs(&[(Reg::a0, 41), (Reg::pc, 0x04)]),
+    );
+
+    assert!(
+        state.ex.is_none(),
+        "Unexpected exception occurred while running the VM!"
+    );
+
+    assert_eq!(state.addr, 0x08);
+    assert_eq!(motherboard.cpu().regs.a[0], 42);
+}
+
+#[test]
+#[should_panic]
+fn initial_regs_read_only() {
+    RunConfig::new().with_initial_regs(&[(Reg::et, 1)]);
+}
@@ -0,0 +1,88 @@
+use crate::debug::DelayedBus;
+use crate::storage::BootRom;
+use crate::volatile_mem::Ram;
+use lrvm::board::Bus;
+use lrvm_tools::asm::{ExtInstr, Instr, Program, Reg};
+use lrvm_tools::debug::{exec_vm, RunConfig};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+#[test]
+fn stack_pointer_invariant() {
+    let init = ExtInstr::SetReg(Reg::ssp, 0x2000);
+    let init_len = init.to_instr().len() as u128;
+
+    let mut program = Program::new();
+    program
+        .append_ext(init)
+        .append(Instr::Push(Reg::a0.into()).into())
+        .append(Instr::Cpy(Reg::ssp, 0x8000_u16.into()).into())
+        .append(Instr::Push(Reg::a0.into()).into())
+        .append(Instr::Halt().into());
+
+    // Address of the instruction corrupting the stack pointer
+    let corruption_addr = (program.size() as u32 - 3) * 4;
+
+    let components = || -> Vec<Box<dyn Bus>> {
+        vec![
+            Box::new(BootRom::with_size(program.encode_words(), 0x1000, 0x0).unwrap()),
+            Box::new(Ram::new(0x1000, 0x1).unwrap()),
+        ]
+    };
+
+    let config = RunConfig::quiet().with_invariant("stack in RAM", move |state| {
+        // The stack pointer is only valid once initialized
+        state.cycles < init_len || (0x1000..=0x2000).contains(&state.regs.ssp)
+    });
+
+    let (_, state) = exec_vm(components(), config.clone());
+
+    let violation = state
+        .invariant_violation
+        .clone()
+        .expect("Invariant violation was not detected");
+
+    assert_eq!(violation.name, "stack in RAM");
+    assert_eq!(violation.pc, corruption_addr);
+    assert!(state.ex.is_none());
+    assert!(state
+        .to_string()
+        .ends_with("because invariant 'stack in RAM' was violated"));
+
+    // With an interval of 2 instructions, the violation is detected one instruction later
+    let (_, state) = exec_vm(components(), config.with_invariants_interval(2));
+    assert_eq!(state.invariant_violation.unwrap().pc, corruption_addr + 4);
+
+    let (_, state) = exec_vm(components(), RunConfig::quiet());
+    assert!(state.invariant_violation.is_none());
+}
+
+#[test]
+fn invariants_interval_with_latency() {
+    let mut program = Program::from_instr(ExtInstr::SetReg(Reg::a0, 0x01234567).to_instr());
+    program.append_all(ExtInstr::CopyRegToMem(Reg::a0, 0x1004).to_prog_words());
+    program.append_all(ExtInstr::CopyMemToReg(Reg::a1, 0x1004).to_prog_words());
+    program.append(Instr::Halt().into());
+
+    let checks = Arc::new(AtomicUsize::new(0));
+    let counter = Arc::clone(&checks);
+
+    let (_, state) = exec_vm(
+        vec![
+            Box::new(BootRom::with_size(program.encode_words(), 0x1000, 0x0).unwrap()),
+            Box::new(DelayedBus::wrap(Ram::new(0x1000, 0x1).unwrap(), 3)),
+        ],
+        RunConfig::quiet()
+            .with_invariant("counter", move |_| {
+                counter.fetch_add(1, Ordering::SeqCst);
+                true
+            })
+            .with_invariants_interval(2),
+    );
+
+    assert!(state.ex.is_none());
+    assert!(state.cycles > program.size() as u128);
+
+    // Invariants are checked every 2 instructions, whatever the components' latency
+    assert_eq!(checks.load(Ordering::SeqCst), program.size() / 2);
+}
@@ -0,0 +1,23 @@
+use crate::storage::BootRom;
+use lrvm_tools::asm::{ExtInstr, Instr, Program, Reg};
+use lrvm_tools::debug::{exec_vm_with_io, RunConfig};
+
+#[test]
+fn io_port() {
+    let mut program = Program::from_instr(ExtInstr::WriteAddrLit(0x8000_0000, 0x12).to_instr());
+    program.append_ext(ExtInstr::ReadAddrTo(Reg::a0, 0x8000_0000));
+    program.append_ext(ExtInstr::WriteAddrLit(0x8000_0000, 0x34));
+    program.append(Instr::Halt().into());
+
+    let (_, state, log) = exec_vm_with_io(
+        vec![Box::new(BootRom::new(program.encode_words(), 0x0).unwrap())],
+        RunConfig::halt_on_ex(),
+        0x8000_0000,
+    );
+
+    if state.ex.is_some() {
+        panic!("Unexpected exception occurred while running the VM!");
+    }
+
+    assert_eq!(log, vec![(true, 0x12), (false, 0), (true, 0x34)]);
+}
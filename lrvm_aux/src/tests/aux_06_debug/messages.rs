@@ -0,0 +1,43 @@
+use crate::storage::BootRom;
+use lrvm_tools::asm::{Instr, Program, Reg};
+use lrvm_tools::debug::{exec_vm, spawn_vm, MessageSink, RunConfig};
+
+fn program() -> Vec<u32> {
+    Program::from_instr(vec![Instr::Cpy(Reg::a0, 1_u16.into()), Instr::Halt()]).encode_words()
+}
+
+#[test]
+fn capture_messages() {
+    let (_, state) = exec_vm(
+        vec![Box::new(BootRom::new(program(), 0x0).unwrap())],
+        RunConfig::new().with_message_sink(MessageSink::Capture),
+    );
+
+    assert_eq!(state.messages.len(), 2);
+    assert!(state.messages[0].starts_with("=> Component 0000 'BootROM"));
+    assert_eq!(
+        state.messages[1],
+        "[lrvm] Cycle 0x00000002: CPU halted at address 0x00000004"
+    );
+
+    let (_, state) = exec_vm(
+        vec![Box::new(BootRom::new(program(), 0x0).unwrap())],
+        RunConfig::new(),
+    );
+
+    assert!(state.messages.is_empty());
+}
+
+#[test]
+fn capture_spawned_messages() {
+    let outcome = spawn_vm(
+        vec![Box::new(BootRom::new(program(), 0x0).unwrap())],
+        RunConfig::quiet()
+            .with_print_finish(true)
+            .with_message_sink(MessageSink::Capture),
+    )
+    .wait();
+
+    assert_eq!(outcome.messages().len(), 2);
+    assert!(outcome.messages()[1].contains("CPU halted"));
+}
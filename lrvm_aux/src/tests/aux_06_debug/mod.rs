@@ -0,0 +1,15 @@
+pub mod access_policy;
+pub mod bus_logger;
+pub mod capture;
+pub mod coverage;
+pub mod delayed;
+pub mod enumerate;
+pub mod initial_regs;
+pub mod invariants;
+pub mod io_port;
+pub mod messages;
+pub mod profiler;
+pub mod regions;
+pub mod reset_hook;
+pub mod spawn;
+pub mod throttle;
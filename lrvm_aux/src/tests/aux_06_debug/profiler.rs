@@ -0,0 +1,40 @@
+use crate::storage::BootRom;
+use lrvm_tools::asm::{cst, Instr, Program, Reg};
+use lrvm_tools::debug::{exec_vm, RunConfig};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+#[test]
+fn profiler() {
+    let program = Program::from_instr(vec![
+        Instr::Cpy(Reg::a0, 5u16.into()),
+        Instr::Sub(Reg::a0, 1u16.into()),
+        Instr::Cmp(Reg::a0, 0u16.into()),
+        Instr::IfN(cst::ZF.into()),
+        Instr::Jpr((-12i16).into()),
+        Instr::Halt(),
+    ]);
+
+    let hits = Arc::new(Mutex::new(HashMap::new()));
+    let hits_sink = Arc::clone(&hits);
+
+    let (_, state) = exec_vm(
+        vec![Box::new(
+            BootRom::with_size(program.encode_words(), 0x1000, 0x0).unwrap(),
+        )],
+        RunConfig::halt_on_ex()
+            .with_profiler(move |addr| *hits_sink.lock().unwrap().entry(addr).or_insert(0) += 1),
+    );
+
+    assert!(
+        state.ex.is_none(),
+        "Unexpected exception occurred while running the VM!"
+    );
+
+    let hits = hits.lock().unwrap();
+
+    assert_eq!(hits[&0x00], 1);
+    assert_eq!(hits[&0x04], 5);
+    assert_eq!(hits[&0x10], 4);
+    assert_eq!(hits[&0x14], 1);
+}
@@ -0,0 +1,33 @@
+use crate::storage::BootRom;
+use lrvm_tools::asm::{Instr, Program, Reg};
+use lrvm_tools::debug::{exec_vm, MessageSink, RunConfig};
+
+#[test]
+fn symbolicate_addresses() {
+    let config = RunConfig::new()
+        .name_region("ram", 0x3000..0x4000)
+        .name_region("display_buf", 0x3000..0x3100);
+
+    assert_eq!(config.symbolicate(0x3024), "display_buf+0x24");
+    assert_eq!(config.symbolicate(0x3000), "display_buf");
+    assert_eq!(config.symbolicate(0x3100), "ram+0x100");
+    assert_eq!(config.symbolicate(0x4000), "0x00004000");
+}
+
+#[test]
+fn symbolicate_messages() {
+    let program =
+        Program::from_instr(vec![Instr::Cpy(Reg::a0, 1_u16.into()), Instr::Halt()]).encode_words();
+
+    let (_, state) = exec_vm(
+        vec![Box::new(BootRom::new(program, 0x0).unwrap())],
+        RunConfig::new()
+            .with_message_sink(MessageSink::Capture)
+            .name_region("boot", 0x0000_0000..0x0000_0100),
+    );
+
+    assert_eq!(
+        state.messages.last().unwrap(),
+        "[lrvm] Cycle 0x00000002: CPU halted at address boot+0x4"
+    );
+}
@@ -0,0 +1,27 @@
+use crate::storage::BootRom;
+use lrvm_tools::asm::{Instr, Program};
+use lrvm_tools::debug::{exec_vm, RunConfig};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+#[test]
+fn reset_hook() {
+    let calls = Arc::new(AtomicUsize::new(0));
+    let hook_calls = Arc::clone(&calls);
+
+    let (mut motherboard, state) = exec_vm(
+        vec![Box::new(
+            BootRom::new(Program::from_instr(vec![Instr::Halt()]).encode_words(), 0x0).unwrap(),
+        )],
+        RunConfig::quiet().on_reset(move |motherboard| {
+            hook_calls.fetch_add(1, Ordering::SeqCst);
+
+            // Registers are cleared by the reset, so this value is only kept if the hook runs after it
+            motherboard.cpu().regs.a[0] = 42;
+        }),
+    );
+
+    assert!(state.ex.is_none());
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+    assert_eq!(motherboard.cpu().regs.a[0], 42);
+}
@@ -0,0 +1,124 @@
+use crate::storage::BootRom;
+use crate::volatile_mem::SharedMem;
+use lrvm::board::Bus;
+use lrvm_tools::asm::{ExtInstr, Instr, Reg};
+use lrvm_tools::debug::{spawn_vm, RunConfig, RunOutcome};
+use lrvm_tools::metadata::{DeviceMetadata, MemoryType};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+/// Build the components of a VM incrementing the first word of the provided buffer forever
+fn counter(buffer: &Arc<Mutex<Vec<u32>>>) -> Vec<Box<dyn Bus + Send>> {
+    let mut program = ExtInstr::SetReg(Reg::rr0, 0x1000).to_program();
+    program.append_all(&[
+        Instr::Add(Reg::a0, 1u16.into()).into(),
+        Instr::Wsa(Reg::rr0.into(), 0u8.into(), Reg::a0.into()).into(),
+        Instr::Jpr((-8i16).into()).into(),
+    ]);
+
+    vec![
+        Box::new(BootRom::with_size(program.encode_words(), 0x1000, 0x0).unwrap()),
+        Box::new(SharedMem::new(Arc::clone(buffer), 0x1, false).unwrap()),
+    ]
+}
+
+#[test]
+fn spawn_pause_resume() {
+    let buffer = Arc::new(Mutex::new(vec![0; 1]));
+    let vm = spawn_vm(counter(&buffer), RunConfig::quiet());
+
+    let read = || buffer.lock().unwrap()[0];
+
+    while read() == 0 {
+        thread::sleep(Duration::from_millis(1));
+    }
+
+    vm.pause();
+
+    // Wait for the current instruction to finish
+    while !vm.is_paused() {
+        thread::sleep(Duration::from_millis(1));
+    }
+
+    let paused_at = read();
+    thread::sleep(Duration::from_millis(20));
+    assert_eq!(read(), paused_at, "VM kept running while paused");
+    assert!(vm.is_paused());
+    assert!(!vm.is_halted());
+
+    vm.resume();
+
+    while read() == paused_at {
+        thread::sleep(Duration::from_millis(1));
+    }
+
+    vm.stop();
+
+    match vm.wait() {
+        RunOutcome::Stopped(state) => assert!(state.ex.is_none()),
+        outcome => panic!("Unexpected VM outcome: {:?}", outcome),
+    }
+}
+
+#[test]
+fn spawn_finish() {
+    let vm = spawn_vm(
+        vec![Box::new(
+            BootRom::with_size(vec![Instr::Halt().encode_word()], 0x1000, 0x0).unwrap(),
+        )],
+        RunConfig::quiet(),
+    );
+
+    match vm.wait() {
+        RunOutcome::Finished(state) => assert_eq!(state.cycles, 1),
+        outcome => panic!("Unexpected VM outcome: {:?}", outcome),
+    }
+}
+
+/// Component indicating when it's dropped
+struct DropFlag(Arc<AtomicBool>);
+
+impl Bus for DropFlag {
+    fn name(&self) -> &'static str {
+        "Drop flag"
+    }
+
+    fn metadata(&self) -> [u32; 8] {
+        DeviceMetadata::new(0x2, 4, MemoryType::Ram.into(), None, None).encode()
+    }
+
+    fn read(&mut self, _addr: u32, _ex: &mut u16) -> u32 {
+        0
+    }
+
+    fn write(&mut self, _addr: u32, _word: u32, _ex: &mut u16) {}
+
+    fn reset(&mut self) {}
+}
+
+impl Drop for DropFlag {
+    fn drop(&mut self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+}
+
+#[test]
+fn spawn_drop() {
+    let buffer = Arc::new(Mutex::new(vec![0; 1]));
+    let dropped = Arc::new(AtomicBool::new(false));
+
+    let mut components = counter(&buffer);
+    components.push(Box::new(DropFlag(Arc::clone(&dropped))));
+
+    let vm = spawn_vm(components, RunConfig::quiet());
+    vm.pause();
+
+    drop(vm);
+
+    assert!(
+        dropped.load(Ordering::SeqCst),
+        "VM thread was not stopped when its handle was dropped"
+    );
+}
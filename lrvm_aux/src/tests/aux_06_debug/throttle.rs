@@ -0,0 +1,52 @@
+use crate::storage::BootRom;
+use lrvm_tools::asm::{Instr, Program, Reg};
+use lrvm_tools::debug::{exec_vm, InstructionsPerSecond, RunConfig};
+use std::time::Instant;
+
+#[test]
+fn throttle() {
+    let mut instrs = vec![Instr::Add(Reg::a0, 1u16.into()); 499];
+    instrs.push(Instr::Halt());
+
+    let program = Program::from_instr(instrs);
+
+    let started_at = Instant::now();
+
+    let (_, state) = exec_vm(
+        vec![Box::new(
+            BootRom::with_size(program.encode_words(), 0x1000, 0x0).unwrap(),
+        )],
+        RunConfig::halt_on_ex().with_target_speed(Some(InstructionsPerSecond(1000))),
+    );
+
+    let elapsed = started_at.elapsed().as_secs_f64();
+
+    assert!(
+        state.ex.is_none(),
+        "Unexpected exception occurred while running the VM!"
+    );
+
+    assert_eq!(state.cycles, 500);
+
+    // One checkpoint every 10 instructions, as 1000 instructions per second make 10 instructions per checkpoint
+    assert_eq!(state.throttle_checkpoints, 50);
+
+    // The runner sleeps until the expected time is reached, so these bounds hold even on a loaded machine
+    assert!(elapsed >= 0.5, "500 instructions at 1kHz took {}s", elapsed);
+
+    let speed = state.effective_speed.unwrap();
+    assert!(
+        speed <= 1000.0,
+        "Effective speed was {} instructions per second",
+        speed
+    );
+
+    let (_, state) = exec_vm(
+        vec![Box::new(
+            BootRom::with_size(program.encode_words(), 0x1000, 0x0).unwrap(),
+        )],
+        RunConfig::halt_on_ex(),
+    );
+
+    assert_eq!(state.throttle_checkpoints, 0);
+}
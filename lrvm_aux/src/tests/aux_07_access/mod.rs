@@ -0,0 +1,2 @@
+pub mod read_only;
+pub mod write_only;
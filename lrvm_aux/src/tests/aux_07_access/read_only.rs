@@ -0,0 +1,40 @@
+use crate::access::ReadOnly;
+use crate::volatile_mem::SharedMem;
+use lrvm::board::Bus;
+use lrvm_tools::exceptions::AuxHwException;
+use std::sync::{Arc, Mutex};
+
+#[test]
+fn read_only() {
+    let buffer = Arc::new(Mutex::new(vec![0x01234567, 0x89ABCDEF]));
+    let shared = SharedMem::new(Arc::clone(&buffer), 0x1, true).unwrap();
+    let metadata = shared.metadata();
+
+    let mut view = ReadOnly::wrap(Box::new(shared));
+
+    assert_eq!(view.name(), "Shared Memory");
+    assert_eq!(view.metadata(), metadata);
+
+    let mut ex = 0;
+    assert_eq!(view.read(0x4, &mut ex), 0x89ABCDEF);
+    assert_eq!(ex, 0);
+
+    view.write(0x4, 0, &mut ex);
+    assert_eq!(ex, AuxHwException::MemoryNotWritable.encode());
+    assert_eq!(buffer.lock().unwrap()[1], 0x89ABCDEF);
+
+    // Reset must still reach the inner component
+    view.reset();
+    assert_eq!(*buffer.lock().unwrap(), vec![0, 0]);
+}
+
+#[test]
+fn read_only_custom_exception() {
+    let buffer = Arc::new(Mutex::new(vec![0; 4]));
+    let mut view = ReadOnly::wrap(Box::new(SharedMem::new(buffer, 0x1, false).unwrap()))
+        .with_exception(AuxHwException::UnsupportedOperation);
+
+    let mut ex = 0;
+    view.write(0x0, 0, &mut ex);
+    assert_eq!(ex, AuxHwException::UnsupportedOperation.encode());
+}
@@ -0,0 +1,28 @@
+use crate::access::WriteOnly;
+use crate::volatile_mem::SharedMem;
+use lrvm::board::Bus;
+use lrvm_tools::exceptions::AuxHwException;
+use std::sync::{Arc, Mutex};
+
+#[test]
+fn write_only() {
+    let buffer = Arc::new(Mutex::new(vec![0; 2]));
+    let shared = SharedMem::new(Arc::clone(&buffer), 0x1, true).unwrap();
+    let metadata = shared.metadata();
+
+    let mut view = WriteOnly::wrap(Box::new(shared));
+
+    assert_eq!(view.metadata(), metadata);
+
+    let mut ex = 0;
+    view.write(0x4, 0x89ABCDEF, &mut ex);
+    assert_eq!(ex, 0);
+    assert_eq!(buffer.lock().unwrap()[1], 0x89ABCDEF);
+
+    assert_eq!(view.read(0x4, &mut ex), 0);
+    assert_eq!(ex, AuxHwException::MemoryNotReadable.encode());
+
+    // Reset must still reach the inner component
+    view.reset();
+    assert_eq!(*buffer.lock().unwrap(), vec![0, 0]);
+}
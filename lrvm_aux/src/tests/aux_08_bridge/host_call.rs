@@ -0,0 +1,91 @@
+use crate::bridge::HostCall;
+use crate::storage::BootRom;
+use lrvm_tools::asm::{ExtInstr, Instr, Program, Reg};
+use lrvm_tools::debug::{exec_vm, RunConfig};
+use lrvm_tools::exceptions::AuxHwException;
+use std::sync::{Arc, Mutex};
+
+fn call_prog(selector: u8) -> Program {
+    let mut prog = Program::from(ExtInstr::SetReg(Reg::a0, 0x1000).to_prog_words());
+
+    for instr in &[
+        // Fill the first two arguments
+        Instr::Wsa(Reg::a0.into(), 0u8.into(), 5u8.into()),
+        Instr::Wsa(Reg::a0.into(), 4u8.into(), 7u8.into()),
+        // Trigger the call
+        Instr::Wsa(Reg::a0.into(), 36u8.into(), selector.into()),
+        // Read the result and the third argument
+        Instr::Lsa(Reg::a1, Reg::a0.into(), 32u8.into()),
+        Instr::Lsa(Reg::a2, Reg::a0.into(), 8u8.into()),
+        Instr::Halt(),
+    ] {
+        prog.append((*instr).into());
+    }
+
+    prog
+}
+
+#[test]
+fn host_call() {
+    let calls = Arc::new(Mutex::new(vec![]));
+    let handler_calls = Arc::clone(&calls);
+
+    let (mut vm, state) = exec_vm(
+        vec![
+            Box::new(BootRom::with_size(call_prog(0x02).encode_words(), 0x1000, 0x0).unwrap()),
+            Box::new(HostCall::new(
+                Box::new(move |selector, args| {
+                    handler_calls.lock().unwrap().push(selector);
+                    args[2] = args[0] * args[1];
+                    args[0] + args[1]
+                }),
+                0x1,
+            )),
+        ],
+        RunConfig::halt_on_ex(),
+    );
+
+    if state.ex.is_some() {
+        panic!("Unexpected exception occurred while running the VM!");
+    }
+
+    assert_eq!(
+        *calls.lock().unwrap(),
+        vec![0x02],
+        "Host function must be called exactly once"
+    );
+
+    let regs = &vm.cpu().regs;
+    assert_eq!(regs.a[1], 12, "Invalid result word: {:#010X}", regs.a[1]);
+    assert_eq!(
+        regs.a[2], 35,
+        "Invalid mutated argument: {:#010X}",
+        regs.a[2]
+    );
+}
+
+#[test]
+fn host_call_exception() {
+    let (_, state) = exec_vm(
+        vec![
+            Box::new(BootRom::with_size(call_prog(0xEE).encode_words(), 0x1000, 0x0).unwrap()),
+            Box::new(HostCall::new_fallible(
+                Box::new(|selector, _| {
+                    Err(AuxHwException::UnknownOperation(selector as u8).encode())
+                }),
+                0x1,
+            )),
+        ],
+        RunConfig::halt_on_ex(),
+    );
+
+    let ex = state
+        .ex
+        .expect("Failed host call did not raise an exception");
+
+    assert_eq!(ex.code, 0xA0, "Expected a hardware exception");
+    assert_eq!(
+        ex.associated,
+        AuxHwException::UnknownOperation(0xEE).encode()
+    );
+}
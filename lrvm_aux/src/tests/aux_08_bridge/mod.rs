@@ -0,0 +1 @@
+pub mod host_call;
@@ -0,0 +1,130 @@
+use crate::access::ReadOnly;
+use crate::volatile_mem::Ram;
+use crate::BoardBuilder;
+use lrvm::board::Bus;
+use lrvm_tools::asm::{ExtInstr, Instr, Program};
+use lrvm_tools::debug::{exec_vm, run_vm, RunConfig};
+
+fn hw_ids(components: &[Box<dyn Bus>]) -> Vec<[u32; 2]> {
+    components
+        .iter()
+        .map(|component| {
+            let metadata = component.metadata();
+            [metadata[0], metadata[1]]
+        })
+        .collect()
+}
+
+#[test]
+fn board_builder() {
+    let mut program = Program::from_instr(ExtInstr::WriteAddrLit(0x1000, 0x01234567).to_instr());
+    program.append(Instr::Halt().into());
+
+    let build = |seed| {
+        BoardBuilder::new()
+            .auto_hw_ids(seed)
+            .boot_rom_with_size(program.encode_words(), 0x1000)
+            .ram(0x1000)
+            .ram(0x100)
+            .named("scratch")
+            .device(Box::new(ReadOnly::wrap(Box::new(
+                Ram::new(0x100, 0xABCD).unwrap(),
+            ))))
+            .build()
+            .unwrap()
+    };
+
+    let (components, index) = build(0);
+
+    assert_eq!(index.len(), 4);
+    assert!(!index.is_empty());
+    assert_eq!(index["boot_rom"], 0);
+    assert_eq!(index["ram"], 1);
+    assert_eq!(index["scratch"], 2);
+    assert_eq!(index["RAM"], 3);
+    assert_eq!(index.get("display"), None);
+
+    // Identifiers must be deterministic, depend on the seed and be left untouched for custom devices
+    let ids = hw_ids(&components);
+    assert_eq!(ids, hw_ids(&build(0).0));
+    assert_ne!(ids, hw_ids(&build(1).0));
+    assert_ne!(ids[0], ids[1]);
+    assert_eq!(ids[3], [0, 0xABCD]);
+
+    let (mut vm, state) = exec_vm(components, RunConfig::halt_on_ex());
+
+    if state.ex.is_some() {
+        panic!("Unexpected exception occurred while running the VM!");
+    }
+
+    let mut ex = 0;
+    assert_eq!(vm.map(|mem| mem.read(0x1000, &mut ex)), 0x01234567);
+}
+
+#[test]
+fn board_builder_errors() {
+    assert!(BoardBuilder::new().ram(0).build().is_err());
+    assert!(BoardBuilder::new().ram(0x100).ram(0x100).build().is_err());
+    assert!(BoardBuilder::new().named("ram").build().is_err());
+
+    let (components, _) = BoardBuilder::new()
+        .ram(0x100)
+        .ram(0x100)
+        .named("ram_2")
+        .build()
+        .unwrap();
+
+    assert_eq!(components.len(), 2);
+}
+
+#[test]
+fn board_builder_motherboard() {
+    let mut program = Program::from_instr(ExtInstr::WriteAddrLit(0x1000, 0x89ABCDEF).to_instr());
+    program.append(Instr::Halt().into());
+
+    let (mut motherboard, index) = BoardBuilder::new()
+        .rom(&program)
+        .ram(0x1000)
+        .build_motherboard()
+        .unwrap();
+
+    assert_eq!(index["boot_rom"], 0);
+    assert_eq!(index["ram"], 1);
+
+    let state = run_vm(motherboard.cpu(), RunConfig::halt_on_ex());
+
+    if state.ex.is_some() {
+        panic!("Unexpected exception occurred while running the VM!");
+    }
+
+    let mut ex = 0;
+    assert_eq!(motherboard.map(|mem| mem.read(0x1000, &mut ex)), 0x89ABCDEF);
+}
+
+#[test]
+fn board_builder_reseed() {
+    let (components, _) = BoardBuilder::new()
+        .ram(0x100)
+        .device(Box::new(Ram::new(0x100, 0xABCD).unwrap()))
+        .auto_hw_ids(0)
+        .ram(0x100)
+        .named("ram_2")
+        .auto_hw_ids(1)
+        .ram(0x100)
+        .named("ram_3")
+        .auto_hw_ids(0)
+        .ram(0x100)
+        .named("ram_4")
+        .build()
+        .unwrap();
+
+    let mut ids = hw_ids(&components);
+    ids.sort_unstable();
+    ids.dedup();
+
+    assert_eq!(
+        ids.len(),
+        5,
+        "Duplicate hardware identifiers were generated"
+    );
+}
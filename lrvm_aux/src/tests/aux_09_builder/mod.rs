@@ -0,0 +1 @@
+pub mod board_builder;
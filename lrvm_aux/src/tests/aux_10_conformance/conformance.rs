@@ -0,0 +1,78 @@
+use crate::storage::BootRom;
+use crate::volatile_mem::{Ram, SharedMem};
+use lrvm::board::Bus;
+use lrvm_tools::metadata::{DeviceMetadata, MemoryType, StorageType};
+use lrvm_tools::testing::{check_bus_component, BusExpectations};
+use std::sync::{Arc, Mutex};
+
+#[test]
+fn conformance_ram() {
+    check_bus_component(
+        || Box::new(Ram::new(0x100, 0x0).unwrap()),
+        BusExpectations::new()
+            .with_size(0x100)
+            .with_category(MemoryType::Ram)
+            .with_out_of_range_exceptions(false),
+    )
+    .unwrap();
+}
+
+#[test]
+fn conformance_bootrom() {
+    check_bus_component(
+        || Box::new(BootRom::with_size(vec![0x01234567; 4], 0x100, 0x0).unwrap()),
+        BusExpectations::new()
+            .with_category(StorageType::Readonly)
+            .with_writable(false)
+            .with_out_of_range_exceptions(false),
+    )
+    .unwrap();
+}
+
+#[test]
+fn conformance_shared_mem() {
+    check_bus_component(
+        || Box::new(SharedMem::new(Arc::new(Mutex::new(vec![0; 0x40])), 0x0, true).unwrap()),
+        BusExpectations::new().with_size(0x100),
+    )
+    .unwrap();
+}
+
+/// Component whose read values change at each read
+struct Unstable(u32);
+
+impl Bus for Unstable {
+    fn name(&self) -> &'static str {
+        "Unstable"
+    }
+
+    fn metadata(&self) -> [u32; 8] {
+        DeviceMetadata::new(0x0, 0x10, MemoryType::Ram.into(), None, None).encode()
+    }
+
+    fn read(&mut self, _addr: u32, _ex: &mut u16) -> u32 {
+        self.0 += 1;
+        self.0
+    }
+
+    fn write(&mut self, _addr: u32, _word: u32, _ex: &mut u16) {}
+
+    fn reset(&mut self) {}
+}
+
+#[test]
+fn conformance_failure() {
+    let result = check_bus_component(|| Box::new(Unstable(0)), BusExpectations::new());
+
+    assert_eq!(
+        result,
+        Err("Component 'Unstable': out-of-range read at address 0x00000010 did not raise an exception".to_string())
+    );
+
+    let result = check_bus_component(
+        || Box::new(Unstable(0)),
+        BusExpectations::new().with_out_of_range_exceptions(false),
+    );
+
+    assert!(result.unwrap_err().contains("state after a reset differs"));
+}
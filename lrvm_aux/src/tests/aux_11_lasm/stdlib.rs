@@ -0,0 +1,215 @@
+use crate::display::BufferedDisplay;
+use crate::storage::BootRom;
+use crate::volatile_mem::Ram;
+use lrvm::board::MotherBoard;
+use lrvm_tools::bytes::words_to_bytes;
+use lrvm_tools::debug::{exec_vm, RunConfig};
+use lrvm_tools::lasm::{self, stdlib};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// Run a driver followed by a routine, with a RAM at 0x1000 and a 16-bytes buffered display at 0x2000
+/// Returns the motherboard and the messages received by the display
+fn run(driver: &str, routine: &str) -> (MotherBoard, Vec<String>) {
+    let source = format!("cpy ssp, 0x2000\n{}\n{}", driver, routine);
+
+    let program = lasm::assemble_words(&source)
+        .unwrap_or_else(|err| panic!("Failed to assemble the driver: {}", err));
+
+    let lines = Rc::new(RefCell::new(vec![]));
+    let lines_sink = Rc::clone(&lines);
+
+    let (motherboard, state) = exec_vm(
+        vec![
+            Box::new(BootRom::with_size(program, 0x1000, 0x0).unwrap()),
+            Box::new(Ram::new(0x1000, 0x1).unwrap()),
+            Box::new(
+                BufferedDisplay::new(
+                    0x10,
+                    Box::new(move |msg| {
+                        lines_sink.borrow_mut().push(
+                            msg.expect("Invalid UTF-8 message received")
+                                .trim_end_matches(char::from(0))
+                                .to_string(),
+                        )
+                    }),
+                    0x2,
+                )
+                .unwrap(),
+            ),
+        ],
+        RunConfig::halt_on_ex(),
+    );
+
+    assert!(
+        state.ex.is_none(),
+        "Unexpected exception occurred while running the VM!"
+    );
+
+    let lines = lines.borrow().clone();
+    (motherboard, lines)
+}
+
+/// Read words from the memory
+fn read_words(motherboard: &mut MotherBoard, addr: u32, count: u32) -> Vec<u32> {
+    motherboard.map(|mem| {
+        (0..count)
+            .map(|i| {
+                let mut ex = 0;
+                let word = mem.read(addr + i * 4, &mut ex);
+                assert_eq!(ex, 0, "Exception occurred while reading the memory");
+                word
+            })
+            .collect()
+    })
+}
+
+#[test]
+fn memcpy() {
+    let (mut motherboard, _) = run(
+        "
+        cpy rr0, 0x1000
+        cpy rr1, source
+        cpy rr2, 12
+        call memcpy
+        halt
+
+        source:
+        #d32 0x11111111
+        #d32 0x22222222
+        #d32 0x33333333
+        #d32 0x44444444
+        ",
+        stdlib::MEMCPY,
+    );
+
+    assert_eq!(
+        read_words(&mut motherboard, 0x1000, 4),
+        vec![0x1111_1111, 0x2222_2222, 0x3333_3333, 0]
+    );
+}
+
+#[test]
+fn memset() {
+    let (mut motherboard, _) = run(
+        "
+        cpy rr0, 0x1004
+        cpy rr1, 0xDEAD
+        shl rr1, 16
+        add rr1, 0xBEEF
+        cpy rr2, 8
+        call memset
+        halt
+        ",
+        stdlib::MEMSET,
+    );
+
+    assert_eq!(
+        read_words(&mut motherboard, 0x1000, 4),
+        vec![0, 0xDEAD_BEEF, 0xDEAD_BEEF, 0]
+    );
+}
+
+#[test]
+fn strlen() {
+    let (mut motherboard, _) = run(
+        "
+        cpy rr0, hello
+        call strlen
+        cpy a0, rr0
+
+        cpy rr0, hello_world
+        call strlen
+        cpy a1, rr0
+
+        cpy rr0, empty
+        call strlen
+        cpy a2, rr0
+        halt
+
+        hello:
+        #d32 0x48656C6C
+        #d32 0x6F000000
+
+        hello_world:
+        #d32 0x48656C6C
+        #d32 0x6F20776F
+        #d32 0x726C6421
+        #d32 0x00000000
+
+        empty:
+        #d32 0x00000000
+        ",
+        stdlib::STRLEN,
+    );
+
+    let regs = &motherboard.cpu().regs;
+    assert_eq!(regs.a[0..3], [5, 12, 0]);
+}
+
+#[test]
+fn print_str() {
+    let (_, lines) = run(
+        "
+        cpy rr1, 0x2000
+        cpy rr2, 0x10
+
+        cpy rr0, hi
+        call print_str
+
+        cpy rr0, hello
+        call print_str
+        halt
+
+        hi:
+        #d32 0x48692100
+
+        hello:
+        #d32 0x48656C6C
+        #d32 0x6F20776F
+        #d32 0x726C642C
+        #d32 0x204C5256
+        #d32 0x4D210000
+        ",
+        stdlib::PRINT_STR,
+    );
+
+    // The display's buffer can only contain 12 bytes, so the second string is printed in two chunks
+    assert_eq!(lines, vec!["Hi!", "Hello world,", " LRVM!"]);
+}
+
+#[test]
+fn itoa() {
+    let (mut motherboard, _) = run(
+        "
+        cpy rr0, 0
+        cpy rr1, 0x1000
+        call itoa
+        cpy a0, rr0
+
+        cpy rr0, 0x0FF
+        cpy rr1, 0x1010
+        call itoa
+        cpy a1, rr0
+
+        cpy rr0, 0xFFFF
+        shl rr0, 16
+        add rr0, 0xFFFF
+        cpy rr1, 0x1020
+        call itoa
+        cpy a2, rr0
+        halt
+        ",
+        stdlib::ITOA,
+    );
+
+    assert_eq!(motherboard.cpu().regs.a[0..3], [1, 3, 10]);
+
+    for (addr, expected) in &[(0x1000, "0"), (0x1010, "255"), (0x1020, "4294967295")] {
+        let bytes = words_to_bytes(read_words(&mut motherboard, *addr, 3));
+        let len = bytes.iter().position(|byte| *byte == 0).unwrap();
+
+        assert_eq!(&String::from_utf8_lossy(&bytes[..len]), expected);
+        assert!(bytes[len..].iter().all(|byte| *byte == 0));
+    }
+}
@@ -0,0 +1,43 @@
+use crate::storage::BootRom;
+use crate::volatile_mem::Ram;
+use lrvm_tools::asm::{cst, ExceptionTable, ExtInstr, Instr, Program, Reg};
+use lrvm_tools::debug::{exec_vm, RunConfig};
+use lrvm_tools::exceptions::NativeException;
+
+#[test]
+fn exception_table() {
+    // Layout: main code (6 words), handler (8 words), dispatcher
+    let handler_addr = 6 * 4;
+    let dispatcher_addr = handler_addr + 8 * 4;
+
+    let table = ExceptionTable::new()
+        .handler(NativeException::DivisionOrModByZero.code(), handler_addr)
+        .handler(NativeException::UnknownOpCode(0).code(), 0x0000);
+
+    let mut program = Program::from_instr(table.install(u32::from(dispatcher_addr)));
+    program.append(Instr::Cpy(Reg::a0, 10_u16.into()).into());
+    program.append(Instr::Div(Reg::a0, 0_u8.into(), cst::DIV_ZRO_FRB.into()).into());
+    program.append(Instr::Halt().into());
+    assert_eq!(program.size() * 4, handler_addr as usize);
+
+    program.append_ext(ExtInstr::WriteAddrLit(0x1000, 0xDEADBEEF));
+    program.append(Instr::Halt().into());
+    assert_eq!(program.size() * 4, dispatcher_addr as usize);
+
+    let dispatcher = table.dispatcher();
+    assert_eq!(dispatcher.size(), table.dispatcher_len());
+    program.append_all(dispatcher.0);
+
+    let (mut vm, state) = exec_vm(
+        vec![
+            Box::new(BootRom::with_size(program.encode_words(), 0x1000, 0x0).unwrap()),
+            Box::new(Ram::new(0x1000, 0x1).unwrap()),
+        ],
+        RunConfig::quiet().with_cycles_limit(Some(100)),
+    );
+
+    assert!(state.cycles < 100, "The VM did not halt");
+
+    let mut ex = 0;
+    assert_eq!(vm.map(|mem| mem.read(0x1000, &mut ex)), 0xDEADBEEF);
+}
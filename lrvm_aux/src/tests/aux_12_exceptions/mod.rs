@@ -0,0 +1 @@
+pub mod exception_table;
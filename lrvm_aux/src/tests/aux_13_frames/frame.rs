@@ -0,0 +1,117 @@
+use crate::storage::BootRom;
+use crate::volatile_mem::Ram;
+use lrvm_tools::asm::frame::Frame;
+use lrvm_tools::asm::{ExtInstr, Instr, Program, Reg};
+use lrvm_tools::debug::{exec_vm, RunConfig};
+
+/// Build a function from its frame and body
+fn function(frame: &Frame, body: Vec<Instr>) -> Vec<Instr> {
+    let mut instr = frame.prologue();
+    instr.extend(body);
+    instr.extend(frame.epilogue());
+    instr
+}
+
+/// Write a register's value at an address
+fn store(addr: u32, reg: Reg) -> Vec<Instr> {
+    ExtInstr::WriteAddr(addr, reg).to_instr()
+}
+
+#[test]
+fn nested_frames() {
+    let mut program = Program::new();
+
+    // Innermost function: overwrites 'a0', which it saves
+    let f3_addr = program.size() as u16 * 4;
+    let f3 = Frame::new(0, &[Reg::a0]);
+    program.append_all(
+        Program::from_instr(function(&f3, vec![Instr::Cpy(Reg::a0, 999_u16.into())])).0,
+    );
+
+    // Middle function: overwrites 'a0' and 'a1', stores them in its local slots and calls the innermost one
+    let f2_addr = program.size() as u16 * 4;
+    let f2 = Frame::new(2, &[Reg::a0, Reg::a1]);
+    let (addr, add, mul) = f2.local_addr(1);
+
+    let mut body = vec![
+        Instr::Cpy(Reg::a0, 100_u16.into()),
+        Instr::Cpy(Reg::a1, 200_u16.into()),
+        Instr::Cpy(Reg::avr, Reg::a1.into()),
+        Instr::Wea(addr, add, mul),
+        Instr::Call(f3_addr.into()),
+    ];
+    body.extend(store(0x1010, Reg::a0));
+    body.extend(store(0x1014, Reg::a1));
+    body.push(Instr::Lea(addr, add, mul));
+    body.push(Instr::Cpy(Reg::a2, Reg::avr.into()));
+    body.extend(store(0x1018, Reg::a2));
+
+    program.append_all(Program::from_instr(function(&f2, body)).0);
+
+    // Outer function: same as the middle one, with a single local slot
+    let f1_addr = program.size() as u16 * 4;
+    let f1 = Frame::new(1, &[Reg::a0, Reg::a1]);
+    let (addr, add, mul) = f1.local_addr(0);
+
+    let mut body = vec![
+        Instr::Cpy(Reg::a0, 10_u16.into()),
+        Instr::Cpy(Reg::a1, 20_u16.into()),
+        Instr::Cpy(Reg::avr, 0x1234_u16.into()),
+        Instr::Wea(addr, add, mul),
+        Instr::Call(f2_addr.into()),
+    ];
+    body.extend(store(0x1008, Reg::a0));
+    body.extend(store(0x100C, Reg::a1));
+    body.push(Instr::Lea(addr, add, mul));
+    body.push(Instr::Cpy(Reg::a2, Reg::avr.into()));
+    body.extend(store(0x101C, Reg::a2));
+
+    program.append_all(Program::from_instr(function(&f1, body)).0);
+
+    // Entry point
+    let main = program.size();
+
+    program.append_ext(ExtInstr::SetReg(Reg::ssp, 0x2000));
+    program.append(Instr::Cpy(Reg::a0, 1_u16.into()).into());
+    program.append(Instr::Cpy(Reg::a1, 2_u16.into()).into());
+    program.append(Instr::Call(f1_addr.into()).into());
+    program.append_all(Program::from_instr(store(0x1000, Reg::a0)).0);
+    program.append_all(Program::from_instr(store(0x1004, Reg::a1)).0);
+    program.append(Instr::Cpy(Reg::a2, Reg::ssp.into()).into());
+    program.append_all(Program::from_instr(store(0x1020, Reg::a2)).0);
+    program.append(Instr::Halt().into());
+
+    program.with_entry_jump(main);
+
+    let (mut vm, state) = exec_vm(
+        vec![
+            Box::new(BootRom::with_size(program.encode_words(), 0x1000, 0x0).unwrap()),
+            Box::new(Ram::new(0x1000, 0x1).unwrap()),
+        ],
+        RunConfig::quiet().with_cycles_limit(Some(1000)),
+    );
+
+    assert!(state.cycles < 1000, "The VM did not halt");
+
+    let expected = [
+        (0x1000, 1),
+        (0x1004, 2),
+        (0x1008, 10),
+        (0x100C, 20),
+        (0x1010, 100),
+        (0x1014, 200),
+        (0x1018, 200),
+        (0x101C, 0x1234),
+        (0x1020, 0x2000),
+    ];
+
+    for (addr, value) in expected.iter() {
+        let mut ex = 0;
+        assert_eq!(
+            vm.map(|mem| mem.read(*addr, &mut ex)),
+            *value,
+            "Unexpected value at address {:#06X}",
+            addr
+        );
+    }
+}
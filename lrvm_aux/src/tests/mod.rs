@@ -3,3 +3,11 @@ pub mod aux_02_volatile_mem;
 pub mod aux_03_display;
 pub mod aux_04_keyboard;
 pub mod aux_05_time;
+pub mod aux_06_debug;
+pub mod aux_07_access;
+pub mod aux_08_bridge;
+pub mod aux_09_builder;
+pub mod aux_10_conformance;
+pub mod aux_11_lasm;
+pub mod aux_12_exceptions;
+pub mod aux_13_frames;
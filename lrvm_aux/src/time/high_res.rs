@@ -0,0 +1,72 @@
+//! The high-resolution clock component provides a nanosecond counter, to measure performances.  
+//! See [`HighResClock`] for more details.
+
+use lrvm::board::Bus;
+use lrvm_tools::exceptions::AuxHwException;
+use lrvm_tools::metadata::{ClockType, DeviceMetadata};
+use std::time::Instant;
+
+/// The high-resolution clock component is a 2-word-long readonly component.  
+///
+/// It contains the number of nanoseconds elapsed since the component was last reset, encoded on 64 bits:
+/// the first word contains the high half of the counter, and the second word its low half.  
+///
+/// Reading the first word takes a snapshot of the counter, and the next read of the second word returns the low half of this snapshot,
+/// so reading the high word then the low word always gives a consistent value, even if the low half overflows between the two reads.  
+/// Reading the second word without reading the first one before returns the low half of the current counter.
+pub struct HighResClock {
+    hw_id: u64,
+    reset_at: Instant,
+    latched_low: Option<u32>,
+}
+
+impl HighResClock {
+    /// Create a high-resolution clock component, whose counter starts at 0.
+    pub fn new(hw_id: u64) -> Self {
+        Self {
+            hw_id,
+            reset_at: Instant::now(),
+            latched_low: None,
+        }
+    }
+
+    /// (Internal) Get the current value of the counter
+    fn nanos(&self) -> u64 {
+        // The counter only overflows after 584 years
+        self.reset_at.elapsed().as_nanos() as u64
+    }
+}
+
+impl Bus for HighResClock {
+    fn name(&self) -> &'static str {
+        "High-Resolution Clock"
+    }
+
+    fn metadata(&self) -> [u32; 8] {
+        DeviceMetadata::new(self.hw_id, 8, ClockType::HighResolution.wrap(), None, None).encode()
+    }
+
+    fn read(&mut self, addr: u32, _ex: &mut u16) -> u32 {
+        match addr {
+            0x00 => {
+                let nanos = self.nanos();
+                self.latched_low = Some(nanos as u32);
+                (nanos >> 32) as u32
+            }
+            0x04 => self
+                .latched_low
+                .take()
+                .unwrap_or_else(|| self.nanos() as u32),
+            _ => unreachable!(), // Safety guarantee
+        }
+    }
+
+    fn write(&mut self, _addr: u32, _word: u32, ex: &mut u16) {
+        *ex = AuxHwException::MemoryNotWritable.into();
+    }
+
+    fn reset(&mut self) {
+        self.reset_at = Instant::now();
+        self.latched_low = None;
+    }
+}
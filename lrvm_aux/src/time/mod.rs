@@ -1,3 +1,5 @@
+mod high_res;
 mod realtime;
 
+pub use high_res::HighResClock;
 pub use realtime::RealtimeClock;
@@ -1,3 +1,5 @@
 mod ram;
+mod shared;
 
 pub use ram::Ram;
+pub use shared::SharedMem;
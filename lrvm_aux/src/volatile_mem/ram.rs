@@ -88,10 +88,37 @@ impl Ram {
         })
     }
 
+    /// Set the RAM's hardware identifier
+    pub fn with_hw_id(mut self, hw_id: u64) -> Self {
+        self.hw_id = hw_id;
+        self
+    }
+
     /// Get the RAM's size
     pub fn size(&self) -> u32 {
         self.size
     }
+
+    /// Get the address (relative to the RAM's start) of the first word equal to the provided value
+    pub fn search_word(&self, value: u32) -> Option<u32> {
+        self.words()
+            .position(|word| *word == value)
+            .map(|index| index as u32 * 4)
+    }
+
+    /// Get the addresses (relative to the RAM's start) of all words equal to the provided value, in ascending order
+    pub fn search_all_words(&self, value: u32) -> Vec<u32> {
+        self.words()
+            .enumerate()
+            .filter(|(_, word)| **word == value)
+            .map(|(index, _)| index as u32 * 4)
+            .collect()
+    }
+
+    /// (Internal) Iterate over the words accessible through the bus
+    fn words(&self) -> impl Iterator<Item = &u32> {
+        self.storage.iter().take(self.size as usize)
+    }
 }
 
 impl Bus for Ram {
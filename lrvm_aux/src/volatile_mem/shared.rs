@@ -0,0 +1,141 @@
+//! The shared memory component offers a storage shared between the VM and host code.
+//! See [`SharedMem`] for more details.
+
+use lrvm::board::Bus;
+use lrvm_tools::exceptions::{AuxHwException, ExceptionSink};
+use lrvm_tools::metadata::{DeviceMetadata, MemoryType};
+use std::convert::TryInto;
+use std::sync::{Arc, Mutex};
+
+/// The shared memory component exposes a host-owned buffer to the VM, allowing host threads to stream data in and out
+/// without stopping the CPU.
+///
+/// The buffer is locked for the duration of each single word access (read or write), and released right after. This means
+/// the host can lock the buffer at any time without deadlocking the VM, but it also means multi-word transfers are not atomic:
+/// if the host needs to publish several words at once, it should write them first and then set a flag word the guest polls.
+///
+/// The component's size is the buffer's length at creation. If the host shrinks the buffer afterwards, accesses outside
+/// of it will raise a physical read/write exception.
+///
+/// When it receives a RESET request from the motherboard, the buffer is zeroed only if the component was created with `zero_on_reset`.
+pub struct SharedMem {
+    buffer: Arc<Mutex<Vec<u32>>>,
+    size: u32,
+    zero_on_reset: bool,
+    hw_id: u64,
+}
+
+impl SharedMem {
+    /// Create a new shared memory component from a shared buffer
+    /// Returns an error message if the buffer is empty or if its size in bytes cannot be represented in 32 bits.
+    pub fn new(
+        buffer: Arc<Mutex<Vec<u32>>>,
+        hw_id: u64,
+        zero_on_reset: bool,
+    ) -> Result<Self, &'static str> {
+        let len = buffer
+            .lock()
+            .map_err(|_| "Shared buffer's lock is poisoned")?
+            .len();
+
+        let size: u32 = len
+            .checked_mul(4)
+            .and_then(|size| size.try_into().ok())
+            .ok_or("Shared buffer's size cannot be larger than 2^32 bytes")?;
+
+        if size == 0 {
+            return Err("Shared buffer's size cannot be 0");
+        }
+
+        Ok(Self {
+            buffer,
+            size: size / 4,
+            zero_on_reset,
+            hw_id,
+        })
+    }
+
+    /// Create a mirror of this component, backed by the same buffer but with its own hardware identifier.  
+    /// This allows to map the same memory at several addresses: writes through one mirror are immediately visible through the others.
+    pub fn mirror(&self, hw_id: u64) -> Self {
+        Self {
+            buffer: Arc::clone(&self.buffer),
+            size: self.size,
+            zero_on_reset: self.zero_on_reset,
+            hw_id,
+        }
+    }
+
+    /// Set the shared memory's hardware identifier
+    pub fn with_hw_id(mut self, hw_id: u64) -> Self {
+        self.hw_id = hw_id;
+        self
+    }
+
+    /// Get a handle to the shared buffer
+    pub fn buffer(&self) -> Arc<Mutex<Vec<u32>>> {
+        Arc::clone(&self.buffer)
+    }
+
+    /// Get the shared memory's size
+    pub fn size(&self) -> u32 {
+        self.size
+    }
+}
+
+impl Bus for SharedMem {
+    fn name(&self) -> &'static str {
+        "Shared Memory"
+    }
+
+    fn metadata(&self) -> [u32; 8] {
+        DeviceMetadata::new(
+            self.hw_id,
+            self.size * 4,
+            MemoryType::Ram.into(),
+            None,
+            None,
+        )
+        .encode()
+    }
+
+    fn read(&mut self, addr: u32, ex: &mut u16) -> u32 {
+        let mut ex = ExceptionSink::new(ex);
+
+        match self.buffer.lock() {
+            Ok(buffer) => match buffer.get(addr as usize / 4) {
+                Some(word) => *word,
+                None => {
+                    ex.raise(AuxHwException::GenericPhysicalReadError);
+                    0
+                }
+            },
+            Err(_) => {
+                ex.raise(AuxHwException::UnspecifiedSyncError);
+                0
+            }
+        }
+    }
+
+    fn write(&mut self, addr: u32, word: u32, ex: &mut u16) {
+        let mut ex = ExceptionSink::new(ex);
+
+        match self.buffer.lock() {
+            Ok(mut buffer) => match buffer.get_mut(addr as usize / 4) {
+                Some(slot) => *slot = word,
+                None => ex.raise(AuxHwException::GenericPhysicalWriteError),
+            },
+            Err(_) => ex.raise(AuxHwException::UnspecifiedSyncError),
+        }
+    }
+
+    fn reset(&mut self) {
+        if self.zero_on_reset {
+            if let Ok(mut buffer) = self.buffer.lock() {
+                for word in buffer.iter_mut() {
+                    *word = 0;
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,62 @@
+//! Build script embedding the version of the CustomAsm library in use, as found in the lockfile.
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+fn main() {
+    let version = find_lockfile()
+        .and_then(|lockfile| {
+            println!("cargo:rerun-if-changed={}", lockfile.display());
+            customasm_version(&fs::read_to_string(lockfile).ok()?)
+        })
+        .unwrap_or_else(|| "unknown".to_string());
+
+    println!("cargo:rustc-env=LRVM_CUSTOMASM_VERSION={}", version);
+}
+
+/// Find the lockfile of the crate being built, which is located in the workspace's root directory
+/// (usually an ancestor of both this crate's directory and the build directory)
+fn find_lockfile() -> Option<PathBuf> {
+    ["CARGO_MANIFEST_DIR", "OUT_DIR"]
+        .iter()
+        .filter_map(|var| env::var(var).ok())
+        .flat_map(|dir| {
+            Path::new(&dir)
+                .ancestors()
+                .map(|dir| dir.join("Cargo.lock"))
+                .collect::<Vec<_>>()
+        })
+        .find(|lockfile| lockfile.is_file())
+}
+
+/// Get the version of the CustomAsm package from a lockfile's content.
+/// For Git sources, the commit's hash is added as build metadata.
+fn customasm_version(lockfile: &str) -> Option<String> {
+    let package = lockfile.split("[[package]]").find(|package| {
+        package
+            .lines()
+            .any(|line| line.trim() == "name = \"customasm\"")
+    })?;
+
+    let field = |name: &str| {
+        package.lines().find_map(|line| {
+            let value = line
+                .trim()
+                .strip_prefix(name)?
+                .trim_start()
+                .strip_prefix('=')?;
+            Some(value.trim().trim_matches('"').to_string())
+        })
+    };
+
+    let version = field("version")?;
+
+    match field("source")
+        .filter(|source| source.starts_with("git+"))
+        .and_then(|source| Some(source[source.rfind('#')? + 1..].to_string()))
+    {
+        Some(commit) => Some(format!("{}+{}", version, &commit[..commit.len().min(12)])),
+        None => Some(version),
+    }
+}
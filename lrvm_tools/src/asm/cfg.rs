@@ -0,0 +1,182 @@
+//! (Internal) Control flow analysis of strongly-typed programs.
+//! Unless specified otherwise, programs are considered to be loaded at address `0x00000000`.
+
+use super::{Instr, Program, ProgramWord, Reg, RegOrLit2};
+use std::collections::BTreeSet;
+
+/// Control flow of a single program word
+enum Flow {
+    /// Continue with the next word
+    Next,
+    /// Jump to the provided word (or to an unknown location)
+    Jump(Option<usize>),
+    /// Call the provided word (or an unknown location), then continue with the next word
+    Call(Option<usize>),
+    /// Either continue with the next word or skip it, depending on a condition
+    Branch,
+    /// Stop the execution
+    Stop,
+}
+
+/// Basic block: a suite of words with a single entry point and a single exit point
+pub(super) struct BasicBlock {
+    /// Index of the block's first word
+    pub start: usize,
+    /// Successors (index of the block and optional edge label)
+    pub successors: Vec<(usize, Option<&'static str>)>,
+    /// Does the block end with an unconditional jump?
+    pub ends_with_jump: bool,
+    /// Does the block jump to or call a location that can't be determined statically (or is outside the program)?
+    pub unknown_target: bool,
+}
+
+/// Get the control flow of a program word, considering the program is loaded at the provided address
+fn flow(index: usize, pword: &ProgramWord, len: usize, base_addr: u32) -> Flow {
+    // Get the word pointed by an absolute address, if it's inside the program
+    let abs_target = |addr: u16| {
+        let addr = u32::from(addr).checked_sub(base_addr)? as usize;
+
+        if addr % 4 == 0 && addr / 4 < len {
+            Some(addr / 4)
+        } else {
+            None
+        }
+    };
+
+    let instr = match pword {
+        ProgramWord::Instr(instr) => instr,
+        ProgramWord::Raw(_) => return Flow::Next,
+    };
+
+    match *instr {
+        Instr::Jpr(RegOrLit2::Lit(offset)) => {
+            let target = index as i64 * 4 + i64::from(offset as i16);
+
+            Flow::Jump(
+                if target >= 0 && target % 4 == 0 && ((target / 4) as usize) < len {
+                    Some((target / 4) as usize)
+                } else {
+                    None
+                },
+            )
+        }
+
+        Instr::Cpy(Reg::pc, RegOrLit2::Lit(addr)) | Instr::Lsm(RegOrLit2::Lit(addr)) => {
+            Flow::Jump(abs_target(addr))
+        }
+
+        Instr::Call(RegOrLit2::Lit(addr)) => Flow::Call(abs_target(addr)),
+        Instr::Call(RegOrLit2::Reg(_)) => Flow::Call(None),
+
+        Instr::Jpr(RegOrLit2::Reg(_)) | Instr::Lsm(RegOrLit2::Reg(_)) => Flow::Jump(None),
+
+        Instr::If(_) | Instr::IfN(_) | Instr::If2(_, _, _) => Flow::Branch,
+
+        Instr::Halt() | Instr::Reset(_) => Flow::Stop,
+
+        // Any other write to the PC register jumps to an unknown location
+        Instr::Cpy(Reg::pc, _)
+        | Instr::Add(Reg::pc, _)
+        | Instr::Sub(Reg::pc, _)
+        | Instr::Mul(Reg::pc, _)
+        | Instr::Div(Reg::pc, _, _)
+        | Instr::Mod(Reg::pc, _, _)
+        | Instr::And(Reg::pc, _)
+        | Instr::Bor(Reg::pc, _)
+        | Instr::Xor(Reg::pc, _)
+        | Instr::Shl(Reg::pc, _)
+        | Instr::Shr(Reg::pc, _)
+        | Instr::Lsa(Reg::pc, _, _)
+        | Instr::Srm(_, _, Reg::pc)
+        | Instr::Pop(Reg::pc)
+        | Instr::Hwd(Reg::pc, _, _)
+        | Instr::Cycles(Reg::pc)
+        | Instr::Ex(Reg::pc, _)
+        | Instr::Ex(_, Reg::pc) => Flow::Jump(None),
+
+        _ => Flow::Next,
+    }
+}
+
+/// Split a program into basic blocks
+pub(super) fn basic_blocks(prog: &Program) -> Vec<BasicBlock> {
+    basic_blocks_at(prog, 0)
+}
+
+/// Split a program loaded at the provided address into basic blocks
+pub(super) fn basic_blocks_at(prog: &Program, base_addr: u32) -> Vec<BasicBlock> {
+    let len = prog.size();
+
+    if len == 0 {
+        return vec![];
+    }
+
+    let flows: Vec<Flow> = prog
+        .prog_words()
+        .enumerate()
+        .map(|(i, pword)| flow(i, pword, len, base_addr))
+        .collect();
+
+    // Find the first word of each block
+    let mut leaders = BTreeSet::new();
+    leaders.insert(0);
+
+    for (i, flow) in flows.iter().enumerate() {
+        match flow {
+            Flow::Next => {}
+            Flow::Jump(target) | Flow::Call(target) => {
+                leaders.extend(target);
+                leaders.insert(i + 1);
+            }
+            Flow::Branch => {
+                leaders.insert(i + 1);
+                leaders.insert(i + 2);
+            }
+            Flow::Stop => {
+                leaders.insert(i + 1);
+            }
+        }
+    }
+
+    let leaders: Vec<usize> = leaders.into_iter().filter(|i| *i < len).collect();
+    let block_of = |word: usize| leaders.binary_search(&word).ok();
+
+    leaders
+        .iter()
+        .enumerate()
+        .map(|(block, start)| {
+            let end = leaders.get(block + 1).copied().unwrap_or(len);
+            let last = end - 1;
+
+            let successors = match flows[last] {
+                Flow::Next => vec![(block_of(last + 1), None)],
+                Flow::Jump(target) => vec![(target.and_then(block_of), None)],
+                Flow::Call(target) => vec![
+                    (target.and_then(block_of), Some("call")),
+                    (block_of(last + 1), None),
+                ],
+                Flow::Branch => vec![
+                    (block_of(last + 1), Some("true")),
+                    (block_of(last + 2), Some("false")),
+                ],
+                Flow::Stop => vec![],
+            };
+
+            let (ends_with_jump, unknown_target) = match flows[last] {
+                Flow::Jump(target) => (true, target.is_none()),
+                Flow::Call(target) => (false, target.is_none()),
+                Flow::Next | Flow::Branch | Flow::Stop => (false, false),
+            };
+
+            BasicBlock {
+                start: *start,
+                ends_with_jump,
+                unknown_target,
+                successors: successors
+                    .into_iter()
+                    .filter_map(|(block, label)| block.map(|block| (block, label)))
+                    .collect(),
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,69 @@
+use super::{InstrDecodingError, Program, ProgramWord};
+use crate::bytes::{bytes_be_to_u32, u32_to_bytes_be};
+use std::fmt;
+
+/// Error returned by [`Program::decode_verified`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ChecksumError {
+    /// The machine code is too short to contain a checksum word
+    Missing,
+    /// The checksum does not match the program's words
+    Mismatch { expected: u32, actual: u32 },
+    /// The program failed to decode
+    Decoding {
+        index: usize,
+        error: InstrDecodingError,
+    },
+}
+
+impl fmt::Display for ChecksumError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Missing => write!(f, "Program is too short to contain a checksum word"),
+            Self::Mismatch { expected, actual } => write!(
+                f,
+                "Checksum mismatch: program's words sum to {:#010X}, but the checksum word is {:#010X}",
+                actual, expected
+            ),
+            Self::Decoding { index, error } => write!(
+                f,
+                "Word {} (offset {:#010X}): {}",
+                index,
+                index * 4,
+                error
+            ),
+        }
+    }
+}
+
+impl Program {
+    /// Append a checksum word to the program, containing the wrapping sum of its words (see [`Program::sum_words`])
+    pub fn append_checksum(&mut self) -> &mut Self {
+        let checksum = self.sum_words();
+        self.append(ProgramWord::Raw(u32_to_bytes_be(checksum)))
+    }
+
+    /// Decode a machine code ending with a checksum word (see [`Program::append_checksum`]).
+    /// The checksum word is verified and stripped from the returned program. Raw data is allowed.
+    pub fn decode_verified(bytes: impl AsRef<[u8]>) -> Result<Program, ChecksumError> {
+        let bytes = bytes.as_ref();
+
+        if bytes.len() < 4 {
+            return Err(ChecksumError::Missing);
+        }
+
+        let (code, checksum) = bytes.split_at(bytes.len() - 4);
+
+        let prog = Program::decode(code, false)
+            .map_err(|(index, error)| ChecksumError::Decoding { index, error })?;
+
+        let expected = bytes_be_to_u32([checksum[0], checksum[1], checksum[2], checksum[3]]);
+        let actual = prog.sum_words();
+
+        if expected != actual {
+            return Err(ChecksumError::Mismatch { expected, actual });
+        }
+
+        Ok(prog)
+    }
+}
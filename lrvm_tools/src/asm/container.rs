@@ -0,0 +1,123 @@
+use super::Program;
+use crate::bytes::{bytes_be_to_u32, u32_to_bytes_be};
+use std::convert::{TryFrom, TryInto};
+use std::fmt;
+
+/// Magic bytes at the beginning of each container
+pub const CONTAINER_MAGIC: [u8; 4] = *b"LRVM";
+
+/// Version of the container format produced by [`Program::to_container`]
+pub const CONTAINER_VERSION: u32 = 1;
+
+/// Size of a container's header, in bytes
+pub const CONTAINER_HEADER_SIZE: usize = 16;
+
+/// Metadata stored in a container's header (see [`Program::to_container`])
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct ContainerMeta {
+    /// Address of the program's entry point
+    pub entry_point: u32,
+}
+
+/// Container building or parsing error
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ContainerError {
+    /// The container is smaller than its header
+    TooShort,
+    /// The container does not start with the expected magic bytes
+    BadMagic,
+    /// The container's format version is not supported
+    UnsupportedVersion { version: u32 },
+    /// The code section's size does not match the container's content
+    SizeMismatch { expected: u32, found: usize },
+    /// The code section is too large for its size to fit in the header (4 GiB or more)
+    CodeTooLarge { size: usize },
+    /// The code section could not be decoded
+    InvalidCode { word: usize },
+}
+
+impl Program {
+    /// Wrap the program in a container, made of a header followed by the code section.
+    ///
+    /// The header is made of 4 big-endian words: the magic bytes (`LRVM`), the format version,
+    /// the entry point's address and the code section's size in bytes.
+    /// Returns an error if the code section is too large for its size to fit in the header.
+    pub fn to_container(&self, meta: ContainerMeta) -> Result<Vec<u8>, ContainerError> {
+        let code = self.encode();
+        let code_size = u32::try_from(code.len())
+            .map_err(|_| ContainerError::CodeTooLarge { size: code.len() })?;
+
+        let mut out = Vec::with_capacity(CONTAINER_HEADER_SIZE + code.len());
+        out.extend_from_slice(&CONTAINER_MAGIC);
+        out.extend_from_slice(&u32_to_bytes_be(CONTAINER_VERSION));
+        out.extend_from_slice(&u32_to_bytes_be(meta.entry_point));
+        out.extend_from_slice(&u32_to_bytes_be(code_size));
+        out.extend_from_slice(&code);
+        Ok(out)
+    }
+
+    /// Parse a container produced by [`Program::to_container`].
+    /// Words of the code section that are not valid instructions are kept as raw data.
+    pub fn from_container(container: &[u8]) -> Result<(Program, ContainerMeta), ContainerError> {
+        if container.len() < CONTAINER_HEADER_SIZE {
+            return Err(ContainerError::TooShort);
+        }
+
+        let word = |i: usize| bytes_be_to_u32(container[i * 4..i * 4 + 4].try_into().unwrap());
+
+        if container[0..4] != CONTAINER_MAGIC {
+            return Err(ContainerError::BadMagic);
+        }
+
+        let version = word(1);
+
+        if version != CONTAINER_VERSION {
+            return Err(ContainerError::UnsupportedVersion { version });
+        }
+
+        let code = &container[CONTAINER_HEADER_SIZE..];
+        let code_size = word(3);
+
+        if code.len() != code_size as usize || code.len() % 4 != 0 {
+            return Err(ContainerError::SizeMismatch {
+                expected: code_size,
+                found: code.len(),
+            });
+        }
+
+        let prog = Program::decode(code, false)
+            .map_err(|(word, _)| ContainerError::InvalidCode { word })?;
+
+        Ok((
+            prog,
+            ContainerMeta {
+                entry_point: word(2),
+            },
+        ))
+    }
+}
+
+impl fmt::Display for ContainerError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::TooShort => write!(f, "Container is smaller than its header"),
+            Self::BadMagic => write!(f, "Container does not start with the expected magic bytes"),
+            Self::UnsupportedVersion { version } => {
+                write!(f, "Unsupported container format version: {}", version)
+            }
+            Self::SizeMismatch { expected, found } => write!(
+                f,
+                "Code section's size is {} bytes but {} bytes were found (must be a multiple of 4 bytes)",
+                expected, found
+            ),
+            Self::CodeTooLarge { size } => write!(
+                f,
+                "Code section is {} bytes long, which exceeds the maximum size of 4 GiB",
+                size
+            ),
+            Self::InvalidCode { word } => {
+                write!(f, "Failed to decode the code section at word {}", word)
+            }
+        }
+    }
+}
@@ -0,0 +1,41 @@
+//! The [`CachedDecoder`] memoizes instruction decoding, which speeds up bulk disassembly of programs with recurring words.
+
+use super::{Instr, InstrDecodingError};
+use crate::bytes::{bytes_be_to_u32, u32_to_bytes_be};
+use std::collections::HashMap;
+
+/// Memoizing instruction decoder
+#[derive(Default, Debug, Clone)]
+pub struct CachedDecoder {
+    cache: HashMap<u32, Result<Instr, InstrDecodingError>>,
+}
+
+impl CachedDecoder {
+    /// Create a decoder with an empty cache
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Decode an instruction word, using the cached result if the same word was already decoded
+    pub fn decode_word(&mut self, word: u32) -> Result<Instr, InstrDecodingError> {
+        *self
+            .cache
+            .entry(word)
+            .or_insert_with(|| Instr::decode(u32_to_bytes_be(word)))
+    }
+
+    /// Decode an instruction, using the cached result if the same instruction was already decoded
+    pub fn decode(&mut self, bytes: [u8; 4]) -> Result<Instr, InstrDecodingError> {
+        self.decode_word(bytes_be_to_u32(bytes))
+    }
+
+    /// Get the number of cached words
+    pub fn cached(&self) -> usize {
+        self.cache.len()
+    }
+
+    /// Clear the cache
+    pub fn clear(&mut self) {
+        self.cache.clear();
+    }
+}
@@ -0,0 +1,303 @@
+//! All-in-one disassembler turning machine code into an annotated LASM listing.
+
+use super::cfg::basic_blocks_at;
+use super::{ExtInstr, Instr, InstrDecodingError, Program, ProgramWord, Reg, RegOrLit2};
+use std::collections::BTreeSet;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// How [`disassemble_to_string`] handles words that are not valid instructions
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RawData {
+    /// Fail on the first invalid instruction
+    Forbid,
+    /// Output invalid instructions as raw data
+    Keep,
+    /// Output invalid instructions as raw data, as well as all the instructions that can't be reached from the program's first word.
+    /// Falls back to [`RawData::Keep`] if a jump or call target can't be determined statically.
+    Infer,
+}
+
+/// Options for [`disassemble_to_string`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DisasmOptions {
+    pub base_addr: u32,
+    pub labels: bool,
+    pub raw_data: RawData,
+    pub fold_ext: bool,
+}
+
+impl DisasmOptions {
+    /// Create default options (program loaded at address `0x00000000`, labels enabled, raw data kept, no folding)
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the address the program is loaded at
+    pub fn base_addr(mut self, base_addr: u32) -> Self {
+        self.base_addr = base_addr;
+        self
+    }
+
+    /// Generate labels for jump and call targets
+    pub fn labels(mut self, labels: bool) -> Self {
+        self.labels = labels;
+        self
+    }
+
+    /// Set how words that are not valid instructions are handled
+    pub fn raw_data(mut self, raw_data: RawData) -> Self {
+        self.raw_data = raw_data;
+        self
+    }
+
+    /// Fold sequences of instructions generated by an extended instruction into a single line.
+    /// As extended instructions have no LASM syntax, the output can't be assembled again if this option is enabled.
+    pub fn fold_ext(mut self, fold_ext: bool) -> Self {
+        self.fold_ext = fold_ext;
+        self
+    }
+}
+
+impl Default for DisasmOptions {
+    fn default() -> Self {
+        Self {
+            base_addr: 0,
+            labels: true,
+            raw_data: RawData::Keep,
+            fold_ext: false,
+        }
+    }
+}
+
+/// Error returned by [`disassemble_to_string`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DisasmError {
+    /// Index of the faulty word
+    pub index: usize,
+    /// Decoding error
+    pub error: InstrDecodingError,
+}
+
+impl fmt::Display for DisasmError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Word {} (offset {:#010X}): {}",
+            self.index,
+            self.index * 4,
+            self.error
+        )
+    }
+}
+
+/// Disassemble a machine code into an annotated LASM listing, with one word (or folded extended instruction) per line
+/// followed by its address.
+pub fn disassemble_to_string(bytes: &[u8], options: DisasmOptions) -> Result<String, DisasmError> {
+    let prog = Program::decode(bytes, options.raw_data == RawData::Forbid)
+        .map_err(|(index, error)| DisasmError { index, error })?;
+
+    let words: Vec<ProgramWord> = prog.prog_words().copied().collect();
+    let addr_of = |index: usize| options.base_addr.wrapping_add(index as u32 * 4);
+
+    // Get the word pointed by a literal target, if it's inside the program
+    let target_of = |index: usize| -> Option<usize> {
+        let target = match words[index] {
+            ProgramWord::Instr(Instr::Jpr(RegOrLit2::Lit(offset))) => {
+                addr_of(index).wrapping_add(offset as i16 as u32)
+            }
+            ProgramWord::Instr(Instr::Cpy(Reg::pc, RegOrLit2::Lit(addr)))
+            | ProgramWord::Instr(Instr::Lsm(RegOrLit2::Lit(addr)))
+            | ProgramWord::Instr(Instr::Call(RegOrLit2::Lit(addr))) => u32::from(addr),
+            _ => return None,
+        };
+
+        let offset = target.checked_sub(options.base_addr)? as usize;
+
+        if offset % 4 == 0 && offset / 4 < words.len() {
+            Some(offset / 4)
+        } else {
+            None
+        }
+    };
+
+    let targets: BTreeSet<usize> = if options.labels {
+        (0..words.len()).filter_map(target_of).collect()
+    } else {
+        BTreeSet::new()
+    };
+
+    let label = |index: usize| format!("L_{:08X}", addr_of(index));
+
+    // Find the words that are considered as data
+    let mut data = vec![false; words.len()];
+
+    if options.raw_data == RawData::Infer {
+        let blocks = basic_blocks_at(&prog, options.base_addr);
+
+        // Returning from a function is covered by the edge following the matching call
+        let unknown_target = blocks.iter().enumerate().any(|(block, infos)| {
+            let last = blocks
+                .get(block + 1)
+                .map(|next| next.start)
+                .unwrap_or(words.len())
+                - 1;
+
+            infos.unknown_target && words[last] != ProgramWord::Instr(Instr::Pop(Reg::pc))
+        });
+
+        if !unknown_target {
+            let mut reachable = vec![false; blocks.len()];
+            let mut queue = vec![0];
+
+            while let Some(block) = queue.pop() {
+                if reachable.get(block) != Some(&false) {
+                    continue;
+                }
+
+                reachable[block] = true;
+                queue.extend(blocks[block].successors.iter().map(|(next, _)| *next));
+            }
+
+            for (block, reachable) in reachable.iter().enumerate() {
+                let end = blocks
+                    .get(block + 1)
+                    .map(|next| next.start)
+                    .unwrap_or(words.len());
+
+                for is_data in &mut data[blocks[block].start..end] {
+                    *is_data = !reachable;
+                }
+            }
+        }
+    }
+
+    let mut out = vec![];
+    let mut i = 0;
+
+    while i < words.len() {
+        if targets.contains(&i) {
+            out.push(format!("{}:", label(i)));
+        }
+
+        let folded = if options.fold_ext && !data[i] {
+            fold_ext(&words[i..]).filter(|(_, len)| {
+                (i + 1..i + len).all(|word| !targets.contains(&word) && !data[word])
+            })
+        } else {
+            None
+        };
+
+        let mut annotation = format!("{:#010X}", addr_of(i));
+
+        let (lasm, len) = match (folded, words[i]) {
+            (Some((ext, len)), _) => (ext_to_string(ext), len),
+
+            (None, ProgramWord::Instr(instr)) if !data[i] => {
+                let lasm = match (instr, target_of(i)) {
+                    // Relative jumps keep their offset, the target is indicated in the annotation
+                    (Instr::Jpr(_), Some(target)) if targets.contains(&target) => {
+                        annotation.push_str(&format!(" -> {}", label(target)));
+                        instr.to_lasm()
+                    }
+                    (Instr::Cpy(Reg::pc, _), Some(target)) if targets.contains(&target) => {
+                        format!("jp {}", label(target))
+                    }
+                    (Instr::Lsm(_), Some(target)) if targets.contains(&target) => {
+                        format!("lsm {}", label(target))
+                    }
+                    (Instr::Call(_), Some(target)) if targets.contains(&target) => {
+                        format!("call {}", label(target))
+                    }
+                    _ => instr.to_lasm(),
+                };
+
+                (lasm, 1)
+            }
+
+            (None, pword) => (ProgramWord::Raw(pword.encode()).to_lasm(), 1),
+        };
+
+        out.push(format!("    {:<40} ; {}", lasm, annotation));
+        i += len;
+    }
+
+    Ok(out.join("\n"))
+}
+
+/// Read a machine code file and disassemble it to LASM, outputting invalid instructions as raw data
+/// (see [`Program::to_lasm`] for the annotation format).
+/// Fails if the file can't be read or if its length is not a multiple of 4 bytes.
+pub fn disassemble_file(path: &Path, annotate: bool) -> io::Result<String> {
+    let bytes = fs::read(path)?;
+
+    let prog = Program::decode(bytes, false)
+        .map_err(|(_, err)| io::Error::new(io::ErrorKind::InvalidData, err.to_string()))?;
+
+    Ok(prog.to_lasm(annotate))
+}
+
+/// Find the longest extended instruction the provided words start with, along with its length (in words)
+fn fold_ext(words: &[ProgramWord]) -> Option<(ExtInstr, usize)> {
+    let instr: Vec<Instr> = words
+        .iter()
+        .take(7)
+        .map_while(|pword| match pword {
+            ProgramWord::Instr(instr) => Some(*instr),
+            ProgramWord::Raw(_) => None,
+        })
+        .collect();
+
+    let (reg, value) = match instr.get(..3)? {
+        [Instr::Cpy(reg, RegOrLit2::Lit(high)), _, Instr::Add(_, RegOrLit2::Lit(low))] => {
+            (*reg, (u32::from(*high) << 16) + u32::from(*low))
+        }
+        _ => return None,
+    };
+
+    let mut candidates = vec![ExtInstr::SetReg(reg, value)];
+
+    match (reg, instr.get(3..)) {
+        (
+            Reg::rr0,
+            Some(
+                [Instr::Cpy(Reg::avr, RegOrLit2::Lit(high)), _, Instr::Add(_, RegOrLit2::Lit(low)), ..],
+            ),
+        ) => candidates.push(ExtInstr::WriteAddrLit(
+            value,
+            (u32::from(*high) << 16) + u32::from(*low),
+        )),
+        (Reg::rr0, Some([Instr::Cpy(Reg::avr, RegOrLit2::Reg(value_reg)), ..])) => {
+            candidates.push(ExtInstr::WriteAddr(value, *value_reg))
+        }
+        (Reg::avr, Some([_, Instr::Cpy(dest, RegOrLit2::Reg(Reg::avr)), ..])) => {
+            candidates.push(ExtInstr::ReadAddrTo(*dest, value));
+            candidates.push(ExtInstr::ReadAddr(value));
+        }
+        (Reg::avr, _) => candidates.push(ExtInstr::ReadAddr(value)),
+        _ => {}
+    }
+
+    candidates
+        .into_iter()
+        .map(|ext| (ext, ext.to_instr()))
+        .filter(|(_, expected)| instr.starts_with(expected))
+        .max_by_key(|(_, expected)| expected.len())
+        .map(|(ext, expected)| (ext, expected.len()))
+}
+
+/// Render a folded extended instruction
+fn ext_to_string(ext: ExtInstr) -> String {
+    match ext {
+        ExtInstr::SetReg(reg, value) => format!("SetReg({}, {:#010X})", reg.name(), value),
+        ExtInstr::ReadAddr(addr) => format!("ReadAddr({:#010X})", addr),
+        ExtInstr::ReadAddrTo(reg, addr) => format!("ReadAddrTo({}, {:#010X})", reg.name(), addr),
+        ExtInstr::WriteAddr(addr, reg) => format!("WriteAddr({:#010X}, {})", addr, reg.name()),
+        ExtInstr::WriteAddrLit(addr, value) => {
+            format!("WriteAddrLit({:#010X}, {:#010X})", addr, value)
+        }
+        ext => format!("{:?}", ext),
+    }
+}
@@ -0,0 +1,75 @@
+//! Guest-side exception handlers, dispatched depending on the exception's code.
+//! See [`ExceptionTable`] for more details.
+
+use super::{cst, ExtInstr, Instr, Program, Reg};
+use std::collections::BTreeMap;
+
+/// Table of exception handlers, indexed by exception code (see [`crate::exceptions::NativeException::code`]).
+///
+/// The CPU has a single exception vector (the `ev` register) it jumps to whatever the exception is, so the table
+/// is implemented as a dispatcher routine: it extracts the exception's code from the `et` register and jumps to the matching handler.
+/// Exceptions without a registered handler jump to the default one, or halt the CPU if there is none.
+///
+/// Handlers are absolute addresses (like jump targets). The dispatcher overwrites the `avr` register and the arithmetic flags.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ExceptionTable {
+    handlers: BTreeMap<u8, u16>,
+    default: Option<u16>,
+}
+
+impl ExceptionTable {
+    /// Create an empty exception table
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register the handler of an exception code (replacing the existing one, if any)
+    pub fn handler(mut self, code: u8, addr: u16) -> Self {
+        self.handlers.insert(code, addr);
+        self
+    }
+
+    /// Register the handler of the exceptions without a specific handler
+    pub fn default_handler(mut self, addr: u16) -> Self {
+        self.default = Some(addr);
+        self
+    }
+
+    /// Get the registered handler of an exception code (not considering the default handler)
+    pub fn get(&self, code: u8) -> Option<u16> {
+        self.handlers.get(&code).copied()
+    }
+
+    /// Generate the dispatcher routine, which must be loaded at the address provided to [`ExceptionTable::install`]
+    pub fn dispatcher(&self) -> Program {
+        let mut instr = vec![
+            Instr::Cpy(Reg::avr, Reg::et.into()),
+            Instr::Shr(Reg::avr, 16_u8.into()),
+            Instr::And(Reg::avr, 0xFF_u16.into()),
+        ];
+
+        for (code, addr) in &self.handlers {
+            instr.push(Instr::Cmp(Reg::avr, u16::from(*code).into()));
+            instr.push(Instr::If(cst::ZF.into()));
+            instr.push(Instr::Cpy(Reg::pc, (*addr).into()));
+        }
+
+        instr.push(match self.default {
+            Some(addr) => Instr::Cpy(Reg::pc, addr.into()),
+            None => Instr::Halt(),
+        });
+
+        Program::from_instr(instr)
+    }
+
+    /// Get the length of the dispatcher routine, in words
+    pub fn dispatcher_len(&self) -> usize {
+        4 + self.handlers.len() * 3
+    }
+
+    /// Generate the instructions installing the dispatcher located at the provided address as the exception vector.
+    /// They must be run in supervisor mode.
+    pub fn install(&self, dispatcher_addr: u32) -> Vec<Instr> {
+        ExtInstr::SetReg(Reg::ev, dispatcher_addr).to_instr()
+    }
+}
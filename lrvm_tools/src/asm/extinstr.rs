@@ -1,8 +1,31 @@
 //! Extended instructions (ExtInstr) are a set of powerful instructions that compile into several sub-instructions.
 
-use super::{Instr, Program, ProgramWord, Reg};
+use super::{cst, HwInfo, Instr, Program, ProgramWord, Reg};
 
-/// Extended instruction
+/// Maximum number of words zeroed by [`ExtInstr::ZeroMem`] using unrolled writes (beyond, a loop is used)
+const ZERO_MEM_UNROLL_LIMIT: usize = 64;
+
+/// Fields of a device table's entry written by [`ExtInstr::EnumerateDevices`], with their offset (in bytes)
+const DEVICE_TABLE_FIELDS: [(HwInfo, u8); 15] = [
+    (HwInfo::UidUpper, 0x00),
+    (HwInfo::UidLower, 0x04),
+    (HwInfo::NameW1, 0x08),
+    (HwInfo::NameW2, 0x0C),
+    (HwInfo::NameW3, 0x10),
+    (HwInfo::NameW4, 0x14),
+    (HwInfo::NameW5, 0x18),
+    (HwInfo::NameW6, 0x1C),
+    (HwInfo::NameW7, 0x20),
+    (HwInfo::NameW8, 0x24),
+    (HwInfo::DevSize, 0x28),
+    (HwInfo::Category, 0x2C),
+    (HwInfo::Type, 0x30),
+    (HwInfo::Model, 0x34),
+    (HwInfo::DataUpper, 0x38),
+];
+
+/// Extended instruction  
+/// Note that some extended instructions use the `rr0` to `rr3` registers as temporaries.
 #[derive(Debug, Copy, Clone)]
 pub enum ExtInstr {
     SetReg(Reg, u32),
@@ -10,6 +33,35 @@ pub enum ExtInstr {
     ReadAddrTo(Reg, u32),
     WriteAddr(u32, Reg),
     WriteAddrLit(u32, u32),
+    /// Write a register's value at the provided address (same as [`ExtInstr::WriteAddr`])
+    CopyRegToMem(Reg, u32),
+    /// Read the word at the provided address into a register (same as [`ExtInstr::ReadAddrTo`])
+    CopyMemToReg(Reg, u32),
+    ZeroReg(Reg),
+    ZeroMem(u32, usize),
+    /// Write a table describing all the components connected to the motherboard at the provided address.
+    /// The table starts with the number of components, followed by an entry of 19 words (76 bytes) per component:
+    /// hardware identifier (2 words), name (8 words), size, category, type, model, additional data (2 words),
+    /// mapping status (1 if mapped, 0 otherwise), mapping start address and mapping end address (0 if not mapped).
+    ///
+    /// The same routine is available as LASM source code through [`crate::lasm::ENUMERATE_DEVICES_ROUTINE`],
+    /// and the table can be decoded on the host side using [`crate::debug::decode_device_table`].
+    EnumerateDevices(u32),
+    /// Push a register's value on a stack whose pointer is stored in `sp`: decrement `sp` by 4, then write the value at its new address.
+    /// Unlike the `push` instruction, any register can be used as the stack pointer, and only `reg` and `sp` are used.
+    ///
+    /// By convention, the stack pointer is `ssp` in supervisor mode, so the stack is shared with the `push`, `pop` and `call` instructions.
+    /// As stack pointers can't be accessed in userland mode, userland programs use `ac2` instead.
+    PushReg {
+        reg: Reg,
+        sp: Reg,
+    },
+    /// Pop a value from a stack whose pointer is stored in `sp` into a register: read the value at its address, then increment `sp` by 4.
+    /// Only `reg` and `sp` are used. See [`ExtInstr::PushReg`] for the stack pointer's convention.
+    PopReg {
+        reg: Reg,
+        sp: Reg,
+    },
 }
 
 impl ExtInstr {
@@ -54,6 +106,106 @@ impl ExtInstr {
                 Instr::Add(Reg::avr, (*value as u16).into()),
                 Instr::Wea(Reg::rr0.into(), 0u8.into(), 0u8.into()),
             ],
+
+            ExtInstr::CopyRegToMem(reg, addr) => ExtInstr::WriteAddr(*addr, *reg).to_instr(),
+
+            ExtInstr::CopyMemToReg(reg, addr) => ExtInstr::ReadAddrTo(*reg, *addr).to_instr(),
+
+            ExtInstr::ZeroReg(reg) => vec![Instr::Cpy(*reg, 0u16.into())],
+
+            // Small regions are zeroed with unrolled writes, larger ones with a loop (using 'rr1' as a counter)
+            ExtInstr::ZeroMem(addr, count) => {
+                if *count == 0 {
+                    return vec![];
+                }
+
+                let mut instr = ExtInstr::SetReg(Reg::rr0, *addr).to_instr();
+
+                if *count <= ZERO_MEM_UNROLL_LIMIT {
+                    instr.extend(
+                        (0..*count).map(|i| {
+                            Instr::Wsa(Reg::rr0.into(), ((i * 4) as u8).into(), 0u8.into())
+                        }),
+                    );
+                } else {
+                    instr.extend(ExtInstr::SetReg(Reg::rr1, *count as u32).to_instr());
+                    instr.extend(vec![
+                        Instr::Wsa(Reg::rr0.into(), 0u8.into(), 0u8.into()),
+                        Instr::Add(Reg::rr0, 4u16.into()),
+                        Instr::Sub(Reg::rr1, 1u16.into()),
+                        Instr::IfN(cst::ZF.into()),
+                        Instr::Jpr((-16i16).into()),
+                    ]);
+                }
+
+                instr
+            }
+
+            // 'rr0' contains the current entry's address, 'rr1' the number of remaining components,
+            // 'rr2' the current component's ID and 'rr3' the value to write
+            ExtInstr::EnumerateDevices(addr) => {
+                let mut instr = ExtInstr::SetReg(Reg::rr0, *addr).to_instr();
+
+                instr.extend(vec![
+                    Instr::Hwd(Reg::rr1, 0u8.into(), HwInfo::Count.code().into()),
+                    Instr::Wsa(Reg::rr0.into(), 0u8.into(), Reg::rr1.into()),
+                    Instr::Add(Reg::rr0, 4u16.into()),
+                    Instr::Cpy(Reg::rr2, 0u16.into()),
+                ]);
+
+                let mut body = vec![];
+
+                for (hw_info, offset) in DEVICE_TABLE_FIELDS.iter() {
+                    body.push(Instr::Hwd(Reg::rr3, Reg::rr2.into(), hw_info.code().into()));
+                    body.push(Instr::Wsa(
+                        Reg::rr0.into(),
+                        (*offset).into(),
+                        Reg::rr3.into(),
+                    ));
+                }
+
+                body.extend(vec![
+                    Instr::Hwd(Reg::rr3, Reg::rr2.into(), HwInfo::DataLower.code().into()),
+                    Instr::Wsa(Reg::rr0.into(), 0x3Cu8.into(), Reg::rr3.into()),
+                    Instr::Wsa(Reg::rr0.into(), 0x44u8.into(), 0u8.into()),
+                    Instr::Wsa(Reg::rr0.into(), 0x48u8.into(), 0u8.into()),
+                    Instr::Hwd(Reg::rr3, Reg::rr2.into(), HwInfo::IsMapped.code().into()),
+                    Instr::Wsa(Reg::rr0.into(), 0x40u8.into(), Reg::rr3.into()),
+                    Instr::Cmp(Reg::rr3, 0u16.into()),
+                    Instr::If(cst::ZF.into()),
+                    Instr::Jpr(20u16.into()),
+                    Instr::Hwd(Reg::rr3, Reg::rr2.into(), HwInfo::MapStart.code().into()),
+                    Instr::Wsa(Reg::rr0.into(), 0x44u8.into(), Reg::rr3.into()),
+                    Instr::Hwd(Reg::rr3, Reg::rr2.into(), HwInfo::MapEnd.code().into()),
+                    Instr::Wsa(Reg::rr0.into(), 0x48u8.into(), Reg::rr3.into()),
+                    Instr::Add(Reg::rr0, 76u16.into()),
+                    Instr::Add(Reg::rr2, 1u16.into()),
+                    Instr::Sub(Reg::rr1, 1u16.into()),
+                ]);
+
+                // Loop until there is no remaining component
+                let loop_len = (body.len() + 4) as i16;
+
+                instr.extend(vec![
+                    Instr::Cmp(Reg::rr1, 0u16.into()),
+                    Instr::If(cst::ZF.into()),
+                    Instr::Jpr(((loop_len - 2) * 4).into()),
+                ]);
+                instr.extend(body);
+                instr.push(Instr::Jpr((-(loop_len - 1) * 4).into()));
+
+                instr
+            }
+
+            ExtInstr::PushReg { reg, sp } => vec![
+                Instr::Sub(*sp, 4u16.into()),
+                Instr::Wsa((*sp).into(), 0u8.into(), (*reg).into()),
+            ],
+
+            ExtInstr::PopReg { reg, sp } => vec![
+                Instr::Lsa(*reg, (*sp).into(), 0u8.into()),
+                Instr::Add(*sp, 4u16.into()),
+            ],
         }
     }
 
@@ -65,6 +217,11 @@ impl ExtInstr {
             .collect()
     }
 
+    /// Convert the extended instruction into a program
+    pub fn to_program(&self) -> Program {
+        Program::from_instr(self.to_instr())
+    }
+
     /// Convert the extended instruction into machine code (split in words)
     pub fn encode_words(&self) -> Vec<u32> {
         Program::from_instr(self.to_instr()).encode_words()
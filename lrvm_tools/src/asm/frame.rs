@@ -0,0 +1,103 @@
+//! Stack frames, to write functions following a common calling convention.
+//! See [`Frame`] for more details.
+
+use super::{Instr, Reg, RegOrLit1};
+
+/// Stack frame of a function, generating its prologue and epilogue.
+///
+/// Calling convention:
+///
+/// * Functions are called with the `call` instruction, which pushes the return address on the stack
+/// * Arguments and return values are passed through registers, chosen by the caller and the callee
+/// * The registers listed as saved are preserved across the call (callee-saved), all the other ones may be overwritten
+/// * The prologue pushes the saved registers (in the provided order), then reserves the local slots by moving the stack pointer down
+/// * The epilogue releases the local slots, restores the saved registers (in reverse order) and returns to the caller
+///
+/// After the prologue, the stack contains (from the lowest address): the local slots, the saved registers and the return address.
+///
+/// As the stack pointer is only readable and writable in supervisor mode, frames use the `ssp` register and must be run in supervisor mode.
+/// Accessing a local slot overwrites the `avr` register, and the prologue and epilogue overwrite the arithmetic flags.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Frame {
+    locals_words: u8,
+    saved_regs: Vec<Reg>,
+}
+
+impl Frame {
+    /// Create a stack frame with the provided number of local slots (one word each) and callee-saved registers.
+    /// Panics if one of the saved registers is `pc` or `ssp`, as they can't be preserved through the stack.
+    pub fn new(locals_words: u8, saved_regs: &[Reg]) -> Self {
+        assert!(
+            !saved_regs.contains(&Reg::pc) && !saved_regs.contains(&Reg::ssp),
+            "Registers 'pc' and 'ssp' can't be saved in a stack frame"
+        );
+
+        Self {
+            locals_words,
+            saved_regs: saved_regs.to_vec(),
+        }
+    }
+
+    /// Get the number of local slots
+    pub fn locals_words(&self) -> u8 {
+        self.locals_words
+    }
+
+    /// Get the callee-saved registers
+    pub fn saved_regs(&self) -> &[Reg] {
+        &self.saved_regs
+    }
+
+    /// Get the size of the frame (local slots and saved registers, not including the return address), in bytes
+    pub fn size(&self) -> usize {
+        (usize::from(self.locals_words) + self.saved_regs.len()) * 4
+    }
+
+    /// Generate the function's prologue, which must be the first instructions run by the function
+    pub fn prologue(&self) -> Vec<Instr> {
+        let mut instr: Vec<Instr> = self
+            .saved_regs
+            .iter()
+            .map(|reg| Instr::Push((*reg).into()))
+            .collect();
+
+        if self.locals_words > 0 {
+            instr.push(Instr::Sub(Reg::ssp, self.locals_size().into()));
+        }
+
+        instr
+    }
+
+    /// Generate the function's epilogue, which returns to the caller
+    pub fn epilogue(&self) -> Vec<Instr> {
+        let mut instr = vec![];
+
+        if self.locals_words > 0 {
+            instr.push(Instr::Add(Reg::ssp, self.locals_size().into()));
+        }
+
+        instr.extend(self.saved_regs.iter().rev().map(|reg| Instr::Pop(*reg)));
+        instr.push(Instr::Pop(Reg::pc));
+
+        instr
+    }
+
+    /// Get the addressing operands (address, addition and multiplier) of a local slot, to use with the `lea` and `wea` instructions.
+    /// Only valid between the prologue and the epilogue, when the stack pointer is not modified.
+    /// Panics if the slot does not exist.
+    pub fn local_addr(&self, slot: u8) -> (RegOrLit1, RegOrLit1, RegOrLit1) {
+        assert!(
+            slot < self.locals_words,
+            "Local slot {} does not exist (frame has {} local slots)",
+            slot,
+            self.locals_words
+        );
+
+        (Reg::ssp.into(), slot.into(), 4_u8.into())
+    }
+
+    /// Get the size of the local slots, in bytes
+    fn locals_size(&self) -> u16 {
+        u16::from(self.locals_words) * 4
+    }
+}
@@ -1,5 +1,6 @@
 use super::{cst, ProgramWord};
 use super::{ArFlag, DivMode, HwInfo, If2Cond, Reg, RegOrLit1, RegOrLit2};
+use crate::bytes::bytes_be_to_u32;
 use std::fmt;
 
 /// Native assembly instruction
@@ -39,354 +40,162 @@ pub enum Instr {
 }
 
 impl Instr {
-    /// Try to decode an assembly instruction
-    pub fn decode(bytes: [u8; 4]) -> Result<Instr, InstrDecodingError> {
-        let opcode = bytes[0] >> 3;
-
-        let (arg_reg, arg_reg_or_lit_1, arg_reg_or_lit_2) = {
-            let mut _decode_reg = move |param: usize| {
-                Reg::from_code(bytes[param]).map_err(|()| InstrDecodingError::UnknownRegister {
-                    param: param - 1,
-                    code: bytes[param],
-                })
-            };
-
-            (
-                move |param: usize| _decode_reg(param),
-                move |param: usize| {
-                    if bytes[0] & (1 << (3 - param)) == 0 {
-                        Ok(RegOrLit1::lit(bytes[param]))
-                    } else {
-                        Ok(RegOrLit1::reg(_decode_reg(param)?))
-                    }
-                },
-                move |param: usize| {
-                    if bytes[0] & (1 << (3 - param)) == 0 {
-                        Ok(RegOrLit2::lit(u16::from_be_bytes([
-                            bytes[param],
-                            bytes[param + 1],
-                        ])))
-                    } else {
-                        Ok(RegOrLit2::reg(_decode_reg(param)?))
-                    }
-                },
-            )
-        };
-
-        // Decode the instruction based on its opcode
-        match opcode {
-            0x01 => Ok(Self::Cpy(arg_reg(1)?, arg_reg_or_lit_2(2)?)),
-            0x02 => Ok(Self::Ex(arg_reg(1)?, arg_reg(2)?)),
-            0x03 => Ok(Self::Add(arg_reg(1)?, arg_reg_or_lit_2(2)?)),
-            0x04 => Ok(Self::Sub(arg_reg(1)?, arg_reg_or_lit_2(2)?)),
-            0x05 => Ok(Self::Mul(arg_reg(1)?, arg_reg_or_lit_2(2)?)),
-            0x06 => Ok(Self::Div(
-                arg_reg(1)?,
-                arg_reg_or_lit_1(2)?,
-                arg_reg_or_lit_1(3)?,
-            )),
-            0x07 => Ok(Self::Mod(
-                arg_reg(1)?,
-                arg_reg_or_lit_1(2)?,
-                arg_reg_or_lit_1(3)?,
-            )),
-            0x08 => Ok(Self::And(arg_reg(1)?, arg_reg_or_lit_2(2)?)),
-            0x09 => Ok(Self::Bor(arg_reg(1)?, arg_reg_or_lit_2(2)?)),
-            0x0A => Ok(Self::Xor(arg_reg(1)?, arg_reg_or_lit_2(2)?)),
-            0x0B => Ok(Self::Shl(arg_reg(1)?, arg_reg_or_lit_1(2)?)),
-            0x0C => Ok(Self::Shr(arg_reg(1)?, arg_reg_or_lit_1(2)?)),
-            0x0D => Ok(Self::Cmp(arg_reg(1)?, arg_reg_or_lit_2(2)?)),
-            0x0E => Ok(Self::Jpr(arg_reg_or_lit_2(1)?)),
-            0x0F => Ok(Self::Lsm(arg_reg_or_lit_2(1)?)),
-            0x10 => Ok(Self::Itr(arg_reg_or_lit_1(1)?)),
-            0x11 => Ok(Self::If(arg_reg_or_lit_1(1)?)),
-            0x12 => Ok(Self::IfN(arg_reg_or_lit_1(1)?)),
-            0x13 => Ok(Self::If2(
-                arg_reg_or_lit_1(1)?,
-                arg_reg_or_lit_1(2)?,
-                arg_reg_or_lit_1(3)?,
-            )),
-            0x14 => Ok(Self::Lsa(
-                arg_reg(1)?,
-                arg_reg_or_lit_1(2)?,
-                arg_reg_or_lit_1(3)?,
-            )),
-            0x15 => Ok(Self::Lea(
-                arg_reg_or_lit_1(1)?,
-                arg_reg_or_lit_1(2)?,
-                arg_reg_or_lit_1(3)?,
-            )),
-            0x16 => Ok(Self::Wsa(
-                arg_reg_or_lit_1(1)?,
-                arg_reg_or_lit_1(2)?,
-                arg_reg_or_lit_1(3)?,
-            )),
-            0x17 => Ok(Self::Wea(
-                arg_reg_or_lit_1(1)?,
-                arg_reg_or_lit_1(2)?,
-                arg_reg_or_lit_1(3)?,
-            )),
-            0x18 => Ok(Self::Srm(
-                arg_reg_or_lit_1(1)?,
-                arg_reg_or_lit_1(2)?,
-                arg_reg(3)?,
-            )),
-            0x19 => Ok(Self::Push(arg_reg_or_lit_2(1)?)),
-            0x1A => Ok(Self::Pop(arg_reg(1)?)),
-            0x1B => Ok(Self::Call(arg_reg_or_lit_2(1)?)),
-            0x1C => Ok(Self::Hwd(
-                arg_reg(1)?,
-                arg_reg_or_lit_1(2)?,
-                arg_reg_or_lit_1(3)?,
-            )),
-            0x1D => Ok(Self::Cycles(arg_reg(1)?)),
-            0x1E => Ok(Self::Halt()),
-            0x1F => Ok(Self::Reset(arg_reg_or_lit_1(1)?)),
-            _ => Err(InstrDecodingError::UnknownOpCode { opcode }),
-        }
+    /// Get the canonical no-op instruction (`CPY a0, a0`, written `nop` in LASM), which changes neither registers nor flags
+    pub fn nop() -> Self {
+        Self::Cpy(Reg::a0, RegOrLit2::Reg(Reg::a0))
     }
 
-    /// Encode the instruction as a set of 4 bytes
-    pub fn encode(self) -> [u8; 4] {
-        let mut is_reg: Vec<bool> = vec![];
-        let mut params: Vec<u8> = vec![];
-
-        // Declare which parameters are registers
-        macro_rules! regs { ($($is_reg: expr),*) => {{ is_reg = vec![ $( $is_reg ),* ]; }} }
-
-        // Push parameters
-        macro_rules! push {
-            // Push register parameters
-            (regs $($reg: expr),*) => {{ $( params.push($reg.code()) );* }};
-            // Push a parameter's value (register or constant)
-            (regs_or_lit $($val: expr),*) => {{ $( params.extend_from_slice(&$val.value().to_be_bytes()) );* }};
-        }
-
-        let opcode = match self {
-            Self::Cpy(a, b) => {
-                regs!(true, b.is_reg());
-                push!(regs a);
-                push!(regs_or_lit b);
-                0x01
-            }
-
-            Self::Ex(a, b) => {
-                regs!(true, true);
-                push!(regs a, b);
-                0x02
-            }
-
-            Self::Add(a, b) => {
-                regs!(true, b.is_reg());
-                push!(regs a);
-                push!(regs_or_lit b);
-                0x03
-            }
-
-            Self::Sub(a, b) => {
-                regs!(true, b.is_reg());
-                push!(regs a);
-                push!(regs_or_lit b);
-                0x04
-            }
-
-            Self::Mul(a, b) => {
-                regs!(true, b.is_reg());
-                push!(regs a);
-                push!(regs_or_lit b);
-                0x05
-            }
-
-            Self::Div(a, b, c) => {
-                regs!(true, b.is_reg(), c.is_reg());
-                push!(regs a);
-                push!(regs_or_lit b, c);
-                0x06
-            }
-
-            Self::Mod(a, b, c) => {
-                regs!(true, b.is_reg(), c.is_reg());
-                push!(regs a);
-                push!(regs_or_lit b, c);
-                0x07
-            }
-
-            Self::And(a, b) => {
-                regs!(true, b.is_reg());
-                push!(regs a);
-                push!(regs_or_lit b);
-                0x08
-            }
-
-            Self::Bor(a, b) => {
-                regs!(true, b.is_reg());
-                push!(regs a);
-                push!(regs_or_lit b);
-                0x09
-            }
-
-            Self::Xor(a, b) => {
-                regs!(true, b.is_reg());
-                push!(regs a);
-                push!(regs_or_lit b);
-                0x0A
-            }
-
-            Self::Shl(a, b) => {
-                regs!(true, b.is_reg());
-                push!(regs a);
-                push!(regs_or_lit b);
-                0x0B
-            }
+    /// Check if the instruction is the canonical no-op instruction (see [`Instr::nop`]).
+    /// Other instructions without effect (like `CPY a1, a1`) are not considered as no-ops.
+    pub fn is_nop(self) -> bool {
+        self == Self::nop()
+    }
 
-            Self::Shr(a, b) => {
-                regs!(true, b.is_reg());
-                push!(regs a);
-                push!(regs_or_lit b);
-                0x0C
-            }
+    /// Encode the instruction as a single word
+    pub fn encode_word(self) -> u32 {
+        bytes_be_to_u32(self.encode())
+    }
 
-            Self::Cmp(a, b) => {
-                regs!(true, b.is_reg());
-                push!(regs a);
-                push!(regs_or_lit b);
-                0x0D
-            }
+    /// Encode the instruction as a set of 4 bytes, after checking its operands are valid.
+    /// This catches instructions that would always raise an exception when run by the CPU, like writing to a read-only register
+    /// or using an unknown division mode, flag, condition or hardware information code as a literal.
+    pub fn try_encode(self) -> Result<[u8; 4], InstrEncodingError> {
+        self.validate()?;
+        Ok(self.encode())
+    }
 
-            Self::Jpr(a) => {
-                regs!(a.is_reg());
-                push!(regs_or_lit a);
-                0x0E
+    /// Check the instruction's operands are valid (see [`Instr::try_encode`])
+    pub fn validate(self) -> Result<(), InstrEncodingError> {
+        let writable = |param: usize, reg: Reg| match reg {
+            Reg::af | Reg::et | Reg::era => {
+                Err(InstrEncodingError::ReadOnlyRegister { param, reg })
             }
+            _ => Ok(()),
+        };
 
-            Self::Lsm(a) => {
-                regs!(a.is_reg());
-                push!(regs_or_lit a);
-                0x0F
+        let flag = |param: usize, flag: RegOrLit1| match flag {
+            RegOrLit1::Lit(flag) if ArFlag::decode(flag).is_err() => {
+                Err(InstrEncodingError::InvalidFlag { param, flag })
             }
+            _ => Ok(()),
+        };
 
-            Self::Itr(a) => {
-                regs!(a.is_reg());
-                push!(regs_or_lit a);
-                0x10
-            }
+        match self {
+            Self::Cpy(a, _)
+            | Self::Add(a, _)
+            | Self::Sub(a, _)
+            | Self::Mul(a, _)
+            | Self::And(a, _)
+            | Self::Bor(a, _)
+            | Self::Xor(a, _)
+            | Self::Shl(a, _)
+            | Self::Shr(a, _)
+            | Self::Lsa(a, _, _)
+            | Self::Pop(a)
+            | Self::Cycles(a) => writable(0, a),
 
-            Self::If(a) => {
-                regs!(a.is_reg());
-                push!(regs_or_lit a);
-                0x11
+            Self::Ex(a, b) => {
+                writable(0, a)?;
+                writable(1, b)
+            }
+
+            Self::Div(a, _, c) | Self::Mod(a, _, c) => {
+                writable(0, a)?;
+
+                match c {
+                    RegOrLit1::Lit(mode)
+                        if mode
+                            & !(cst::DIV_SIGN_MODE_MASK
+                                | cst::DIV_ZERO_MODE_MASK
+                                | cst::DIV_OVFW_MODE_MASK)
+                            != 0
+                            || DivMode::decode(mode).is_err() =>
+                    {
+                        Err(InstrEncodingError::InvalidDivMode { mode })
+                    }
+                    _ => Ok(()),
+                }
             }
 
-            Self::IfN(a) => {
-                regs!(a.is_reg());
-                push!(regs_or_lit a);
-                0x12
-            }
+            Self::If(a) | Self::IfN(a) => flag(0, a),
 
             Self::If2(a, b, c) => {
-                regs!(a.is_reg(), b.is_reg(), c.is_reg());
-                push!(regs_or_lit a, b, c);
-                0x13
-            }
-
-            Self::Lsa(a, b, c) => {
-                regs!(true, b.is_reg(), c.is_reg());
-                push!(regs a);
-                push!(regs_or_lit b, c);
-                0x14
-            }
-
-            Self::Lea(a, b, c) => {
-                regs!(a.is_reg(), b.is_reg(), c.is_reg());
-                push!(regs_or_lit a, b, c);
-                0x15
-            }
-
-            Self::Wsa(a, b, c) => {
-                regs!(a.is_reg(), b.is_reg(), c.is_reg());
-                push!(regs_or_lit a, b, c);
-                0x16
-            }
-
-            Self::Wea(a, b, c) => {
-                regs!(a.is_reg(), b.is_reg(), c.is_reg());
-                push!(regs_or_lit a, b, c);
-                0x17
-            }
-
-            Self::Srm(a, b, c) => {
-                regs!(a.is_reg(), b.is_reg(), true);
-                push!(regs_or_lit a, b);
-                push!(regs c);
-                0x18
-            }
-
-            Self::Push(a) => {
-                regs!(a.is_reg());
-                push!(regs_or_lit a);
-                0x19
-            }
-
-            Self::Pop(a) => {
-                regs!(true);
-                push!(regs a);
-                0x1A
-            }
-
-            Self::Call(a) => {
-                regs!(a.is_reg());
-                push!(regs_or_lit a);
-                0x1B
-            }
+                flag(0, a)?;
+                flag(1, b)?;
 
-            Self::Hwd(a, b, c) => {
-                regs!(true, b.is_reg(), c.is_reg());
-                push!(regs a);
-                push!(regs_or_lit b, c);
-                0x1C
+                match c {
+                    RegOrLit1::Lit(cond) if If2Cond::decode(cond).is_err() => {
+                        Err(InstrEncodingError::InvalidIf2Cond { cond })
+                    }
+                    _ => Ok(()),
+                }
             }
 
-            Self::Cycles(a) => {
-                regs!(true);
-                push!(regs a);
-                0x1D
-            }
+            Self::Srm(_, _, c) => writable(2, c),
 
-            Self::Halt() => 0x1E,
+            Self::Hwd(a, _, c) => {
+                writable(0, a)?;
 
-            Self::Reset(a) => {
-                regs!(a.is_reg());
-                push!(regs_or_lit a);
-                0x1F
+                match c {
+                    RegOrLit1::Lit(code) if HwInfo::decode(code).is_err() => {
+                        Err(InstrEncodingError::InvalidHwInfo { code })
+                    }
+                    _ => Ok(()),
+                }
             }
-        };
 
-        assert!(
-            is_reg.len() <= 3,
-            "Internal error: more than 3 serialized parameters"
-        );
-        assert!(
-            params.len() <= 3,
-            "Internal error: serialized parameters length exceed 3 bytes"
-        );
-
-        is_reg.resize(3, false);
-        params.resize(3, 0);
-
-        [
-            (opcode << 3)
-                + if is_reg[0] { 1 << 2 } else { 0 }
-                + if is_reg[1] { 1 << 1 } else { 0 }
-                + if is_reg[2] { 1 } else { 0 },
-            params[0],
-            params[1],
-            params[2],
-        ]
+            Self::Cmp(_, _)
+            | Self::Jpr(_)
+            | Self::Lsm(_)
+            | Self::Itr(_)
+            | Self::Lea(_, _, _)
+            | Self::Wsa(_, _, _)
+            | Self::Wea(_, _, _)
+            | Self::Push(_)
+            | Self::Call(_)
+            | Self::Halt()
+            | Self::Reset(_) => Ok(()),
+        }
     }
 
-    /// Encode the instruction as a single word
-    pub fn encode_word(self) -> u32 {
-        u32::from_be_bytes(self.encode())
+    /// Get the registers written by the instruction through its operands.
+    /// Registers modified implicitly (e.g. the stack pointers by `PUSH`) are not included.
+    pub fn written_regs(self) -> Vec<Reg> {
+        match self {
+            Self::Cpy(a, _)
+            | Self::Add(a, _)
+            | Self::Sub(a, _)
+            | Self::Mul(a, _)
+            | Self::Div(a, _, _)
+            | Self::Mod(a, _, _)
+            | Self::And(a, _)
+            | Self::Bor(a, _)
+            | Self::Xor(a, _)
+            | Self::Shl(a, _)
+            | Self::Shr(a, _)
+            | Self::Lsa(a, _, _)
+            | Self::Srm(_, _, a)
+            | Self::Pop(a)
+            | Self::Hwd(a, _, _)
+            | Self::Cycles(a) => vec![a],
+
+            Self::Ex(a, b) => vec![a, b],
+
+            Self::Cmp(_, _)
+            | Self::Jpr(_)
+            | Self::Lsm(_)
+            | Self::Itr(_)
+            | Self::If(_)
+            | Self::IfN(_)
+            | Self::If2(_, _, _)
+            | Self::Lea(_, _, _)
+            | Self::Wsa(_, _, _)
+            | Self::Wea(_, _, _)
+            | Self::Push(_)
+            | Self::Call(_)
+            | Self::Halt()
+            | Self::Reset(_) => vec![],
+        }
     }
 
     /// Convert the instruction to LASM assembly
@@ -395,6 +204,7 @@ impl Instr {
         match self {
             Self::Cpy(a, b) => match (a, b) {
                 (Reg::pc, _) => format!("jp {}", b.to_lasm()),
+                (Reg::a0, RegOrLit2::Reg(Reg::a0)) => "nop".to_string(),
                 (_, RegOrLit2::Lit(0)) => format!("zro {}", a.to_lasm()),
                 (_, _) => format!("cpy {}, {}", a.to_lasm(), b.to_lasm()),
             },
@@ -615,12 +425,33 @@ pub enum InstrDecodingError {
     UnknownRegister { param: usize, code: u8 },
 }
 
+/// Instruction encoding error
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum InstrEncodingError {
+    /// A read-only register is used as a destination
+    ReadOnlyRegister { param: usize, reg: Reg },
+    /// An invalid division mode (or one with unused bits set) was provided as a literal
+    InvalidDivMode { mode: u8 },
+    /// An invalid arithmetic flag was provided as a literal
+    InvalidFlag { param: usize, flag: u8 },
+    /// An invalid IF2 condition was provided as a literal
+    InvalidIf2Cond { cond: u8 },
+    /// An invalid hardware information code was provided as a literal
+    InvalidHwInfo { code: u8 },
+}
+
 impl From<Instr> for ProgramWord {
     fn from(instr: Instr) -> ProgramWord {
         ProgramWord::Instr(instr)
     }
 }
 
+impl fmt::Display for Instr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.to_lasm())
+    }
+}
+
 impl fmt::Display for InstrDecodingError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "{}", match self {
@@ -630,3 +461,27 @@ impl fmt::Display for InstrDecodingError {
         })
     }
 }
+
+impl fmt::Display for InstrEncodingError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::ReadOnlyRegister { param, reg } => write!(
+                f,
+                "Parameter {} writes to read-only register: {}",
+                param + 1,
+                reg.name()
+            ),
+            Self::InvalidDivMode { mode } => write!(f, "Invalid division mode: {:#010b}", mode),
+            Self::InvalidFlag { param, flag } => write!(
+                f,
+                "Parameter {} uses invalid arithmetic flag: {:#004X}",
+                param + 1,
+                flag
+            ),
+            Self::InvalidIf2Cond { cond } => write!(f, "Invalid IF2 condition: {:#004X}", cond),
+            Self::InvalidHwInfo { code } => {
+                write!(f, "Invalid hardware information code: {:#004X}", code)
+            }
+        }
+    }
+}
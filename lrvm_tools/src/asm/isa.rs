@@ -0,0 +1,453 @@
+//! Instruction set of the CPU, used by the instructions' decoder and encoder as well as to generate its documentation.
+
+use super::{Instr, InstrDecodingError, Reg, RegOrLit1, RegOrLit2};
+use std::ops::Range;
+
+/// Type of an instruction's operand
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OperandType {
+    /// A register
+    Reg,
+    /// A register or an 8-bit immediate value
+    RegOrLit1,
+    /// A register or a 16-bit immediate value
+    RegOrLit2,
+}
+
+impl OperandType {
+    /// Get the operand's size, in bits
+    pub fn bits(self) -> u8 {
+        match self {
+            Self::Reg | Self::RegOrLit1 => 8,
+            Self::RegOrLit2 => 16,
+        }
+    }
+
+    /// Check if the operand accepts an immediate value
+    pub fn accepts_lit(self) -> bool {
+        match self {
+            Self::Reg => false,
+            Self::RegOrLit1 | Self::RegOrLit2 => true,
+        }
+    }
+
+    /// Get a short description of the operand's type
+    pub fn describe(self) -> &'static str {
+        match self {
+            Self::Reg => "reg",
+            Self::RegOrLit1 => "reg / imm8",
+            Self::RegOrLit2 => "reg / imm16",
+        }
+    }
+}
+
+/// Value of an instruction's operand
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum InstrOperand {
+    /// A register
+    Reg(Reg),
+    /// A 16-bit immediate value
+    Imm16(u16),
+    /// An 8-bit immediate value
+    Imm8(u8),
+}
+
+/// Description of an instruction's operand
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct IsaOperand {
+    /// Operand's name
+    pub name: &'static str,
+    /// Operand's type
+    pub ty: OperandType,
+    /// Bits of the instruction's word the operand is encoded in (bit 0 being the weakest one)
+    pub bits: Range<u8>,
+    /// Bit of the instruction's word indicating if the operand is a register
+    pub reg_flag_bit: u8,
+}
+
+/// Description of an instruction
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct IsaEntry {
+    /// Instruction's mnemonic, in LASM
+    pub mnemonic: &'static str,
+    /// Instruction's opcode (5 strongest bits of the instruction's word)
+    pub opcode: u8,
+    /// Instruction's operands
+    pub operands: Vec<IsaOperand>,
+    /// Short description of the instruction
+    pub description: &'static str,
+}
+
+/// (Internal) Instruction's definition in the ISA table
+struct IsaDef {
+    mnemonic: &'static str,
+    opcode: u8,
+    operands: &'static [(&'static str, OperandType)],
+    description: &'static str,
+}
+
+/// (Internal) Operand which can be decoded from and encoded to an instruction
+trait Operand: Sized {
+    const TYPE: OperandType;
+
+    fn read(reader: &mut OperandReader) -> Result<Self, InstrDecodingError>;
+    fn write(self, writer: &mut OperandWriter);
+    fn operand(self) -> InstrOperand;
+}
+
+/// (Internal) Decoder of an instruction's operands, in order
+struct OperandReader {
+    bytes: [u8; 4],
+    operand: usize,
+    byte: usize,
+}
+
+impl OperandReader {
+    fn new(bytes: [u8; 4]) -> Self {
+        Self {
+            bytes,
+            operand: 0,
+            byte: 1,
+        }
+    }
+
+    /// Read the next operand
+    fn read<T: Operand>(&mut self) -> Result<T, InstrDecodingError> {
+        T::read(self)
+    }
+
+    /// Decode the next operand with the provided decoder, called with the operand's "is a register" flag and its bytes
+    fn next<T>(
+        &mut self,
+        size: usize,
+        decode: impl FnOnce(bool, &[u8]) -> Result<T, InstrDecodingError>,
+    ) -> Result<T, InstrDecodingError> {
+        let is_reg = self.bytes[0] & (1 << (2 - self.operand)) != 0;
+        let value = decode(is_reg, &self.bytes[self.byte..self.byte + size]);
+
+        self.operand += 1;
+        self.byte += size;
+
+        value
+    }
+
+    /// Decode a register code
+    fn reg(&self, code: u8) -> Result<Reg, InstrDecodingError> {
+        Reg::from_code(code).map_err(|()| InstrDecodingError::UnknownRegister {
+            param: self.byte - 1,
+            code,
+        })
+    }
+}
+
+/// (Internal) Encoder of an instruction's operands, in order
+struct OperandWriter {
+    is_reg: Vec<bool>,
+    params: Vec<u8>,
+}
+
+impl OperandWriter {
+    fn new() -> Self {
+        Self {
+            is_reg: vec![],
+            params: vec![],
+        }
+    }
+
+    /// Write the next operand
+    fn write<T: Operand>(&mut self, operand: T) {
+        operand.write(self)
+    }
+
+    /// Push an operand's bytes
+    /// Register codes are always put in the operand's first byte
+    fn push(&mut self, is_reg: bool, bytes: &[u8]) {
+        self.is_reg.push(is_reg);
+
+        if is_reg {
+            self.params.push(bytes[bytes.len() - 1]);
+            self.params.resize(self.params.len() + bytes.len() - 1, 0);
+        } else {
+            self.params.extend_from_slice(bytes);
+        }
+    }
+
+    /// Build the instruction's bytes
+    fn finish(mut self, opcode: u8) -> [u8; 4] {
+        assert!(
+            self.is_reg.len() <= 3,
+            "Internal error: more than 3 serialized parameters"
+        );
+        assert!(
+            self.params.len() <= 3,
+            "Internal error: serialized parameters length exceed 3 bytes"
+        );
+
+        self.is_reg.resize(3, false);
+        self.params.resize(3, 0);
+
+        [
+            (opcode << 3)
+                + if self.is_reg[0] { 1 << 2 } else { 0 }
+                + if self.is_reg[1] { 1 << 1 } else { 0 }
+                + if self.is_reg[2] { 1 } else { 0 },
+            self.params[0],
+            self.params[1],
+            self.params[2],
+        ]
+    }
+}
+
+impl Operand for Reg {
+    const TYPE: OperandType = OperandType::Reg;
+
+    fn read(reader: &mut OperandReader) -> Result<Self, InstrDecodingError> {
+        let code = reader.bytes[reader.byte];
+        let reg = reader.reg(code);
+        reader.next(1, |_, _| reg)
+    }
+
+    fn write(self, writer: &mut OperandWriter) {
+        writer.push(true, &[self.code()])
+    }
+
+    fn operand(self) -> InstrOperand {
+        InstrOperand::Reg(self)
+    }
+}
+
+impl Operand for RegOrLit1 {
+    const TYPE: OperandType = OperandType::RegOrLit1;
+
+    fn read(reader: &mut OperandReader) -> Result<Self, InstrDecodingError> {
+        let code = reader.bytes[reader.byte];
+        let reg = reader.reg(code);
+
+        reader.next(1, |is_reg, bytes| {
+            if is_reg {
+                Ok(Self::reg(reg?))
+            } else {
+                Ok(Self::lit(bytes[0]))
+            }
+        })
+    }
+
+    fn write(self, writer: &mut OperandWriter) {
+        writer.push(self.is_reg(), &self.value().to_be_bytes())
+    }
+
+    fn operand(self) -> InstrOperand {
+        match self {
+            Self::Reg(reg) => InstrOperand::Reg(reg),
+            Self::Lit(lit) => InstrOperand::Imm8(lit),
+        }
+    }
+}
+
+impl Operand for RegOrLit2 {
+    const TYPE: OperandType = OperandType::RegOrLit2;
+
+    fn read(reader: &mut OperandReader) -> Result<Self, InstrDecodingError> {
+        let code = reader.bytes[reader.byte];
+        let reg = reader.reg(code);
+
+        reader.next(2, |is_reg, bytes| {
+            if is_reg {
+                Ok(Self::reg(reg?))
+            } else {
+                Ok(Self::lit(u16::from_be_bytes([bytes[0], bytes[1]])))
+            }
+        })
+    }
+
+    fn write(self, writer: &mut OperandWriter) {
+        writer.push(self.is_reg(), &self.value().to_be_bytes())
+    }
+
+    fn operand(self) -> InstrOperand {
+        match self {
+            Self::Reg(reg) => InstrOperand::Reg(reg),
+            Self::Lit(lit) => InstrOperand::Imm16(lit),
+        }
+    }
+}
+
+/// Declare the instruction set: generates the ISA table as well as the instructions' decoder and encoder
+macro_rules! isa {
+    (@one $operand: ident) => { 1 };
+
+    ($(
+        $opcode: literal => $variant: ident $mnemonic: literal ($($operand: ident: $ty: ident),*)
+            $description: literal;
+    )*) => {
+        /// (Internal) Instruction set, sorted by opcode
+        static ISA: &[IsaDef] = &[$(
+            IsaDef {
+                mnemonic: $mnemonic,
+                opcode: $opcode,
+                operands: &[$((stringify!($operand), <$ty as Operand>::TYPE)),*],
+                description: $description,
+            }
+        ),*];
+
+        impl Instr {
+            /// Try to decode an assembly instruction
+            pub fn decode(bytes: [u8; 4]) -> Result<Instr, InstrDecodingError> {
+                #[allow(unused_mut, unused_variables)]
+                let mut reader = OperandReader::new(bytes);
+
+                // Decode the instruction based on its opcode (5 first bits of the first byte)
+                match bytes[0] >> 3 {
+                    $( $opcode => Ok(Self::$variant($(reader.read::<$ty>()?),*)), )*
+                    opcode => Err(InstrDecodingError::UnknownOpCode { opcode }),
+                }
+            }
+
+            /// Encode the instruction as a set of 4 bytes
+            pub fn encode(self) -> [u8; 4] {
+                #[allow(unused_mut)]
+                let mut writer = OperandWriter::new();
+
+                let opcode = match self {
+                    $( Self::$variant($($operand),*) => {
+                        $( writer.write::<$ty>($operand); )*
+                        $opcode
+                    } )*
+                };
+
+                writer.finish(opcode)
+            }
+
+            /// Get the number of operands of the instruction (between 0 and 3)
+            pub fn operand_count(&self) -> usize {
+                match self {
+                    $( Self::$variant(..) => 0 $(+ isa!(@one $operand))*, )*
+                }
+            }
+
+            /// Get the value of the instruction's operands, in order
+            pub fn operands(&self) -> Vec<InstrOperand> {
+                match *self {
+                    $( Self::$variant($($operand),*) => vec![$(<$ty as Operand>::operand($operand)),*], )*
+                }
+            }
+        }
+    };
+}
+
+isa! {
+    0x01 => Cpy "cpy" (dest: Reg, value: RegOrLit2) "Copy a value into a register";
+    0x02 => Ex "ex" (a: Reg, b: Reg) "Exchange the values of two registers";
+    0x03 => Add "add" (reg: Reg, value: RegOrLit2) "Add a value to a register";
+    0x04 => Sub "sub" (reg: Reg, value: RegOrLit2) "Subtract a value from a register";
+    0x05 => Mul "mul" (reg: Reg, value: RegOrLit2) "Multiply a register by a value";
+    0x06 => Div "div" (reg: Reg, value: RegOrLit1, mode: RegOrLit1) "Divide a register by a value, using a division mode";
+    0x07 => Mod "mod" (reg: Reg, value: RegOrLit1, mode: RegOrLit1) "Compute the remainder of a register divided by a value, using a division mode";
+    0x08 => And "and" (reg: Reg, value: RegOrLit2) "Bit-by-bit AND between a register and a value";
+    0x09 => Bor "bor" (reg: Reg, value: RegOrLit2) "Bit-by-bit OR between a register and a value";
+    0x0A => Xor "xor" (reg: Reg, value: RegOrLit2) "Bit-by-bit XOR between a register and a value";
+    0x0B => Shl "shl" (reg: Reg, bits: RegOrLit1) "Shift a register to the left";
+    0x0C => Shr "shr" (reg: Reg, bits: RegOrLit1) "Shift a register to the right";
+    0x0D => Cmp "cmp" (reg: Reg, value: RegOrLit2) "Compare a register to a value, only setting the arithmetic flags";
+    0x0E => Jpr "jpr" (offset: RegOrLit2) "Jump relatively to the instruction's address (signed offset)";
+    0x0F => Lsm "lsm" (addr: RegOrLit2) "Jump to an address and leave supervisor mode";
+    0x10 => Itr "itr" (code: RegOrLit1) "Raise an interruption";
+    0x11 => If "if" (flag: RegOrLit1) "Run the next instruction only if a flag is set";
+    0x12 => IfN "ifn" (flag: RegOrLit1) "Run the next instruction only if a flag is not set";
+    0x13 => If2 "if2" (flag_a: RegOrLit1, flag_b: RegOrLit1, cond: RegOrLit1) "Run the next instruction only if a condition on two flags is met";
+    0x14 => Lsa "lsa" (dest: Reg, addr: RegOrLit1, add: RegOrLit1) "Read the word at (addr + add) into a register";
+    0x15 => Lea "lea" (addr: RegOrLit1, add: RegOrLit1, mul: RegOrLit1) "Read the word at (addr + add * mul) into avr";
+    0x16 => Wsa "wsa" (addr: RegOrLit1, add: RegOrLit1, value: RegOrLit1) "Write a value to the word at (addr + add)";
+    0x17 => Wea "wea" (addr: RegOrLit1, add: RegOrLit1, mul: RegOrLit1) "Write avr to the word at (addr + add * mul)";
+    0x18 => Srm "srm" (addr: RegOrLit1, add: RegOrLit1, swap: Reg) "Swap a register with the word at (addr + add)";
+    0x19 => Push "push" (value: RegOrLit2) "Push a value on the stack";
+    0x1A => Pop "pop" (dest: Reg) "Pop a value from the stack into a register";
+    0x1B => Call "call" (addr: RegOrLit2) "Push the next instruction's address on the stack and jump to an address";
+    0x1C => Hwd "hwd" (dest: Reg, id: RegOrLit1, info: RegOrLit1) "Read a hardware information about a component into a register";
+    0x1D => Cycles "cycles" (dest: Reg) "Copy the number of cycles run so far into a register";
+    0x1E => Halt "halt" () "Halt the processor";
+    0x1F => Reset "reset" (mode: RegOrLit1) "Reset the processor and/or the auxiliary components";
+}
+
+/// Get the description of all instructions, sorted by opcode.
+///
+/// Each instruction is encoded in a big-endian word, made of the opcode (bits 31 to 27), one flag per operand
+/// indicating if it's a register (bits 26 to 24), followed by the operands themselves (starting at bit 23).
+pub fn isa_table() -> Vec<IsaEntry> {
+    ISA.iter()
+        .map(|def| {
+            let mut next_bit = 24;
+
+            let operands = def
+                .operands
+                .iter()
+                .enumerate()
+                .map(|(i, (name, ty))| {
+                    let bits = next_bit - ty.bits()..next_bit;
+                    next_bit = bits.start;
+
+                    IsaOperand {
+                        name: *name,
+                        ty: *ty,
+                        bits,
+                        reg_flag_bit: 26 - i as u8,
+                    }
+                })
+                .collect();
+
+            IsaEntry {
+                mnemonic: def.mnemonic,
+                opcode: def.opcode,
+                operands,
+                description: def.description,
+            }
+        })
+        .collect()
+}
+
+/// Generate a Markdown reference document of the instruction set (see [`isa_table`])
+pub fn isa_table_markdown() -> String {
+    let mut out = vec![
+        "# Instruction set".to_string(),
+        String::new(),
+        "Each instruction is encoded in a big-endian word:".to_string(),
+        String::new(),
+        "- bits 31 to 27: opcode".to_string(),
+        "- bits 26 to 24: one flag per operand, set if the operand is a register".to_string(),
+        "- bits 23 to 0: operands (register codes are put in the operand's first byte)".to_string(),
+        String::new(),
+        "| Opcode | Mnemonic | Operands | Description |".to_string(),
+        "| ------ | -------- | -------- | ----------- |".to_string(),
+    ];
+
+    for entry in isa_table() {
+        let operands = entry
+            .operands
+            .iter()
+            .map(|operand| {
+                format!(
+                    "`{}` ({}, bits {}-{})",
+                    operand.name,
+                    operand.ty.describe(),
+                    operand.bits.end - 1,
+                    operand.bits.start
+                )
+            })
+            .collect::<Vec<_>>();
+
+        out.push(format!(
+            "| `{:#04X}` | `{}` | {} | {} |",
+            entry.opcode,
+            entry.mnemonic,
+            if operands.is_empty() {
+                "-".to_string()
+            } else {
+                operands.join(", ")
+            },
+            entry.description
+        ));
+    }
+
+    out.push(String::new());
+    out.join("\n")
+}
@@ -0,0 +1,190 @@
+//! Static analysis of strongly-typed programs, reporting suspicious (yet valid) code.
+
+use super::cfg::basic_blocks;
+use super::{Instr, Program, ProgramWord, Reg, RegOrLit1, RegOrLit2};
+use std::collections::HashMap;
+use std::fmt;
+use std::ops::Range;
+
+/// Warning emitted by [`lint`]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Lint {
+    /// Index of the offending word in the program
+    pub index: usize,
+    /// Category of the warning
+    pub kind: LintKind,
+}
+
+/// Category of a [`Lint`]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum LintKind {
+    /// A general-purpose register is written, then overwritten before being read
+    DeadStore(Reg),
+    /// Instruction directly following an unconditional jump, which no other instruction jumps to
+    UnreachableAfterJump,
+    /// `HALT` instruction that can never be reached
+    UnreachableHalt,
+}
+
+impl fmt::Display for Lint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Word {} (offset {:#010X}): ", self.index, self.index * 4)?;
+
+        match self.kind {
+            LintKind::DeadStore(reg) => write!(
+                f,
+                "value written to register '{}' is overwritten before being read",
+                reg.name()
+            ),
+            LintKind::UnreachableAfterJump => write!(f, "unreachable code after jump"),
+            LintKind::UnreachableHalt => write!(f, "unreachable halt"),
+        }
+    }
+}
+
+/// Analyze a program and report suspicious code, sorted by word index.
+/// Reachability is only checked if all jump and call targets are literal addresses inside the program,
+///  considering it is loaded at address `0x00000000`. Raw words are never reported as unreachable.
+pub fn lint(prog: &Program) -> Vec<Lint> {
+    let words: Vec<&ProgramWord> = prog.prog_words().collect();
+    let blocks = basic_blocks(prog);
+
+    // Words range of each block
+    let ranges: Vec<Range<usize>> = blocks
+        .iter()
+        .enumerate()
+        .map(|(i, block)| {
+            block.start
+                ..blocks
+                    .get(i + 1)
+                    .map(|next| next.start)
+                    .unwrap_or(words.len())
+        })
+        .collect();
+
+    let mut lints = vec![];
+
+    // Find registers written twice in a row inside a basic block
+    for range in &ranges {
+        let mut pending = HashMap::new();
+
+        for (i, pword) in range.clone().zip(&words[range.clone()]) {
+            let instr = match pword {
+                ProgramWord::Instr(instr) => *instr,
+                ProgramWord::Raw(_) => continue,
+            };
+
+            for reg in read_regs(instr) {
+                pending.remove(&reg);
+            }
+
+            for reg in instr.written_regs() {
+                if !reg.is_general() {
+                    continue;
+                }
+
+                if let Some(index) = pending.insert(reg, i) {
+                    lints.push(Lint {
+                        index,
+                        kind: LintKind::DeadStore(reg),
+                    });
+                }
+            }
+        }
+    }
+
+    // Find blocks that can't be reached from the program's first word
+    if !blocks.iter().any(|block| block.unknown_target) {
+        let mut reachable = vec![false; blocks.len()];
+        let mut queue = vec![0];
+
+        while let Some(block) = queue.pop() {
+            if reachable.get(block) != Some(&false) {
+                continue;
+            }
+
+            reachable[block] = true;
+            queue.extend(blocks[block].successors.iter().map(|(next, _)| *next));
+        }
+
+        for block in (0..blocks.len()).filter(|block| !reachable[*block]) {
+            let start = blocks[block].start;
+            let after_jump = block > 0
+                && blocks[block - 1].ends_with_jump
+                && matches!(words[start], ProgramWord::Instr(_));
+
+            if after_jump {
+                lints.push(Lint {
+                    index: start,
+                    kind: LintKind::UnreachableAfterJump,
+                });
+            }
+
+            // Don't report the same word twice
+            let first_halt = if after_jump { start + 1 } else { start };
+
+            for (i, pword) in
+                (first_halt..ranges[block].end).zip(&words[first_halt..ranges[block].end])
+            {
+                if **pword == ProgramWord::Instr(Instr::Halt()) {
+                    lints.push(Lint {
+                        index: i,
+                        kind: LintKind::UnreachableHalt,
+                    });
+                }
+            }
+        }
+    }
+
+    lints.sort_by_key(|lint| lint.index);
+    lints
+}
+
+/// Get the registers read by an instruction through its operands
+fn read_regs(instr: Instr) -> Vec<Reg> {
+    let r1 = |val: RegOrLit1| match val {
+        RegOrLit1::Reg(reg) => Some(reg),
+        RegOrLit1::Lit(_) => None,
+    };
+
+    let r2 = |val: RegOrLit2| match val {
+        RegOrLit2::Reg(reg) => Some(reg),
+        RegOrLit2::Lit(_) => None,
+    };
+
+    match instr {
+        Instr::Cpy(_, b) | Instr::Jpr(b) | Instr::Lsm(b) | Instr::Push(b) | Instr::Call(b) => {
+            r2(b).into_iter().collect()
+        }
+
+        Instr::Add(a, b)
+        | Instr::Sub(a, b)
+        | Instr::Mul(a, b)
+        | Instr::And(a, b)
+        | Instr::Bor(a, b)
+        | Instr::Xor(a, b)
+        | Instr::Cmp(a, b) => Some(a).into_iter().chain(r2(b)).collect(),
+
+        Instr::Shl(a, b) | Instr::Shr(a, b) => Some(a).into_iter().chain(r1(b)).collect(),
+
+        Instr::Div(a, b, c) | Instr::Mod(a, b, c) => {
+            Some(a).into_iter().chain(r1(b)).chain(r1(c)).collect()
+        }
+
+        Instr::Ex(a, b) => vec![a, b],
+
+        Instr::Itr(a) | Instr::If(a) | Instr::IfN(a) | Instr::Reset(a) => {
+            r1(a).into_iter().collect()
+        }
+
+        Instr::If2(a, b, c) | Instr::Lea(a, b, c) | Instr::Wsa(a, b, c) | Instr::Wea(a, b, c) => {
+            r1(a).into_iter().chain(r1(b)).chain(r1(c)).collect()
+        }
+
+        Instr::Lsa(_, b, c) | Instr::Hwd(_, b, c) => r1(b).into_iter().chain(r1(c)).collect(),
+
+        Instr::Srm(a, b, c) => r1(a).into_iter().chain(r1(b)).chain(Some(c)).collect(),
+
+        Instr::Pop(_) | Instr::Cycles(_) | Instr::Halt() => vec![],
+    }
+}
@@ -2,25 +2,51 @@
 //! and guarantee them to be valid at build time.
 
 pub mod cst;
+pub mod frame;
 
 mod arflag;
+mod cfg;
+mod checksum;
 mod cond;
+mod container;
+mod decoder;
+mod disasm;
 mod div_modes;
+mod exception_table;
 mod extinstr;
 mod hw_infos;
 mod instr;
+mod isa;
+mod lint;
 mod prog;
 mod prog_word;
+mod pseudo;
+mod rebase;
 mod reg;
+mod size;
 mod val;
+mod visitor;
 
 pub use arflag::ArFlag;
+pub use checksum::ChecksumError;
 pub use cond::If2Cond;
+pub use container::{
+    ContainerError, ContainerMeta, CONTAINER_HEADER_SIZE, CONTAINER_MAGIC, CONTAINER_VERSION,
+};
+pub use decoder::CachedDecoder;
+pub use disasm::{disassemble_file, disassemble_to_string, DisasmError, DisasmOptions, RawData};
 pub use div_modes::{DivByZeroMode, DivMode, DivOverflowMode, DivSignMode};
+pub use exception_table::ExceptionTable;
 pub use extinstr::ExtInstr;
 pub use hw_infos::HwInfo;
-pub use instr::{Instr, InstrDecodingError};
+pub use instr::{Instr, InstrDecodingError, InstrEncodingError};
+pub use isa::{isa_table, isa_table_markdown, InstrOperand, IsaEntry, IsaOperand, OperandType};
+pub use lint::{lint, Lint, LintKind};
 pub use prog::Program;
 pub use prog_word::ProgramWord;
+pub use pseudo::PseudoInstr;
+pub use rebase::RebaseError;
 pub use reg::Reg;
+pub use size::ProgramTooLarge;
 pub use val::{RegOrLit1, RegOrLit2};
+pub use visitor::ProgramVisitor;
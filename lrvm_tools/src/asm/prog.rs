@@ -1,7 +1,13 @@
 //! The [`Program`] struct allows to represent a strongly-typed assembly program.
 //! If the program builds, then it's guaranteed to be correct and does not need a runtime validation.
 
-use super::{Instr, InstrDecodingError, ProgramWord};
+use super::cfg::basic_blocks;
+use super::{ExtInstr, Instr, InstrDecodingError, ProgramWord, PseudoInstr, Reg, RegOrLit2};
+use std::convert::TryFrom;
+use std::ops::Range;
+
+/// Number of words decoded between two progress reports in [`Program::decode_with_progress`]
+const DECODE_PROGRESS_INTERVAL: usize = 4096;
 
 /// Strongly-typed assembly program
 #[derive(Default, Debug, Clone, PartialEq, Eq)]
@@ -33,6 +39,25 @@ impl Program {
         self.0.iter()
     }
 
+    /// Get the word at the provided address (in bytes, relative to the program's start)
+    /// Returns `None` if the address is not aligned or is out of the program's bounds.
+    pub fn instruction_at_byte_addr(&self, byte_addr: u32) -> Option<&ProgramWord> {
+        if byte_addr % 4 != 0 {
+            return None;
+        }
+
+        self.0.get(usize::try_from(byte_addr / 4).ok()?)
+    }
+
+    /// Get a mutable reference to the word at the provided address (see [`Program::instruction_at_byte_addr`])
+    pub fn instruction_at_byte_addr_mut(&mut self, byte_addr: u32) -> Option<&mut ProgramWord> {
+        if byte_addr % 4 != 0 {
+            return None;
+        }
+
+        self.0.get_mut(usize::try_from(byte_addr / 4).ok()?)
+    }
+
     /// Prepend an instruction at the beginning of the program
     pub fn prepend(&mut self, instr: ProgramWord) -> &mut Self {
         self.0.insert(0, instr);
@@ -43,9 +68,8 @@ impl Program {
     pub fn prepend_all(&mut self, instr: impl AsRef<[ProgramWord]>) -> &mut Self {
         let instr = instr.as_ref();
 
-        let tail = self.0.len() - instr.len();
         self.0.extend(instr);
-        self.0[instr.len()..].rotate_left(tail);
+        self.0.rotate_right(instr.len());
 
         self
     }
@@ -62,12 +86,249 @@ impl Program {
         self
     }
 
+    /// Count the raw data words of the program
+    pub fn count_raw(&self) -> usize {
+        self.0
+            .iter()
+            .filter(|pword| matches!(pword, ProgramWord::Raw(_)))
+            .count()
+    }
+
+    /// Get the ranges of contiguous raw data words of the program (by word index)
+    pub fn raw_ranges(&self) -> Vec<Range<usize>> {
+        let mut ranges: Vec<Range<usize>> = vec![];
+
+        for (i, pword) in self.0.iter().enumerate() {
+            if let ProgramWord::Raw(_) = pword {
+                match ranges.last_mut() {
+                    Some(range) if range.end == i => range.end += 1,
+                    _ => ranges.push(i..i + 1),
+                }
+            }
+        }
+
+        ranges
+    }
+
+    /// Split the program into two independent programs, the second one starting at the provided word index.
+    /// Absolute jump and call targets are not adjusted, so the second program should be relocated (see [`Program::relocate`])
+    /// if it's not loaded right after the first one.
+    /// Panics if the index is greater than the program's size.
+    pub fn split_at(&self, index: usize) -> (Program, Program) {
+        let (first, second) = self.0.split_at(index);
+        (Self::from(first.to_vec()), Self::from(second.to_vec()))
+    }
+
+    /// Reverse the order of the program's words
+    pub fn reverse(&mut self) -> &mut Self {
+        self.0.reverse();
+        self
+    }
+
+    /// Shift the program's words left by a given number of positions, the first words being moved at the end of the program.
+    /// Rotating by more than the program's size wraps around.
+    pub fn rotate_left(&mut self, n: usize) -> &mut Self {
+        if !self.0.is_empty() {
+            let len = self.0.len();
+            self.0.rotate_left(n % len);
+        }
+
+        self
+    }
+
+    /// Remove all canonical no-op instructions (see [`Instr::nop`]) from the program.
+    /// Note that the following words are moved, so relative jumps over removed no-ops and absolute addresses are not valid anymore.
+    pub fn strip_nops(&mut self) -> &mut Self {
+        self.0
+            .retain(|pword| !matches!(pword, ProgramWord::Instr(instr) if instr.is_nop()));
+        self
+    }
+
+    /// Create a new program by applying a function to each instruction, raw data words being kept as they are
+    pub fn map_instrs(self, f: impl Fn(Instr) -> Instr) -> Program {
+        Self::from(
+            self.0
+                .into_iter()
+                .map(|pword| match pword {
+                    ProgramWord::Instr(instr) => ProgramWord::Instr(f(instr)),
+                    ProgramWord::Raw(bytes) => ProgramWord::Raw(bytes),
+                })
+                .collect(),
+        )
+    }
+
+    /// Expand an extended instruction and append it at the end of the program
+    pub fn append_ext(&mut self, ext: ExtInstr) -> &mut Self {
+        self.append_pseudo(&ext)
+    }
+
+    /// Expand a list of extended instructions and append them at the end of the program
+    pub fn append_ext_all(&mut self, exts: impl AsRef<[ExtInstr]>) -> &mut Self {
+        for ext in exts.as_ref() {
+            self.append_ext(*ext);
+        }
+
+        self
+    }
+
+    /// Expand an extended instruction and prepend it at the beginning of the program
+    pub fn prepend_ext(&mut self, ext: ExtInstr) -> &mut Self {
+        let instr: Vec<ProgramWord> = ext.to_instr().into_iter().map(ProgramWord::Instr).collect();
+        self.prepend_all(instr)
+    }
+
+    /// Expand a pseudo-instruction and append it at the end of the program
+    /// Panics if the pseudo-instruction's expansion is not [`PseudoInstr::len_words`] long.
+    pub fn append_pseudo(&mut self, pseudo: &dyn PseudoInstr) -> &mut Self {
+        let instr = pseudo.expand();
+
+        assert_eq!(
+            instr.len(),
+            pseudo.len_words(),
+            "Pseudo-instruction's expansion length does not match its declared length"
+        );
+
+        self.0.extend(instr.into_iter().map(ProgramWord::Instr));
+        self
+    }
+
+    /// Expand a list of pseudo-instructions and append them at the end of the program
+    /// Panics if one of the pseudo-instructions' expansion is not [`PseudoInstr::len_words`] long.
+    pub fn append_all_pseudo(&mut self, pseudo: &[Box<dyn PseudoInstr>]) -> &mut Self {
+        for pseudo in pseudo {
+            self.append_pseudo(pseudo.as_ref());
+        }
+
+        self
+    }
+
+    /// Shift the absolute jump and call targets of the program by a given number of bytes,
+    /// so it can be loaded at a different address.
+    /// Only the targets pointing inside the program (as if it was loaded at address `0x00000000`) are relocated.
+    /// Panics if a relocated target does not fit in 16 bits.
+    pub fn relocate(&mut self, offset: u16) -> &mut Self {
+        let len = self.size();
+
+        for pword in self.0.iter_mut() {
+            let addr = match pword {
+                ProgramWord::Instr(Instr::Cpy(Reg::pc, RegOrLit2::Lit(addr)))
+                | ProgramWord::Instr(Instr::Lsm(RegOrLit2::Lit(addr)))
+                | ProgramWord::Instr(Instr::Call(RegOrLit2::Lit(addr))) => addr,
+                _ => continue,
+            };
+
+            if *addr % 4 == 0 && usize::from(*addr / 4) < len {
+                *addr = addr
+                    .checked_add(offset)
+                    .expect("Relocated target does not fit in 16 bits");
+            }
+        }
+
+        self
+    }
+
+    /// Prepend a jump to the instruction at the given index, which becomes the program's entry point.
+    /// The existing absolute targets are relocated accordingly.
+    /// Panics if the index is out of the program's bounds, or if the entry point is more than 0x7FFF bytes away.
+    pub fn with_entry_jump(&mut self, entry_word_index: usize) -> &mut Self {
+        assert!(
+            entry_word_index < self.size(),
+            "Entry point is out of the program's bounds"
+        );
+
+        // The CPU reads JPR's offset as a signed value
+        let target = i16::try_from((entry_word_index + 1) * 4)
+            .expect("Entry point is too far to be jumped to");
+
+        self.relocate(4)
+            .prepend(ProgramWord::Instr(Instr::Jpr(target.into())))
+    }
+
+    /// List the instructions writing a special-purpose register (see [`Reg::is_special`]), as (word index, register) pairs.
+    /// Jumps (`CPY pc, ...`) are reported as well, so they should be filtered out by callers allowing them.
+    pub fn check_special_writes(&self) -> Vec<(usize, Reg)> {
+        self.0
+            .iter()
+            .enumerate()
+            .filter_map(|(i, pword)| match pword {
+                ProgramWord::Instr(instr) => Some((i, *instr)),
+                ProgramWord::Raw(_) => None,
+            })
+            .flat_map(|(i, instr)| {
+                instr
+                    .written_regs()
+                    .into_iter()
+                    .filter(|reg| reg.is_special())
+                    .map(move |reg| (i, reg))
+            })
+            .collect()
+    }
+
+    /// Check if the program contains an obvious infinite loop: a set of instructions reachable from the first word
+    /// that only jump between each other (e.g. a jump to itself or an unconditional backward jump), without any halt, conditional
+    /// instruction or jump to a location that can't be determined statically.
+    ///
+    /// This is a heuristic: loops only exited through an exception or an interruption are reported, while loops whose exit condition
+    /// can never be met are not. Jump targets are only known for literal addresses, considering the program is loaded at address `0x00000000`.
+    pub fn contains_infinite_loop(&self) -> bool {
+        let blocks = basic_blocks(self);
+
+        // Find the blocks that may lead out of the program or stop it
+        let mut can_exit: Vec<bool> = blocks
+            .iter()
+            .map(|block| block.successors.is_empty() || block.unknown_target)
+            .collect();
+
+        let mut changed = true;
+
+        while changed {
+            changed = false;
+
+            for (i, block) in blocks.iter().enumerate() {
+                if !can_exit[i] && block.successors.iter().any(|(next, _)| can_exit[*next]) {
+                    can_exit[i] = true;
+                    changed = true;
+                }
+            }
+        }
+
+        // Check if a block that can't exit is reachable from the program's first word
+        let mut reachable = vec![false; blocks.len()];
+        let mut queue = if blocks.is_empty() { vec![] } else { vec![0] };
+
+        while let Some(block) = queue.pop() {
+            if reachable[block] {
+                continue;
+            }
+
+            if !can_exit[block] {
+                return true;
+            }
+
+            reachable[block] = true;
+            queue.extend(blocks[block].successors.iter().map(|(next, _)| *next));
+        }
+
+        false
+    }
+
     /// Disassemble a machine code into a program.
     /// In case of error, returns a tuple containing the faulty instruction's index along with the decoding error.
     /// Raw data can be forbidden to ensure strict checking of instructions.
     pub fn decode(
         prog: impl AsRef<[u8]>,
         forbid_raw: bool,
+    ) -> Result<Self, (usize, InstrDecodingError)> {
+        Self::decode_with_progress(prog, forbid_raw, |_, _| {})
+    }
+
+    /// Disassemble a machine code into a program like [`Program::decode`], reporting the progress to the provided callback
+    /// with the number of decoded words and the total number of words.
+    /// The callback is called every 4096 words, and once all words are decoded (not called if the decoding fails).
+    pub fn decode_with_progress(
+        prog: impl AsRef<[u8]>,
+        forbid_raw: bool,
+        mut progress: impl FnMut(usize, usize),
     ) -> Result<Self, (usize, InstrDecodingError)> {
         let prog = prog.as_ref();
 
@@ -76,10 +337,15 @@ impl Program {
             return Err((0, InstrDecodingError::SourceNotMultipleOf4Bytes));
         }
 
-        let mut out = vec![];
+        let total = prog.len() / 4;
+        let mut out = Vec::with_capacity(total);
 
         // Decode all instructions (each instruction being on 4 bytes)
-        for i in 0..prog.len() / 4 {
+        for i in 0..total {
+            if i % DECODE_PROGRESS_INTERVAL == 0 && i > 0 {
+                progress(i, total);
+            }
+
             let bytes = [
                 prog[i * 4],
                 prog[i * 4 + 1],
@@ -101,6 +367,8 @@ impl Program {
             out.push(pword);
         }
 
+        progress(total, total);
+
         Ok(Self::from(out))
     }
 
@@ -125,6 +393,20 @@ impl Program {
         self.prog_words().map(|pword| pword.encode_word()).collect()
     }
 
+    /// Compute the XOR of all the program's encoded words (simple checksum)
+    pub fn xor_words(&self) -> u32 {
+        self.encode_words()
+            .into_iter()
+            .fold(0, |acc, word| acc ^ word)
+    }
+
+    /// Compute the wrapping sum of all the program's encoded words (simple checksum)
+    pub fn sum_words(&self) -> u32 {
+        self.encode_words()
+            .into_iter()
+            .fold(0, |acc, word| acc.wrapping_add(word))
+    }
+
     /// Convert the program to a LASM source code
     pub fn to_lasm(&self, annotate_instr_addr: bool) -> String {
         if !annotate_instr_addr {
@@ -151,4 +433,39 @@ impl Program {
             })
             .collect()
     }
+
+    /// Render the program's control flow graph as a Graphviz DOT file.
+    /// Each node is a basic block, labelled with its first instruction's mnemonic and byte offset.
+    /// Jump and call targets are only known for literal addresses, considering the program is loaded at address `0x00000000`.
+    pub fn to_dot_graph(&self) -> String {
+        let mut out = vec![
+            "digraph program {".to_string(),
+            "    node [shape=box, fontname=\"monospace\"];".to_string(),
+        ];
+
+        let blocks = basic_blocks(self);
+
+        for (i, block) in blocks.iter().enumerate() {
+            let lasm = self.0[block.start].to_lasm();
+
+            out.push(format!(
+                "    b{} [label=\"{:#010X}: {}\"];",
+                i,
+                block.start * 4,
+                lasm.split_whitespace().next().unwrap_or("")
+            ));
+        }
+
+        for (i, block) in blocks.iter().enumerate() {
+            for (successor, label) in &block.successors {
+                out.push(match label {
+                    Some(label) => format!("    b{} -> b{} [label=\"{}\"];", i, successor, label),
+                    None => format!("    b{} -> b{};", i, successor),
+                });
+            }
+        }
+
+        out.push("}".to_string());
+        out.join("\n")
+    }
 }
@@ -1,4 +1,5 @@
 use super::Instr;
+use crate::bytes::{bytes_be_to_u32, u32_to_bytes_be};
 
 /// A single word in a strongly-typed program
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -16,7 +17,7 @@ impl ProgramWord {
     }
 
     pub fn decode_word(&self, word: u32) -> Self {
-        Self::decode(word.to_be_bytes())
+        Self::decode(u32_to_bytes_be(word))
     }
 
     pub fn is_instr(&self) -> bool {
@@ -37,7 +38,7 @@ impl ProgramWord {
     pub fn encode_word(&self) -> u32 {
         match self {
             Self::Instr(instr) => instr.encode_word(),
-            Self::Raw(bytes) => u32::from_be_bytes(*bytes),
+            Self::Raw(bytes) => bytes_be_to_u32(*bytes),
         }
     }
 
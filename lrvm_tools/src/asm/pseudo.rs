@@ -0,0 +1,25 @@
+//! Pseudo-instructions are user-defined instructions expanding into a fixed sequence of native instructions.
+
+use super::{ExtInstr, Instr};
+
+/// Pseudo-instruction, expanding into a sequence of native instructions.
+///
+/// The expansion's length must only depend on the pseudo-instruction itself and must always be equal to [`PseudoInstr::len_words`],
+/// so addresses can be computed before the program is expanded.
+pub trait PseudoInstr {
+    /// Expand the pseudo-instruction into native instructions
+    fn expand(&self) -> Vec<Instr>;
+
+    /// Get the number of words the pseudo-instruction expands into
+    fn len_words(&self) -> usize;
+}
+
+impl PseudoInstr for ExtInstr {
+    fn expand(&self) -> Vec<Instr> {
+        self.to_instr()
+    }
+
+    fn len_words(&self) -> usize {
+        self.to_instr().len()
+    }
+}
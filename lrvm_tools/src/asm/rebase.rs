@@ -0,0 +1,100 @@
+use super::{Instr, Program, ProgramWord, Reg, RegOrLit2};
+use std::convert::TryFrom;
+use std::fmt;
+
+/// Error returned by [`Program::concat_rebased`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RebaseError {
+    /// The prelude is too large for the main program's targets to be shifted by its size
+    PreludeTooLarge { size: usize },
+    /// A jump or call of the main program targets an address stored in a register, which can't be rebased
+    RegisterTarget { index: usize, reg: Reg },
+    /// A jump or call of the main program targets an address outside of it, which may not be an address of the program
+    TargetOutsideProgram { index: usize, addr: u16 },
+    /// A rebased target does not fit in 16 bits
+    TargetOverflow { index: usize, addr: u16 },
+}
+
+impl fmt::Display for RebaseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::PreludeTooLarge { size } => write!(
+                f,
+                "Prelude is {} bytes long, which is too large to rebase 16-bit targets",
+                size
+            ),
+            Self::RegisterTarget { index, reg } => write!(
+                f,
+                "Word {} (offset {:#010X}) of the main program targets the address stored in register '{}', which can't be rebased",
+                index,
+                index * 4,
+                reg.name()
+            ),
+            Self::TargetOutsideProgram { index, addr } => write!(
+                f,
+                "Word {} (offset {:#010X}) of the main program targets address {:#06X}, which is outside of it",
+                index,
+                index * 4,
+                addr
+            ),
+            Self::TargetOverflow { index, addr } => write!(
+                f,
+                "Word {} (offset {:#010X}) of the main program targets address {:#06X}, which does not fit in 16 bits once rebased",
+                index,
+                index * 4,
+                addr
+            ),
+        }
+    }
+}
+
+impl Program {
+    /// Concatenate a prelude (e.g. runtime support routines) and a main program, shifting the absolute jump and call targets
+    /// of the main program by the prelude's size. Relative jumps are left untouched.
+    ///
+    /// Fails if a target of the main program can't be proven to be an address inside of it (jumps and calls to a register
+    /// or to a literal address outside the main program), as it couldn't be rebased safely.
+    /// Raw words are copied as they are.
+    pub fn concat_rebased(prelude: &Program, main: &Program) -> Result<Program, RebaseError> {
+        let offset =
+            u16::try_from(prelude.size() * 4).map_err(|_| RebaseError::PreludeTooLarge {
+                size: prelude.size() * 4,
+            })?;
+
+        let mut out = prelude.0.clone();
+        out.reserve(main.size());
+
+        for (index, pword) in main.prog_words().enumerate() {
+            let mut pword = *pword;
+
+            let target = match &mut pword {
+                ProgramWord::Instr(Instr::Cpy(Reg::pc, target))
+                | ProgramWord::Instr(Instr::Lsm(target))
+                | ProgramWord::Instr(Instr::Call(target)) => Some(target),
+                _ => None,
+            };
+
+            match target {
+                Some(RegOrLit2::Reg(reg)) => {
+                    return Err(RebaseError::RegisterTarget { index, reg: *reg })
+                }
+
+                Some(RegOrLit2::Lit(addr)) => {
+                    if *addr % 4 != 0 || usize::from(*addr / 4) >= main.size() {
+                        return Err(RebaseError::TargetOutsideProgram { index, addr: *addr });
+                    }
+
+                    *addr = addr
+                        .checked_add(offset)
+                        .ok_or(RebaseError::TargetOverflow { index, addr: *addr })?;
+                }
+
+                None => {}
+            }
+
+            out.push(pword);
+        }
+
+        Ok(Program::from(out))
+    }
+}
@@ -196,6 +196,16 @@ impl Reg {
         }
     }
 
+    /// Check if the register is a general-purpose one (arguments, call-saved, accumulators and return registers)
+    pub fn is_general(self) -> bool {
+        self.code() <= Self::rr7.code()
+    }
+
+    /// Check if the register is a special-purpose one (flags, program counter, stack pointers, exceptions, ...)
+    pub fn is_special(self) -> bool {
+        !self.is_general()
+    }
+
     /// Convert the register to a register-or-1-byte-literal parameter
     pub fn to_roc_1(self) -> RegOrLit1 {
         RegOrLit1::reg(self)
@@ -0,0 +1,46 @@
+use super::Program;
+use std::fmt;
+
+/// Error returned when a program doesn't fit in the space it's meant to be loaded in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ProgramTooLarge {
+    /// Size of the program, in bytes
+    pub size: usize,
+    /// Maximum allowed size, in bytes
+    pub max_size: u32,
+}
+
+impl ProgramTooLarge {
+    /// Check if a program of the provided size (in bytes) fits in the provided maximum size (in bytes)
+    pub fn check(size: usize, max_size: u32) -> Result<(), Self> {
+        if size > max_size as usize {
+            Err(Self { size, max_size })
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Get the number of bytes exceeding the maximum size
+    pub fn overflow(&self) -> usize {
+        self.size - self.max_size as usize
+    }
+}
+
+impl fmt::Display for ProgramTooLarge {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Program is {} bytes long, which exceeds the maximum size of {} bytes by {} bytes",
+            self.size,
+            self.max_size,
+            self.overflow()
+        )
+    }
+}
+
+impl Program {
+    /// Check if the encoded program fits in the provided size (in bytes)
+    pub fn check_size(&self, max_size: u32) -> Result<(), ProgramTooLarge> {
+        ProgramTooLarge::check(self.size() * 4, max_size)
+    }
+}
@@ -0,0 +1,25 @@
+//! Visitors allow to write analysis passes over programs without matching program words manually.
+
+use super::{Instr, Program, ProgramWord};
+
+/// Program visitor, called on each word of a program (see [`Program::visit`]).
+/// All methods do nothing by default, so visitors only implement the ones they need.
+pub trait ProgramVisitor {
+    /// Visit an instruction, with the index of its word in the program
+    fn visit_instr(&mut self, _index: usize, _instr: &Instr) {}
+
+    /// Visit a raw word, with its index in the program
+    fn visit_raw(&mut self, _index: usize, _raw: &[u8; 4]) {}
+}
+
+impl Program {
+    /// Call a visitor on each word of the program, in order
+    pub fn visit(&self, v: &mut impl ProgramVisitor) {
+        for (index, pword) in self.prog_words().enumerate() {
+            match pword {
+                ProgramWord::Instr(instr) => v.visit_instr(index, instr),
+                ProgramWord::Raw(raw) => v.visit_raw(index, raw),
+            }
+        }
+    }
+}
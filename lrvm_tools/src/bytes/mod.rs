@@ -1,35 +1,104 @@
 //! Utilities to deal with bytes
 
+use std::string::FromUtf8Error;
+
 /// Convert a list of bytes to a list of words
 pub fn bytes_to_words(bytes: impl AsRef<[u8]>) -> Vec<u32> {
     let bytes = bytes.as_ref();
 
-    let rem = bytes.len() % 4;
-    let mut words = Vec::with_capacity(bytes.len() / 4 + if rem == 0 { 1 } else { 0 });
-    let mut word = 0;
-
-    for (i, byte) in bytes.iter().enumerate() {
-        word += u32::from(*byte) << ((3 - (i % 4)) * 8);
+    // One word per 4 bytes, plus one for the incomplete last chunk (if any)
+    let capacity = (bytes.len() + 3) / 4;
+    let mut words = Vec::with_capacity(capacity);
 
-        if i % 4 == 3 {
-            words.push(word);
-            word = 0;
-        }
+    // The last chunk is padded with zeros if it's incomplete
+    for chunk in bytes.chunks(4) {
+        let mut word = [0; 4];
+        word[..chunk.len()].copy_from_slice(chunk);
+        words.push(bytes_be_to_u32(word));
     }
 
-    if rem != 0 {
-        words.push(word << ((4 - rem) * 8));
-    }
+    debug_assert_eq!(words.len(), capacity);
 
     words
 }
 
+/// Convert a word to bytes (strongest byte first)
+#[inline(always)]
+pub fn u32_to_bytes_be(word: u32) -> [u8; 4] {
+    word.to_be_bytes()
+}
+
+/// Convert bytes (strongest byte first) to a word
+#[inline(always)]
+pub fn bytes_be_to_u32(bytes: [u8; 4]) -> u32 {
+    u32::from_be_bytes(bytes)
+}
+
 /// Convert a list of words to a list of bytes
 pub fn words_to_bytes(bytes: impl AsRef<[u32]>) -> Vec<u8> {
     bytes
         .as_ref()
         .iter()
-        .map(|word| word.to_be_bytes().to_vec())
+        .map(|word| u32_to_bytes_be(*word).to_vec())
         .flatten()
         .collect()
 }
+
+/// Decode a null-terminated UTF-8 string packed into words (strongest byte first).
+/// The string stops at the first null byte, or at the end of the words if there is none.
+pub fn decode_utf8_words(words: &[u32]) -> Result<String, FromUtf8Error> {
+    let mut bytes = words_to_bytes(words);
+
+    if let Some(len) = bytes.iter().position(|byte| *byte == 0) {
+        bytes.truncate(len);
+    }
+
+    String::from_utf8(bytes)
+}
+
+/// Encode a string as null-terminated UTF-8 packed into words (strongest byte first).
+/// The last word is padded with null bytes, and the string is always followed by at least one of them.
+pub fn encode_utf8_words(s: &str) -> Vec<u32> {
+    let mut bytes = s.as_bytes().to_vec();
+    bytes.push(0);
+    bytes_to_words(bytes)
+}
+
+/// Read a big-endian word at the provided byte offset.
+/// Returns `None` if the 4-byte window does not fit in the buffer.
+pub fn read_word_at(buf: &[u8], offset: usize) -> Option<u32> {
+    let window = buf.get(offset..offset.checked_add(4)?)?;
+    Some(bytes_be_to_u32([
+        window[0], window[1], window[2], window[3],
+    ]))
+}
+
+/// Write a big-endian word at the provided byte offset.
+/// Fails if the 4-byte window does not fit in the buffer (in which case the buffer is left untouched).
+pub fn write_word_at(buf: &mut [u8], offset: usize, word: u32) -> Result<(), ()> {
+    let end = offset.checked_add(4).ok_or(())?;
+    let window = buf.get_mut(offset..end).ok_or(())?;
+    window.copy_from_slice(&u32_to_bytes_be(word));
+    Ok(())
+}
+
+/// Pack two half-words into a word (high half-word first)
+pub fn pack_u16_be(high: u16, low: u16) -> u32 {
+    (u32::from(high) << 16) | u32::from(low)
+}
+
+/// Unpack a word into two half-words (high half-word first)
+pub fn unpack_u16_be(word: u32) -> (u16, u16) {
+    ((word >> 16) as u16, word as u16)
+}
+
+/// Pack four bytes into a word (strongest byte first)
+pub fn pack_u8x4(b0: u8, b1: u8, b2: u8, b3: u8) -> u32 {
+    bytes_be_to_u32([b0, b1, b2, b3])
+}
+
+/// Unpack a word into four bytes (strongest byte first)
+pub fn unpack_u8x4(word: u32) -> (u8, u8, u8, u8) {
+    let [b0, b1, b2, b3] = u32_to_bytes_be(word);
+    (b0, b1, b2, b3)
+}
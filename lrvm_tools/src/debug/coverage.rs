@@ -0,0 +1,119 @@
+use super::StoppedState;
+use crate::asm::Program;
+use lrvm::board::MotherBoard;
+use std::collections::BTreeSet;
+
+/// Range of addresses a component is mapped to
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CoverageRegion {
+    /// Name of the component
+    pub name: String,
+    /// Start address (included)
+    pub start_addr: u32,
+    /// End address (included)
+    pub end_addr: u32,
+}
+
+/// Addresses of the instructions run by the VM (see [`super::RunConfig::with_coverage`])
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CoverageReport {
+    /// Absolute address of each instruction that was run at least once
+    pub executed: BTreeSet<u32>,
+    /// Components mapped in memory, to group the addresses by component.
+    /// Only filled by the runners preparing the motherboard themselves (e.g. [`super::exec_vm`]).
+    pub regions: Vec<CoverageRegion>,
+}
+
+impl CoverageReport {
+    /// Check if the instruction at the provided address was run at least once
+    pub fn was_executed(&self, addr: u32) -> bool {
+        self.executed.contains(&addr)
+    }
+
+    /// Get the addresses of the instructions run in each component, in mapping order.
+    /// Addresses outside of all regions (if any) are not included.
+    pub fn by_region(&self) -> Vec<(&CoverageRegion, Vec<u32>)> {
+        self.regions
+            .iter()
+            .map(|region| {
+                let addrs = self
+                    .executed
+                    .range(region.start_addr..=region.end_addr)
+                    .copied()
+                    .collect();
+
+                (region, addrs)
+            })
+            .collect()
+    }
+
+    /// Disassemble a program located at address `0x00000000` (e.g. in a boot ROM mapped first), see [`CoverageReport::annotate_at`]
+    pub fn annotate(&self, program: &Program) -> String {
+        self.annotate_at(program, 0x0000_0000)
+    }
+
+    /// Disassemble a program located at the provided address, marking the instructions that were run with a `>`.
+    /// Ends with a summary line giving the ratio of instructions that were run (raw data words are not counted).
+    pub fn annotate_at(&self, program: &Program, base_addr: u32) -> String {
+        let mut lines = vec![];
+        let mut instr = 0;
+        let mut executed = 0;
+
+        for (i, pword) in program.prog_words().enumerate() {
+            let addr = base_addr.wrapping_add(i as u32 * 4);
+            let was_executed = self.was_executed(addr);
+
+            if pword.is_instr() {
+                instr += 1;
+
+                if was_executed {
+                    executed += 1;
+                }
+            }
+
+            lines.push(format!(
+                "{} {:#010X}: {}",
+                if was_executed { '>' } else { ' ' },
+                addr,
+                pword.to_lasm()
+            ));
+        }
+
+        lines.push(format!(
+            "=> {} of {} instructions executed ({:.1}%)",
+            executed,
+            instr,
+            if instr == 0 {
+                0.0
+            } else {
+                executed as f64 * 100.0 / instr as f64
+            }
+        ));
+
+        lines.join("\n")
+    }
+}
+
+/// (Internal) Fill the regions of a stopped state's coverage report (if any) with the motherboard's mapping
+pub(super) fn attach_regions(motherboard: &mut MotherBoard, state: &mut StoppedState) {
+    let coverage = match &mut state.coverage {
+        Some(coverage) => coverage,
+        None => return,
+    };
+
+    let count = motherboard.count();
+
+    coverage.regions = motherboard.map(|mem| {
+        (0..count)
+            .filter_map(|aux_id| {
+                let mapping = mem.get_mapping(aux_id)?;
+
+                Some(CoverageRegion {
+                    name: mem.bridge().name_of(aux_id)?.clone(),
+                    start_addr: mapping.addr,
+                    end_addr: mapping.end_addr(),
+                })
+            })
+            .collect()
+    });
+}
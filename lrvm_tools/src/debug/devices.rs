@@ -0,0 +1,87 @@
+use crate::bytes::words_to_bytes;
+use crate::metadata::DeviceCategory;
+use lrvm::board::MotherBoard;
+
+/// Size of a single device's entry in a device table, in words
+/// See [`crate::asm::ExtInstr::EnumerateDevices`] for the table's format.
+pub const DEVICE_TABLE_ENTRY_WORDS: usize = 19;
+
+/// Device connected to a motherboard, along with its mapping
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MappedDevice {
+    /// Component's ID
+    pub aux_id: usize,
+    /// Component's generic name
+    pub name: String,
+    /// Component's hardware identifier
+    pub hw_id: u64,
+    /// Component's category (raw code if it cannot be decoded)
+    pub category: Result<DeviceCategory, u64>,
+    /// Component's size, in bytes
+    pub size: u32,
+    /// Component's start and end addresses, if it's mapped in memory
+    pub mapping: Option<(u32, u32)>,
+}
+
+/// Get informations on all the components connected to a motherboard, as seen from the host
+pub fn enumerate_devices(motherboard: &mut MotherBoard) -> Vec<MappedDevice> {
+    motherboard.map(|mem| {
+        (0..mem.bridge().count())
+            .map(|aux_id| {
+                let cache = mem.bridge().cache_of(aux_id).unwrap();
+                let code = ((cache.metadata[3] as u64) << 32) + cache.metadata[4] as u64;
+
+                MappedDevice {
+                    aux_id,
+                    name: cache.name.clone(),
+                    hw_id: cache.hw_id,
+                    category: DeviceCategory::decode(code).map_err(|()| code),
+                    size: cache.size,
+                    mapping: mem
+                        .get_mapping(aux_id)
+                        .map(|mapping| (mapping.addr, mapping.end_addr())),
+                }
+            })
+            .collect()
+    })
+}
+
+/// Decode a device table written by a guest program (see [`crate::asm::ExtInstr::EnumerateDevices`]).
+/// Fails if the table is truncated, or if its number of entries is too large to be addressed.
+pub fn decode_device_table(table: &[u32]) -> Result<Vec<MappedDevice>, ()> {
+    let count = *table.first().ok_or(())? as usize;
+
+    let end = count
+        .checked_mul(DEVICE_TABLE_ENTRY_WORDS)
+        .and_then(|len| len.checked_add(1))
+        .ok_or(())?;
+
+    let entries = table.get(1..end).ok_or(())?;
+
+    Ok(entries
+        .chunks(DEVICE_TABLE_ENTRY_WORDS)
+        .enumerate()
+        .map(|(aux_id, entry)| {
+            let mut name = words_to_bytes(&entry[2..10]);
+
+            while name.last() == Some(&0) {
+                name.pop();
+            }
+
+            let code = ((entry[11] as u64) << 32) + entry[12] as u64;
+
+            MappedDevice {
+                aux_id,
+                name: String::from_utf8_lossy(&name).to_string(),
+                hw_id: ((entry[0] as u64) << 32) + entry[1] as u64,
+                category: DeviceCategory::decode(code).map_err(|()| code),
+                size: entry[10],
+                mapping: if entry[16] == 0 {
+                    None
+                } else {
+                    Some((entry[17], entry[18]))
+                },
+            }
+        })
+        .collect())
+}
@@ -0,0 +1,24 @@
+use crate::asm::Program;
+use crate::bytes::words_to_bytes;
+
+/// Disassemble up to `count` words to LASM source code, annotated with their address.
+/// The first word is considered to be located at `base_addr`.
+/// Words that cannot be decoded are displayed as raw data.
+pub fn disassemble_at(words: &[u32], base_addr: u32, count: usize) -> String {
+    let words = &words[..count.min(words.len())];
+
+    // Decoding cannot fail as raw data is allowed
+    let prog = Program::decode(words_to_bytes(words), false).unwrap();
+
+    prog.prog_words()
+        .enumerate()
+        .map(|(i, pword)| {
+            format!(
+                "{:#010X}: {}",
+                base_addr.wrapping_add(i as u32 * 4),
+                pword.to_lasm()
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
@@ -1,9 +1,89 @@
-use super::{prepare_vm, run_vm, RunConfig, StoppedState};
+use super::coverage::attach_regions;
+use super::report::Reporter;
+use super::{
+    guard_components, map_and_report, prepare_vm_with, reset_and_notify, run::run_vm_until,
+    RunConfig, StoppedState,
+};
+use crate::metadata::{DeviceCategory, DeviceMetadata};
 use lrvm::board::{Bus, MotherBoard};
+use std::sync::{Arc, Mutex};
+
+/// Log of the accesses to a simulated I/O port, as `(is_write, word)` pairs
+pub type IoLog = Vec<(bool, u32)>;
 
 /// Prepare a virtual machine with the provided components and run it with the provided configuration
+/// The mapping status of all components is reported through the configuration's message sink.
 pub fn exec_vm(components: Vec<Box<dyn Bus>>, config: RunConfig) -> (MotherBoard, StoppedState) {
-    let mut motherboard = prepare_vm(components);
-    let status = run_vm(motherboard.cpu(), config);
+    let mut reporter = Reporter::new(config.message_sink);
+    let mut motherboard = prepare_vm_with(components, &mut reporter, &config);
+    let mut status = run_vm_until(motherboard.cpu(), config, reporter, || false);
+    attach_regions(&mut motherboard, &mut status);
     (motherboard, status)
 }
+
+/// Prepare a virtual machine like [`exec_vm`] with an additional simulated I/O port of a single word, mapped at the provided address,
+/// and run it with the provided configuration.
+/// Reading the port always returns `0`.
+///
+/// Returns the log of all accesses to the port, in order. Reads are logged with the returned word.
+/// Panics if the I/O port overlaps with the other components (which are mapped contiguously from address `0x00000000`).
+pub fn exec_vm_with_io(
+    components: Vec<Box<dyn Bus>>,
+    config: RunConfig,
+    io_port: u32,
+) -> (MotherBoard, StoppedState, IoLog) {
+    let aux_count = components.len();
+    let log = Arc::new(Mutex::new(vec![]));
+
+    let mut components = components;
+    components.push(Box::new(IoPort {
+        log: Arc::clone(&log),
+    }));
+
+    let (components, guards) = guard_components(components, config.access_policy.as_ref());
+    let mut motherboard = MotherBoard::new(components);
+    let mut reporter = Reporter::new(config.message_sink);
+
+    motherboard.map(|mem| {
+        mem.map(io_port, aux_count)
+            .unwrap_or_else(|err| panic!("Failed to map the I/O port: {:?}", err));
+
+        map_and_report(mem, aux_count, &mut reporter);
+        guards.locate(mem);
+    });
+
+    reset_and_notify(&mut motherboard, config.reset_hook.as_ref());
+
+    let mut status = run_vm_until(motherboard.cpu(), config, reporter, || false);
+    attach_regions(&mut motherboard, &mut status);
+
+    let log = log.lock().unwrap().clone();
+
+    (motherboard, status, log)
+}
+
+/// (Internal) Simulated I/O port logging all accesses
+struct IoPort {
+    log: Arc<Mutex<IoLog>>,
+}
+
+impl Bus for IoPort {
+    fn name(&self) -> &'static str {
+        "I/O Port"
+    }
+
+    fn metadata(&self) -> [u32; 8] {
+        DeviceMetadata::new(0, 4, DeviceCategory::Uncategorized(), None, None).encode()
+    }
+
+    fn read(&mut self, _addr: u32, _ex: &mut u16) -> u32 {
+        self.log.lock().unwrap().push((false, 0));
+        0
+    }
+
+    fn write(&mut self, _addr: u32, word: u32, _ex: &mut u16) {
+        self.log.lock().unwrap().push((true, word));
+    }
+
+    fn reset(&mut self) {}
+}
@@ -0,0 +1,81 @@
+use crate::metadata::DeviceCategory;
+
+/// Describe a memory map as a table of ranges, one line per component (sorted by start address).
+/// Each component is described by its name, category, start address and size in bytes.
+///
+/// Overlapping components, gaps between components and ranges exceeding the 32-bit address space are flagged,
+/// and a summary line is added at the end of the table.
+pub fn describe_map(components: &[(&str, DeviceCategory, u32, u32)]) -> String {
+    let mut sorted = components.to_vec();
+    sorted.sort_by_key(|(_, _, start, size)| (*start, *size));
+
+    let mut lines = vec![];
+    let mut overlaps = 0;
+    let mut gaps = 0;
+
+    // Component with the highest end address seen so far (name + end address)
+    let mut last: Option<(&str, u64)> = None;
+
+    for (name, category, start, size) in sorted {
+        let start = u64::from(start);
+        let end = start + u64::from(size);
+
+        if let Some((_, last_end)) = last {
+            if start > last_end {
+                gaps += 1;
+                lines.push(format!(
+                    "{:#010X} -> {:#010X} | {:#010X} | <gap>",
+                    last_end,
+                    start - 1,
+                    start - last_end
+                ));
+            }
+        }
+
+        let mut line = format!(
+            "{:#010X} -> {:#010X} | {:#010X} | {} ({})",
+            start,
+            if size == 0 { start } else { end - 1 },
+            size,
+            name,
+            category
+        );
+
+        if size == 0 {
+            line.push_str(" ✗ empty range");
+        }
+
+        if end > 0x1_0000_0000 {
+            line.push_str(" ✗ exceeds the address space");
+        }
+
+        match last {
+            Some((last_name, last_end)) if start < last_end && size != 0 => {
+                overlaps += 1;
+                line.push_str(&format!(" ✗ overlaps with '{}'", last_name));
+            }
+            _ => {}
+        }
+
+        lines.push(line);
+
+        match last {
+            Some((_, last_end)) if last_end >= end => {}
+            _ => last = Some((name, end)),
+        }
+    }
+
+    lines.push(if overlaps == 0 && gaps == 0 {
+        "=> No overlap or gap detected".to_string()
+    } else {
+        format!(
+            "=> {} overlap{} and {} gap{} detected",
+            overlaps,
+            if overlaps == 1 { "" } else { "s" },
+            gaps,
+            if gaps == 1 { "" } else { "s" }
+        )
+    });
+
+    lines.join("\n")
+}
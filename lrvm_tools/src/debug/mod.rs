@@ -1,9 +1,22 @@
+mod coverage;
+mod devices;
+mod disasm;
 mod exec;
+mod map;
+mod policy;
 mod prepare;
+mod report;
 mod run;
 mod run_config;
+mod spawn;
 
+pub use coverage::*;
+pub use devices::*;
+pub use disasm::*;
 pub use exec::*;
+pub use map::*;
+pub use policy::*;
 pub use prepare::*;
 pub use run::*;
 pub use run_config::*;
+pub use spawn::*;
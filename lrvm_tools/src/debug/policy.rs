@@ -0,0 +1,119 @@
+use crate::exceptions::AuxHwException;
+use lrvm::board::Bus;
+use lrvm::mem::MappedMemory;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+
+/// Write request submitted to an access policy (see [`super::RunConfig::with_access_policy`])
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct WriteAccess {
+    /// Identifier of the targeted component
+    pub aux_id: usize,
+    /// Name of the targeted component
+    pub aux_name: &'static str,
+    /// Absolute address of the targeted word
+    pub addr: u32,
+    /// Word to write
+    pub word: u32,
+}
+
+/// Decision of an access policy
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AccessDecision {
+    /// Perform the write
+    Allow,
+    /// Reject the write, which raises a `MemoryNotWritable` hardware exception
+    Deny,
+}
+
+/// Access policy, consulted on each write request sent to a component
+pub type AccessPolicy = Arc<dyn Fn(&WriteAccess) -> AccessDecision + Send + Sync>;
+
+/// (Internal) Wrapper submitting the write requests of a component to an access policy
+struct Guarded {
+    inner: Box<dyn Bus>,
+    aux_id: usize,
+    base_addr: Arc<AtomicU32>,
+    policy: AccessPolicy,
+}
+
+impl Bus for Guarded {
+    fn name(&self) -> &'static str {
+        self.inner.name()
+    }
+
+    fn metadata(&self) -> [u32; 8] {
+        self.inner.metadata()
+    }
+
+    fn read(&mut self, addr: u32, ex: &mut u16) -> u32 {
+        self.inner.read(addr, ex)
+    }
+
+    fn write(&mut self, addr: u32, word: u32, ex: &mut u16) {
+        let access = WriteAccess {
+            aux_id: self.aux_id,
+            aux_name: self.inner.name(),
+            addr: self.base_addr.load(Ordering::SeqCst).wrapping_add(addr),
+            word,
+        };
+
+        match (self.policy)(&access) {
+            AccessDecision::Allow => self.inner.write(addr, word, ex),
+            AccessDecision::Deny => *ex = AuxHwException::MemoryNotWritable.encode(),
+        }
+    }
+
+    fn reset(&mut self) {
+        self.inner.reset()
+    }
+
+    fn latency(&self) -> u32 {
+        self.inner.latency()
+    }
+}
+
+/// (Internal) Base addresses of guarded components, to set once they are mapped
+pub(super) struct Guards(Vec<Arc<AtomicU32>>);
+
+impl Guards {
+    /// Set the base addresses of the guarded components from the memory's mapping
+    pub fn locate(&self, mem: &MappedMemory) {
+        for (aux_id, base_addr) in self.0.iter().enumerate() {
+            if let Some(mapping) = mem.get_mapping(aux_id) {
+                base_addr.store(mapping.addr, Ordering::SeqCst);
+            }
+        }
+    }
+}
+
+/// (Internal) Wrap components so their write requests are submitted to the provided access policy (if any)
+pub(super) fn guard_components(
+    components: Vec<Box<dyn Bus>>,
+    policy: Option<&AccessPolicy>,
+) -> (Vec<Box<dyn Bus>>, Guards) {
+    let policy = match policy {
+        Some(policy) => policy,
+        None => return (components, Guards(vec![])),
+    };
+
+    let mut guards = vec![];
+
+    let components = components
+        .into_iter()
+        .enumerate()
+        .map(|(aux_id, inner)| {
+            let base_addr = Arc::new(AtomicU32::new(0));
+            guards.push(Arc::clone(&base_addr));
+
+            Box::new(Guarded {
+                inner,
+                aux_id,
+                base_addr,
+                policy: Arc::clone(policy),
+            }) as Box<dyn Bus>
+        })
+        .collect();
+
+    (components, Guards(guards))
+}
@@ -1,5 +1,7 @@
+use super::report::{MessageLevel, Reporter};
+use super::{guard_components, MessageSink, ResetHook, RunConfig};
 use lrvm::board::{Bus, MotherBoard};
-use lrvm::mem::{ContiguousMappingResult, MappingRange};
+use lrvm::mem::{ContiguousMappingResult, MappedMemory, MappingRange};
 
 /// Prepare a motherboard from a list of components.
 /// The mapping status of all components is displayed.
@@ -7,52 +9,91 @@ use lrvm::mem::{ContiguousMappingResult, MappingRange};
 /// In case of success, the component's name as well as its start and mapping address are displayed.
 /// In case of fail, the reason is displayed with the component's name and the program panics.
 pub fn prepare_vm(components: Vec<Box<dyn Bus>>) -> MotherBoard {
+    prepare_vm_with(
+        components,
+        &mut Reporter::new(MessageSink::Stdout),
+        &RunConfig::default(),
+    )
+}
+
+/// (Internal) Prepare a motherboard like [`prepare_vm`], but report the mapping status through the provided reporter,
+/// submit the components' write requests to the configuration's access policy and call its reset hook after the reset
+pub(super) fn prepare_vm_with(
+    components: Vec<Box<dyn Bus>>,
+    reporter: &mut Reporter,
+    config: &RunConfig,
+) -> MotherBoard {
     let aux_count = components.len();
+    let (components, guards) = guard_components(components, config.access_policy.as_ref());
 
     let mut motherboard = MotherBoard::new(components);
 
     motherboard.map(|mem| {
-        let ContiguousMappingResult {
-            mapping,
-            aux_mapping,
-        } = mem.map_contiguous(0x0000_0000, (0..aux_count).collect::<Vec<_>>());
-
-        for result in aux_mapping {
-            println!(
-                "=> Component {:04} '{:32}': {} {} (HW ID: 0x{})",
-                result.aux_id,
-                result.aux_name,
-                if result.aux_mapping.is_ok() {
-                    "✓"
-                } else {
-                    "✗"
-                },
-                match result.aux_mapping {
-                    Ok(MappingRange {
-                        start_addr,
-                        end_addr,
-                    }) => format!("{:#010X} -> {:#010X}", start_addr, end_addr),
-                    Err(err) => format!("{:?}", err),
-                },
-                result
-                    .aux_hw_id
-                    .to_be_bytes()
-                    .iter()
-                    .map(|byte| format!("{:002X}", byte))
-                    .collect::<Vec<String>>()
-                    .join(" "),
-            );
-        }
-
-        if let Err(failed) = mapping {
-            panic!(
-                "Failed to map {} component{}!",
-                failed.len(),
-                if failed.len() == 1 { "" } else { "s" }
-            );
-        }
+        map_and_report(mem, aux_count, reporter);
+        guards.locate(mem);
     });
 
-    motherboard.reset();
+    reset_and_notify(&mut motherboard, config.reset_hook.as_ref());
     motherboard
 }
+
+/// (Internal) Reset a motherboard, then call the provided hook
+pub(super) fn reset_and_notify(motherboard: &mut MotherBoard, reset_hook: Option<&ResetHook>) {
+    motherboard.reset();
+
+    if let Some(hook) = reset_hook {
+        (hook.lock().unwrap())(motherboard);
+    }
+}
+
+/// (Internal) Map the first components contiguously from address `0x00000000` and report their mapping status.
+/// Panics if a component failed to be mapped.
+pub(super) fn map_and_report(mem: &mut MappedMemory, aux_count: usize, reporter: &mut Reporter) {
+    let ContiguousMappingResult {
+        mapping,
+        aux_mapping,
+    } = mem.map_contiguous(0x0000_0000, (0..aux_count).collect::<Vec<_>>());
+
+    for result in aux_mapping {
+        let level = if result.aux_mapping.is_ok() {
+            MessageLevel::Info
+        } else {
+            MessageLevel::Warn
+        };
+
+        let message = format!(
+            "=> Component {:04} '{:32}': {} {} (HW ID: 0x{})",
+            result.aux_id,
+            result.aux_name,
+            if result.aux_mapping.is_ok() {
+                "✓"
+            } else {
+                "✗"
+            },
+            match result.aux_mapping {
+                Ok(MappingRange {
+                    start_addr,
+                    end_addr,
+                }) => format!("{:#010X} -> {:#010X}", start_addr, end_addr),
+                Err(err) => format!("{:?}", err),
+            },
+            result
+                .aux_hw_id
+                .to_be_bytes()
+                .iter()
+                .map(|byte| format!("{:002X}", byte))
+                .collect::<Vec<String>>()
+                .join(" "),
+        );
+
+        reporter.report(level, message);
+    }
+
+    if let Err(failed) = mapping {
+        panic!(
+            "Failed to map {} component{}!",
+            failed.len(),
+            if failed.len() == 1 { "" } else { "s" }
+        );
+    }
+}
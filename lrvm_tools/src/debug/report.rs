@@ -0,0 +1,45 @@
+use super::MessageSink;
+
+/// (Internal) Importance of a runner's message
+#[derive(Clone, Copy)]
+pub(super) enum MessageLevel {
+    Trace,
+    Info,
+    Warn,
+}
+
+/// (Internal) Emitter of the runner's messages, sending them to a [`MessageSink`]
+pub(super) struct Reporter {
+    sink: MessageSink,
+    captured: Vec<String>,
+}
+
+impl Reporter {
+    /// Create an emitter for the provided sink
+    pub fn new(sink: MessageSink) -> Self {
+        Self {
+            sink,
+            captured: vec![],
+        }
+    }
+
+    /// Emit a message
+    #[cfg_attr(not(feature = "log"), allow(unused_variables))]
+    pub fn report(&mut self, level: MessageLevel, message: String) {
+        match self.sink {
+            MessageSink::Stdout => println!("{}", message),
+            MessageSink::Capture => self.captured.push(message),
+            #[cfg(feature = "log")]
+            MessageSink::Log => match level {
+                MessageLevel::Trace => log::trace!("{}", message),
+                MessageLevel::Info => log::info!("{}", message),
+                MessageLevel::Warn => log::warn!("{}", message),
+            },
+        }
+    }
+
+    /// Get the captured messages (always empty if the sink is not [`MessageSink::Capture`])
+    pub fn into_captured(self) -> Vec<String> {
+        self.captured
+    }
+}
@@ -1,7 +1,15 @@
-use super::RunConfig;
+use super::report::{MessageLevel, Reporter};
+use super::{CoverageReport, InspectState, MessageSink, RunConfig};
+use crate::asm::Reg;
+use crate::bytes::u32_to_bytes_be;
 use crate::exceptions::NativeException;
-use lrvm::cpu::Cpu;
+use lrvm::cpu::{Cpu, Registers};
 use std::fmt;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Number of throttle checkpoints per second when running at a target speed
+const THROTTLE_CHECKPOINTS_PER_SEC: u64 = 100;
 
 /// State of the VM when exited
 #[derive(Debug, Clone)]
@@ -12,6 +20,16 @@ pub struct StoppedState {
     pub addr: u32,
     /// If the VM was stopped due to an exception, contains the faulty exception
     pub ex: Option<ExWithMode>,
+    /// If the VM was stopped due to an invariant returning `false`, contains the violated invariant
+    pub invariant_violation: Option<InvariantViolation>,
+    /// Effective speed the VM ran at, in instructions per second (`None` if no measurable time elapsed)
+    pub effective_speed: Option<f64>,
+    /// Number of times the runner compared the elapsed time to the target speed (see [`RunConfig::with_target_speed`])
+    pub throttle_checkpoints: u64,
+    /// Messages emitted by the runner, if they were captured (see [`MessageSink::Capture`])
+    pub messages: Vec<String>,
+    /// Addresses of the instructions that were run, if coverage was enabled
+    pub coverage: Option<CoverageReport>,
 }
 
 /// Native exception, with mode
@@ -28,14 +46,64 @@ pub struct ExWithMode {
     pub associated: u16,
 }
 
+/// Invariant violation (see [`RunConfig::with_invariant`])
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct InvariantViolation {
+    /// Name of the violated invariant
+    pub name: String,
+    /// Address of the instruction after which the violation was detected
+    pub pc: u32,
+}
+
 /// Run a virtual machine until the CPU halt, eventually encounters an exception or reaches a given number of cycles.
 pub fn run_vm(cpu: &mut Cpu, config: RunConfig) -> StoppedState {
+    let reporter = Reporter::new(config.message_sink);
+    run_vm_until(cpu, config, reporter, || false)
+}
+
+/// (Internal) Run a virtual machine like [`run_vm`], but also stop if the provided callback returns `true`.
+/// The callback is called before each instruction, and the messages are emitted through the provided reporter.
+pub(super) fn run_vm_until(
+    cpu: &mut Cpu,
+    config: RunConfig,
+    mut reporter: Reporter,
+    mut stop: impl FnMut() -> bool,
+) -> StoppedState {
+    for (reg, value) in &config.initial_regs {
+        set_reg(&mut cpu.regs, *reg, *value);
+    }
+
     // If the VM is stopped because of an exception, it will be put in here
     let mut stop_ex = None;
 
     // Address the CPU was at when the VM was stopped
     let mut was_at = cpu.regs.pc;
 
+    // Addresses of the instructions run so far, if coverage is enabled
+    let mut coverage = if config.coverage {
+        Some(CoverageReport::default())
+    } else {
+        None
+    };
+
+    // If the VM is stopped because of an invariant, it will be put in here
+    let mut violation = None;
+
+    // Used to measure the effective speed and to throttle the VM
+    let started_at = Instant::now();
+
+    // Number of instructions run so far (the cycles count can't be used as it includes the components' latency)
+    let mut executed: u128 = 0;
+
+    // Number of throttle checkpoints reached so far
+    let mut checkpoints = 0;
+
+    // Target speed, and number of instructions to run between two throttle checkpoints
+    let throttle = config
+        .target_speed
+        .filter(|speed| speed.0 > 0)
+        .map(|speed| (speed.0, (speed.0 / THROTTLE_CHECKPOINTS_PER_SEC).max(1)));
+
     // Run the VM until it halts
     while !cpu.halted() {
         // Ensure cycles limit isn't exceeded yet
@@ -45,23 +113,53 @@ pub fn run_vm(cpu: &mut Cpu, config: RunConfig) -> StoppedState {
             }
         }
 
+        if stop() {
+            break;
+        }
+
         // Update the current instruction address
         was_at = cpu.regs.pc;
 
         if config.print_cycles {
-            println!(
-                "[lrvm] Running cycle {:#010X} at address {:#010X}",
-                cpu.cycles(),
-                cpu.regs.pc
+            reporter.report(
+                MessageLevel::Trace,
+                format!(
+                    "[lrvm] Running cycle {:#010X} at address {}",
+                    cpu.cycles(),
+                    config.symbolicate(cpu.regs.pc)
+                ),
             );
         }
 
+        if let Some(profiler) = &config.profiler {
+            (profiler.lock().unwrap())(cpu.regs.pc);
+        }
+
+        if let Some(coverage) = &mut coverage {
+            coverage.executed.insert(cpu.regs.pc);
+        }
+
         // Run the next instruction
         cpu.next();
+        executed += 1;
+
+        // Sleep until the instructions run so far match the target speed
+        if let Some((speed, batch)) = throttle {
+            if executed % batch as u128 == 0 {
+                checkpoints += 1;
+
+                let expected = Duration::from_secs_f64(executed as f64 / speed as f64);
+                let elapsed = started_at.elapsed();
+
+                if expected > elapsed {
+                    thread::sleep(expected - elapsed);
+                }
+            }
+        }
 
         // Check if an exception occurred
         if cpu.regs.et != 0 {
-            let exception_bytes = cpu.regs.et.to_be_bytes();
+            let exception_bytes = u32_to_bytes_be(cpu.regs.et);
 
             // Complete the exception with the mode it occurred in
             let ex = ExWithMode {
@@ -72,10 +170,13 @@ pub fn run_vm(cpu: &mut Cpu, config: RunConfig) -> StoppedState {
             };
 
             if config.print_exceptions && !(config.halt_on_exception && config.print_finish) {
-                println!(
-                    "[lrvm] At address {:#010X} - Exception occurred: {}",
-                    was_at,
-                    prettify_ex_with_mode(&ex)
+                reporter.report(
+                    MessageLevel::Warn,
+                    format!(
+                        "[lrvm] At address {} - Exception occurred: {}",
+                        config.symbolicate(was_at),
+                        prettify_ex_with_mode(&ex)
+                    ),
                 );
             }
 
@@ -84,25 +185,95 @@ pub fn run_vm(cpu: &mut Cpu, config: RunConfig) -> StoppedState {
                 break;
             }
         }
+
+        // Check the invariants (if any)
+        if !config.invariants.is_empty()
+            && executed % u128::from(config.invariants_interval.get()) == 0
+        {
+            let inspect = InspectState {
+                regs: &cpu.regs,
+                cycles: cpu.cycles(),
+                addr: was_at,
+            };
+
+            if let Some((name, _)) = config
+                .invariants
+                .iter()
+                .find(|(_, invariant)| !invariant(&inspect))
+            {
+                violation = Some(InvariantViolation {
+                    name: name.clone(),
+                    pc: was_at,
+                });
+
+                break;
+            }
+        }
     }
 
-    let state = StoppedState {
+    let elapsed = started_at.elapsed().as_secs_f64();
+
+    let mut state = StoppedState {
         cycles: cpu.cycles(),
         addr: was_at,
         ex: stop_ex,
+        invariant_violation: violation,
+        effective_speed: if elapsed > 0.0 {
+            Some(executed as f64 / elapsed)
+        } else {
+            None
+        },
+        throttle_checkpoints: checkpoints,
+        messages: vec![],
+        coverage,
     };
 
     if config.print_finish {
-        if config.newline_on_finish {
+        // The newline only makes sense when other things are printed to the standard output
+        if config.newline_on_finish && config.message_sink == MessageSink::Stdout {
             println!();
         }
 
-        println!("[lrvm] {}", prettify_stop(&state));
+        reporter.report(
+            MessageLevel::Info,
+            format!(
+                "[lrvm] {}",
+                prettify_stop_with(&state, |addr| config.symbolicate(addr))
+            ),
+        );
     }
 
+    state.messages = reporter.into_captured();
     state
 }
 
+/// (Internal) Write a register
+fn set_reg(regs: &mut Registers, reg: Reg, value: u32) {
+    let code = usize::from(reg.code());
+
+    match reg {
+        Reg::a0 | Reg::a1 | Reg::a2 | Reg::a3 | Reg::a4 | Reg::a5 | Reg::a6 | Reg::a7 => {
+            regs.a[code - usize::from(Reg::a0.code())] = value
+        }
+        Reg::c0 | Reg::c1 => regs.c[code - usize::from(Reg::c0.code())] = value,
+        Reg::ac0 | Reg::ac1 | Reg::ac2 => regs.ac[code - usize::from(Reg::ac0.code())] = value,
+        Reg::rr0 | Reg::rr1 | Reg::rr2 | Reg::rr3 | Reg::rr4 | Reg::rr5 | Reg::rr6 | Reg::rr7 => {
+            regs.rr[code - usize::from(Reg::rr0.code())] = value
+        }
+        Reg::avr => regs.avr = value,
+        Reg::af => regs.af = value,
+        Reg::pc => regs.pc = value,
+        Reg::ssp => regs.ssp = value,
+        Reg::usp => regs.usp = value,
+        Reg::et => regs.et = value,
+        Reg::era => regs.era = value,
+        Reg::ev => regs.ev = value,
+        Reg::mtt => regs.mtt = value,
+        Reg::pda => regs.pda = value,
+        Reg::smt => regs.smt = value,
+    }
+}
+
 /// Prettify an exception with mode
 pub fn prettify_ex_with_mode(ex: &ExWithMode) -> String {
     match NativeException::decode_parts(ex.code, Some(ex.associated)) {
@@ -113,9 +284,15 @@ pub fn prettify_ex_with_mode(ex: &ExWithMode) -> String {
 
 /// Prettify a stop state
 pub fn prettify_stop(state: &StoppedState) -> String {
+    prettify_stop_with(state, |addr| format!("{:#010X}", addr))
+}
+
+/// (Internal) Prettify a stop state, displaying its address with the provided function
+fn prettify_stop_with(state: &StoppedState, display_addr: impl Fn(u32) -> String) -> String {
     let mut output = format!(
-        "Cycle {:#010X}: CPU halted at address {:#010X}",
-        state.cycles, state.addr
+        "Cycle {:#010X}: CPU halted at address {}",
+        state.cycles,
+        display_addr(state.addr)
     );
 
     if let Some(ex) = &state.ex {
@@ -126,6 +303,13 @@ pub fn prettify_stop(state: &StoppedState) -> String {
         ));
     }
 
+    if let Some(violation) = &state.invariant_violation {
+        output.push_str(&format!(
+            " because invariant '{}' was violated",
+            violation.name
+        ));
+    }
+
     output
 }
 
@@ -1,5 +1,61 @@
+use super::{AccessDecision, AccessPolicy, WriteAccess};
+use crate::asm::Reg;
+use lrvm::board::MotherBoard;
+use lrvm::cpu::Registers;
+use std::fmt;
+use std::num::NonZeroU32;
+use std::ops::Range;
+use std::sync::{Arc, Mutex};
+
+/// Profiler callback, called with the address of each instruction before it is run
+pub type Profiler = Arc<Mutex<dyn FnMut(u32) + Send>>;
+
+/// Reset hook, called with the motherboard after the runner reset it and before the VM is run
+pub type ResetHook = Arc<Mutex<dyn FnMut(&mut MotherBoard) + Send>>;
+
+/// State of the VM passed to invariants.
+/// Memory is not included, as reading it goes through the components and may have side effects.
+pub struct InspectState<'a> {
+    /// CPU registers
+    pub regs: &'a Registers,
+    /// Cycles count
+    pub cycles: u128,
+    /// Address of the instruction that was just run
+    pub addr: u32,
+}
+
+/// Invariant, which must return `true` as long as the VM is in a valid state
+pub type Invariant = Arc<dyn Fn(&InspectState) -> bool + Send + Sync>;
+
+/// Named range of addresses, used to display the addresses it contains relative to its start (see [`RunConfig::name_region`])
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct NamedRegion {
+    pub name: String,
+    pub range: Range<u32>,
+}
+
+/// Execution speed, in instructions per second
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InstructionsPerSecond(pub u64);
+
+/// Destination of the runner's messages (mapping status, exceptions, cycles and halt notices)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MessageSink {
+    /// Print the messages to the standard output (default)
+    Stdout,
+    /// Store the messages in the [`super::StoppedState`] returned by the runner, without displaying anything
+    Capture,
+    /// Send the messages to the `log` crate's facade: cycles are logged at the `trace` level, exceptions at the `warn` level,
+    /// and other messages at the `info` level
+    #[cfg(feature = "log")]
+    Log,
+}
+
 /// VM runner configuration to use with 'run_vm' or 'exec_vm' from 'lrvm_tools::debug'
-#[derive(Debug, Clone, Copy)]
+///
+/// The configuration is not `Copy` as it holds callbacks (e.g. the profiler) and lists (e.g. the initial registers),
+/// so it must be cloned to be reused for several runs.
+#[derive(Clone)]
 pub struct RunConfig {
     pub cycles_limit: Option<u128>,
     pub halt_on_exception: bool,
@@ -7,6 +63,16 @@ pub struct RunConfig {
     pub print_exceptions: bool,
     pub print_finish: bool,
     pub newline_on_finish: bool,
+    pub profiler: Option<Profiler>,
+    pub target_speed: Option<InstructionsPerSecond>,
+    pub initial_regs: Vec<(Reg, u32)>,
+    pub message_sink: MessageSink,
+    pub reset_hook: Option<ResetHook>,
+    pub coverage: bool,
+    pub invariants: Vec<(String, Invariant)>,
+    pub invariants_interval: NonZeroU32,
+    pub access_policy: Option<AccessPolicy>,
+    pub regions: Vec<NamedRegion>,
 }
 
 impl RunConfig {
@@ -73,6 +139,119 @@ impl RunConfig {
         self
     }
 
+    /// Set a callback to call with the address of each instruction before it is run, e.g. to build an address-frequency histogram.
+    /// Unlike [`RunConfig::with_print_cycles`], nothing is displayed.
+    pub fn with_profiler(mut self, profiler: impl FnMut(u32) + Send + 'static) -> Self {
+        self.profiler = Some(Arc::new(Mutex::new(profiler)));
+        self
+    }
+
+    /// Set the speed the VM should run at, or `None` to run as fast as possible (default).
+    /// The runner sleeps every few instructions to stay close to the target, so it's only suited for demos or real-time displays.
+    /// A speed of 0 instructions per second is treated as unlimited.
+    pub fn with_target_speed(mut self, speed: Option<InstructionsPerSecond>) -> Self {
+        self.target_speed = speed;
+        self
+    }
+
+    /// Set values to write in registers before the first instruction is run, e.g. to pass arguments to a routine.
+    /// Registers are written in order, so a register appearing twice gets its last value.
+    ///
+    /// # Panics
+    ///
+    /// Panics if one of the registers is read-only (`af`, `et` or `era`).
+    pub fn with_initial_regs(mut self, regs: &[(Reg, u32)]) -> Self {
+        if let Some((reg, _)) = regs
+            .iter()
+            .find(|(reg, _)| matches!(reg, Reg::af | Reg::et | Reg::era))
+        {
+            panic!("Cannot set an initial value for read-only register {}", reg);
+        }
+
+        self.initial_regs = regs.to_vec();
+        self
+    }
+
+    /// Set where the runner's messages should go. Messages disabled by the other options are not emitted, whatever the sink is.
+    pub fn with_message_sink(mut self, sink: MessageSink) -> Self {
+        self.message_sink = sink;
+        self
+    }
+
+    /// Set a callback to call each time the runner resets the motherboard, before the VM is run again.
+    /// It receives the motherboard, e.g. to reconfigure components or re-seed host-side state.
+    ///
+    /// Only runners preparing the motherboard themselves ([`super::exec_vm`], [`super::exec_vm_with_io`] and [`super::spawn_vm`]) call it.
+    pub fn on_reset(mut self, hook: impl FnMut(&mut MotherBoard) + Send + 'static) -> Self {
+        self.reset_hook = Some(Arc::new(Mutex::new(hook)));
+        self
+    }
+
+    /// Set if the runner should collect the addresses of the instructions it runs, in the returned state's [`super::CoverageReport`].
+    /// This allows to check which parts of a program were exercised.
+    pub fn with_coverage(mut self, coverage: bool) -> Self {
+        self.coverage = coverage;
+        self
+    }
+
+    /// Register an invariant, checked after each instruction (see [`RunConfig::with_invariants_interval`]).
+    /// If it returns `false`, the VM is stopped and the state it stopped in indicates which invariant was violated.
+    /// Invariants are checked in the order they were registered.
+    pub fn with_invariant(
+        mut self,
+        name: impl Into<String>,
+        invariant: impl Fn(&InspectState) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        self.invariants.push((name.into(), Arc::new(invariant)));
+        self
+    }
+
+    /// Set the number of instructions to run between two checks of the invariants (default: 1, which checks them after each instruction).
+    /// Panics if the interval is 0.
+    pub fn with_invariants_interval(mut self, interval: u32) -> Self {
+        self.invariants_interval =
+            NonZeroU32::new(interval).expect("Invariants interval cannot be 0");
+        self
+    }
+
+    /// Set a policy consulted on each write request sent to a component, e.g. to run untrusted programs in a sandbox.
+    /// Denied writes are not performed and raise a `MemoryNotWritable` hardware exception instead.
+    ///
+    /// Only runners preparing the motherboard themselves ([`super::exec_vm`], [`super::exec_vm_with_io`] and [`super::spawn_vm`]) use it.
+    pub fn with_access_policy(
+        mut self,
+        policy: impl Fn(&WriteAccess) -> AccessDecision + Send + Sync + 'static,
+    ) -> Self {
+        self.access_policy = Some(Arc::new(policy));
+        self
+    }
+
+    /// Name a range of addresses, so the runner's messages display the addresses it contains as `name+offset` (see [`RunConfig::symbolicate`]).
+    pub fn name_region(mut self, name: impl Into<String>, range: Range<u32>) -> Self {
+        self.regions.push(NamedRegion {
+            name: name.into(),
+            range,
+        });
+
+        self
+    }
+
+    /// Display an address relative to the smallest named region containing it (e.g. `display_buf+0x24`, or `display_buf` for its first byte).
+    /// Addresses outside of all named regions are displayed as-is.
+    pub fn symbolicate(&self, addr: u32) -> String {
+        let region = self
+            .regions
+            .iter()
+            .filter(|region| region.range.contains(&addr))
+            .min_by_key(|region| region.range.end - region.range.start);
+
+        match region {
+            Some(region) if addr == region.range.start => region.name.clone(),
+            Some(region) => format!("{}+{:#X}", region.name, addr - region.range.start),
+            None => format!("{:#010X}", addr),
+        }
+    }
+
     /// Enable all display informations.
     pub fn be_verbose(mut self) -> Self {
         self.print_cycles = true;
@@ -99,6 +278,52 @@ impl Default for RunConfig {
             print_exceptions: true,
             print_finish: true,
             newline_on_finish: false,
+            profiler: None,
+            target_speed: None,
+            initial_regs: vec![],
+            message_sink: MessageSink::Stdout,
+            reset_hook: None,
+            coverage: false,
+            invariants: vec![],
+            invariants_interval: NonZeroU32::new(1).unwrap(),
+            access_policy: None,
+            regions: vec![],
         }
     }
 }
+
+impl fmt::Debug for RunConfig {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("RunConfig")
+            .field("cycles_limit", &self.cycles_limit)
+            .field("halt_on_exception", &self.halt_on_exception)
+            .field("print_cycles", &self.print_cycles)
+            .field("print_exceptions", &self.print_exceptions)
+            .field("print_finish", &self.print_finish)
+            .field("newline_on_finish", &self.newline_on_finish)
+            .field("profiler", &self.profiler.as_ref().map(|_| "<callback>"))
+            .field("target_speed", &self.target_speed)
+            .field("initial_regs", &self.initial_regs)
+            .field("message_sink", &self.message_sink)
+            .field(
+                "reset_hook",
+                &self.reset_hook.as_ref().map(|_| "<callback>"),
+            )
+            .field("coverage", &self.coverage)
+            .field(
+                "invariants",
+                &self
+                    .invariants
+                    .iter()
+                    .map(|(name, _)| name)
+                    .collect::<Vec<_>>(),
+            )
+            .field("invariants_interval", &self.invariants_interval)
+            .field(
+                "access_policy",
+                &self.access_policy.as_ref().map(|_| "<callback>"),
+            )
+            .field("regions", &self.regions)
+            .finish()
+    }
+}
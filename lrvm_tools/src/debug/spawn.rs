@@ -0,0 +1,175 @@
+use super::coverage::attach_regions;
+use super::report::Reporter;
+use super::{prepare_vm_with, run::run_vm_until, RunConfig, StoppedState};
+use lrvm::board::Bus;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread::{self, JoinHandle};
+
+/// Outcome of a virtual machine run on a dedicated thread
+#[derive(Debug, Clone)]
+pub enum RunOutcome {
+    /// The VM stopped by itself (CPU halted, exception or cycles limit, depending on the configuration)
+    Finished(StoppedState),
+    /// The VM was stopped through [`VmHandle::stop`]
+    Stopped(StoppedState),
+    /// The VM's thread panicked
+    Panicked,
+}
+
+impl RunOutcome {
+    /// Get the state the VM stopped in (`None` if its thread panicked)
+    pub fn state(&self) -> Option<&StoppedState> {
+        match self {
+            Self::Finished(state) | Self::Stopped(state) => Some(state),
+            Self::Panicked => None,
+        }
+    }
+
+    /// Get the messages captured by the runner (see [`super::MessageSink::Capture`])
+    pub fn messages(&self) -> &[String] {
+        self.state().map_or(&[], |state| &state.messages)
+    }
+}
+
+/// (Internal) Control flags shared between a [`VmHandle`] and its thread
+#[derive(Default)]
+struct VmControl {
+    paused: AtomicBool,
+    parked: AtomicBool,
+    stopped: AtomicBool,
+    halted: AtomicBool,
+    lock: Mutex<()>,
+    resumed: Condvar,
+}
+
+impl VmControl {
+    /// Wake up the VM's thread if it's paused
+    fn wake(&self) {
+        let _guard = self.lock.lock().unwrap();
+        self.resumed.notify_all();
+    }
+
+    /// Block while the VM is paused, then indicate if it should stop
+    fn wait_if_paused(&self) -> bool {
+        if self.paused.load(Ordering::SeqCst) {
+            let mut guard = self.lock.lock().unwrap();
+            self.parked.store(true, Ordering::SeqCst);
+
+            while self.paused.load(Ordering::SeqCst) && !self.stopped.load(Ordering::SeqCst) {
+                guard = self.resumed.wait(guard).unwrap();
+            }
+
+            self.parked.store(false, Ordering::SeqCst);
+        }
+
+        self.stopped.load(Ordering::SeqCst)
+    }
+}
+
+/// Handle to a virtual machine running on a dedicated thread (see [`spawn_vm`]).
+/// Dropping the handle stops the VM and waits for its thread to finish.
+pub struct VmHandle {
+    control: Arc<VmControl>,
+    thread: Option<JoinHandle<RunOutcome>>,
+}
+
+impl VmHandle {
+    /// Pause the VM. Takes effect between two instructions.
+    pub fn pause(&self) {
+        self.control.paused.store(true, Ordering::SeqCst);
+    }
+
+    /// Check if the pause took effect, i.e. if the VM's thread is waiting to be resumed.
+    /// Once this returns `true`, no instruction is run until the VM is resumed.
+    pub fn is_paused(&self) -> bool {
+        self.control.parked.load(Ordering::SeqCst)
+    }
+
+    /// Resume the VM after a pause
+    pub fn resume(&self) {
+        self.control.paused.store(false, Ordering::SeqCst);
+        self.control.wake();
+    }
+
+    /// Stop the VM. Takes effect between two instructions, even if the VM is paused.
+    pub fn stop(&self) {
+        self.control.stopped.store(true, Ordering::SeqCst);
+        self.control.wake();
+    }
+
+    /// Check if the VM is not running anymore
+    pub fn is_halted(&self) -> bool {
+        self.control.halted.load(Ordering::SeqCst)
+    }
+
+    /// Wait for the VM to finish and get its outcome
+    pub fn wait(mut self) -> RunOutcome {
+        self.join()
+    }
+
+    /// (Internal) Wait for the VM's thread to finish
+    fn join(&mut self) -> RunOutcome {
+        match self.thread.take() {
+            Some(thread) => thread.join().unwrap_or(RunOutcome::Panicked),
+            None => RunOutcome::Panicked,
+        }
+    }
+}
+
+impl Drop for VmHandle {
+    fn drop(&mut self) {
+        if self.thread.is_some() {
+            self.stop();
+            self.join();
+        }
+    }
+}
+
+/// Prepare a virtual machine with the provided components and run it on a dedicated thread with the provided configuration.
+/// This allows host applications to keep running while the VM does.
+pub fn spawn_vm(components: Vec<Box<dyn Bus + Send>>, config: RunConfig) -> VmHandle {
+    let control = Arc::new(VmControl::default());
+    let thread_control = Arc::clone(&control);
+
+    let thread = thread::spawn(move || {
+        // Sets the VM as halted when the thread ends, even if it panics
+        struct HaltOnDrop(Arc<VmControl>);
+
+        impl Drop for HaltOnDrop {
+            fn drop(&mut self) {
+                self.0.halted.store(true, Ordering::SeqCst);
+            }
+        }
+
+        let control = HaltOnDrop(thread_control);
+
+        let mut reporter = Reporter::new(config.message_sink);
+
+        let mut motherboard = prepare_vm_with(
+            components
+                .into_iter()
+                .map(|component| component as Box<dyn Bus>)
+                .collect(),
+            &mut reporter,
+            &config,
+        );
+
+        let mut state = run_vm_until(motherboard.cpu(), config, reporter, || {
+            control.0.wait_if_paused()
+        });
+
+        attach_regions(&mut motherboard, &mut state);
+
+        if control.0.stopped.load(Ordering::SeqCst) {
+            RunOutcome::Stopped(state)
+        } else {
+            RunOutcome::Finished(state)
+        }
+    });
+
+    VmHandle {
+        control,
+        thread: Some(thread),
+    }
+}
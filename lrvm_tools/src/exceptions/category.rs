@@ -0,0 +1,32 @@
+use std::fmt;
+
+/// Category of a native exception (see [`super::NativeException::category`])
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ExceptionCategory {
+    /// Invalid instruction, register or operand
+    Illegal,
+    /// Unaligned or refused memory access
+    Memory,
+    /// Division or modulus error
+    Arithmetic,
+    /// Error related to an auxiliary component
+    Device,
+    /// Interruption raised by the program itself
+    Interruption,
+}
+
+impl fmt::Display for ExceptionCategory {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::Illegal => "Illegal",
+                Self::Memory => "Memory",
+                Self::Arithmetic => "Arithmetic",
+                Self::Device => "Device",
+                Self::Interruption => "Interruption",
+            }
+        )
+    }
+}
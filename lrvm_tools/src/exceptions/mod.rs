@@ -1,5 +1,9 @@
 mod auxhw;
+mod category;
 mod native;
+mod sink;
 
 pub use auxhw::AuxHwException;
+pub use category::ExceptionCategory;
 pub use native::NativeException;
+pub use sink::ExceptionSink;
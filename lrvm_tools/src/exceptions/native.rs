@@ -1,5 +1,6 @@
 use crate::asm::Reg;
-use crate::exceptions::AuxHwException;
+use crate::bytes::u32_to_bytes_be;
+use crate::exceptions::{AuxHwException, ExceptionCategory};
 use std::fmt;
 
 /// Describe a native exception
@@ -25,6 +26,39 @@ pub enum NativeException {
 }
 
 impl NativeException {
+    /// Get the exception's category
+    pub fn category(&self) -> ExceptionCategory {
+        match self {
+            Self::UnknownOpCode(_)
+            | Self::UnknownRegister(_)
+            | Self::ReadProtectedRegister(_)
+            | Self::WriteProtectedRegister(_)
+            | Self::SupervisorReservedInstruction(_)
+            | Self::InvalidCondFlag(_)
+            | Self::InvalidCondMode(_) => ExceptionCategory::Illegal,
+
+            Self::UnalignedMemoryAddress { unalignment: _ }
+            | Self::MmuRefusedRead(_)
+            | Self::MmuRefusedWrite(_)
+            | Self::MmuRefusedExec(_) => ExceptionCategory::Memory,
+
+            Self::DivisionOrModByZero | Self::OverflowingDivOrMod => ExceptionCategory::Arithmetic,
+
+            Self::UnknownComponentId(_)
+            | Self::UnknownHardwareInformationCode(_)
+            | Self::ComponentNotMapped(_)
+            | Self::HardwareException(_) => ExceptionCategory::Device,
+
+            Self::Interruption(_) => ExceptionCategory::Interruption,
+        }
+    }
+
+    /// Check if the exception is fatal, meaning the program is invalid (e.g. corrupted or not made for this CPU)
+    /// and resuming its execution makes no sense. Other exceptions may be handled by the program to recover from them.
+    pub fn is_fatal(&self) -> bool {
+        self.category() == ExceptionCategory::Illegal
+    }
+
     /// Decode a native exception
     pub fn decode(ex: u32) -> Result<Self, ()> {
         Self::decode_with_mode(ex).map(|(ex, _)| ex)
@@ -34,7 +68,7 @@ impl NativeException {
     /// If the error is indicated to have happened in supervisor mode, the second member of the returned tuple is set to `true`.
     /// If it's `false`, the error indicates to have happened in userland mode.
     pub fn decode_with_mode(ex: u32) -> Result<(Self, bool), ()> {
-        let bytes = ex.to_be_bytes();
+        let bytes = u32_to_bytes_be(ex);
 
         let code = bytes[1];
         let associated = u16::from_be_bytes([bytes[2], bytes[3]]);
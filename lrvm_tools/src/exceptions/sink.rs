@@ -0,0 +1,23 @@
+use super::AuxHwException;
+
+/// Typed wrapper over the exception out-parameter of the [`lrvm::board::Bus`] methods,
+/// to raise exceptions from auxiliary components without writing raw codes.
+pub struct ExceptionSink<'a>(pub &'a mut u16);
+
+impl<'a> ExceptionSink<'a> {
+    /// Wrap an exception out-parameter
+    pub fn new(ex: &'a mut u16) -> Self {
+        Self(ex)
+    }
+
+    /// Raise an exception (replacing the previously raised one, if any)
+    pub fn raise(&mut self, ex: AuxHwException) {
+        *self.0 = ex.encode();
+    }
+
+    /// Check if an exception has been raised.
+    /// As for the CPU, [`AuxHwException::UnknownError`] (encoded as `0`) is not considered as raised.
+    pub fn is_raised(&self) -> bool {
+        *self.0 != 0
+    }
+}
@@ -0,0 +1,64 @@
+use super::{assemble, CUSTOMASM_HEADER};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+
+/// In-memory cache of assembled programs, keyed by a hash of their source code (and of the LASM header).
+/// Only successful assemblies are cached.
+#[derive(Debug, Clone, Default)]
+pub struct AssembleCache {
+    entries: HashMap<u64, Vec<u8>>,
+    assembled: usize,
+}
+
+impl AssembleCache {
+    /// Create an empty cache
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get the machine code of a LASM source code from the cache, or assemble it if it isn't cached yet.
+    /// Returns an error message in case of error.
+    pub fn get_or_assemble(&mut self, source: &str) -> Result<Vec<u8>, String> {
+        let key = Self::key(source);
+
+        if let Some(bytes) = self.entries.get(&key) {
+            return Ok(bytes.clone());
+        }
+
+        self.assembled += 1;
+
+        let bytes = assemble(source)?;
+        self.entries.insert(key, bytes.clone());
+
+        Ok(bytes)
+    }
+
+    /// Get the number of times the assembler was run by this cache
+    pub fn assembled(&self) -> usize {
+        self.assembled
+    }
+
+    /// Get the number of cached programs
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Check if the cache is empty
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Remove all cached programs
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    /// (Internal) Compute the cache key of a source code
+    fn key(source: &str) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        CUSTOMASM_HEADER.hash(&mut hasher);
+        source.hash(&mut hasher);
+        hasher.finish()
+    }
+}
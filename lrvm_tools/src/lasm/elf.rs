@@ -0,0 +1,75 @@
+use super::assemble;
+use crate::bytes::u32_to_bytes_be;
+
+/// Value of the ELF header's `e_machine` field used for LRVM programs.
+/// It is not registered officially, and has been chosen outside of the ranges used by the known architectures.
+pub const ELF_MACHINE: u16 = 0x4C52;
+
+/// Size of the ELF header, in bytes
+const ELF_HEADER_SIZE: u32 = 52;
+
+/// Size of a section header, in bytes
+const SECTION_HEADER_SIZE: u32 = 40;
+
+/// Names of the sections, as stored in the section names' string table
+const SECTION_NAMES: &[u8] = b"\0.text\0.shstrtab\0";
+
+/// Assemble a LASM source code to a 32-bit big-endian ELF relocatable object, with a single `.text` section containing the machine code
+/// (see [`elf_from_binary`]).
+/// Returns an error message in case of error.
+pub fn assemble_to_elf(source: &str, base_address: u32) -> Result<Vec<u8>, String> {
+    assemble(source).map(|code| elf_from_binary(&code, base_address))
+}
+
+/// Wrap machine code in a 32-bit big-endian ELF relocatable object, with a single `.text` section containing the machine code.
+/// The section's address and the entry point are set to the provided base address.
+/// The machine is identified by [`ELF_MACHINE`].
+pub fn elf_from_binary(code: &[u8], base_address: u32) -> Vec<u8> {
+    let text_offset = ELF_HEADER_SIZE;
+    let text_size = code.len() as u32;
+    let names_offset = text_offset + text_size;
+    let names_size = SECTION_NAMES.len() as u32;
+
+    // Section headers are aligned on 4 bytes
+    let sh_offset = (names_offset + names_size + 3) / 4 * 4;
+
+    let mut elf = vec![];
+
+    let push_u16 = |elf: &mut Vec<u8>, value: u16| elf.extend_from_slice(&value.to_be_bytes());
+    let push_u32 = |elf: &mut Vec<u8>, value: u32| elf.extend_from_slice(&u32_to_bytes_be(value));
+
+    // Identification: magic number, 32-bit class, big-endian data, current version, System V ABI
+    elf.extend_from_slice(&[0x7F, b'E', b'L', b'F', 1, 2, 1, 0]);
+    elf.resize(16, 0);
+
+    push_u16(&mut elf, 1); // e_type: relocatable file
+    push_u16(&mut elf, ELF_MACHINE); // e_machine
+    push_u32(&mut elf, 1); // e_version
+    push_u32(&mut elf, base_address); // e_entry
+    push_u32(&mut elf, 0); // e_phoff: no program header
+    push_u32(&mut elf, sh_offset); // e_shoff
+    push_u32(&mut elf, 0); // e_flags
+    push_u16(&mut elf, ELF_HEADER_SIZE as u16); // e_ehsize
+    push_u16(&mut elf, 0); // e_phentsize
+    push_u16(&mut elf, 0); // e_phnum
+    push_u16(&mut elf, SECTION_HEADER_SIZE as u16); // e_shentsize
+    push_u16(&mut elf, 3); // e_shnum
+    push_u16(&mut elf, 2); // e_shstrndx
+
+    elf.extend_from_slice(code);
+    elf.extend_from_slice(SECTION_NAMES);
+    elf.resize(sh_offset as usize, 0);
+
+    // Section headers: name, type, flags, address, offset, size, link, info, alignment, entry size
+    let sections = [
+        [0; 10],
+        [1, 1, 0x6, base_address, text_offset, text_size, 0, 0, 4, 0],
+        [7, 3, 0, 0, names_offset, names_size, 0, 0, 1, 0],
+    ];
+
+    for field in sections.iter().flatten() {
+        push_u32(&mut elf, *field);
+    }
+
+    elf
+}
@@ -0,0 +1,53 @@
+use super::{assemble, is_reserved_name, to_const_name};
+use lrvm::mem::ContiguousMappingResult;
+use std::collections::HashSet;
+
+/// Generate LASM constants containing the start address of each successfully mapped component (e.g. `BUFFERED_DISPLAY = 0x00001000`),
+/// one per line, in the components' order.
+///
+/// Constants are named after the components' generic name in uppercase, with non-alphanumeric characters replaced by underscores.
+/// If a name is already taken (by a previous component or by the LASM header), a `_2`, `_3`, ... suffix is added to it.
+pub fn mapping_to_lasm_consts(mapping: &ContiguousMappingResult) -> String {
+    let mut used = HashSet::new();
+    let mut lines = vec![];
+
+    for aux in &mapping.aux_mapping {
+        let addr = match &aux.aux_mapping {
+            Ok(range) => range.start_addr,
+            Err(_) => continue,
+        };
+
+        let mut base = to_const_name(&aux.aux_name);
+
+        if base.is_empty() || base.starts_with(|c: char| c.is_ascii_digit()) {
+            base.insert(0, '_');
+        }
+
+        let mut name = base.clone();
+        let mut suffix = 1;
+
+        while used.contains(&name) || is_reserved_name(&name) {
+            suffix += 1;
+            name = format!("{}_{}", base, suffix);
+        }
+
+        lines.push(format!("{} = {:#010X}", name, addr));
+        used.insert(name);
+    }
+
+    lines.join("\n")
+}
+
+/// Assemble a LASM source code to machine code, with the start address of each mapped component declared as a constant
+/// before the source code (see [`mapping_to_lasm_consts`]).
+/// Returns an error message in case of error.
+pub fn assemble_for_machine(
+    source: &str,
+    mapping: &ContiguousMappingResult,
+) -> Result<Vec<u8>, String> {
+    let mut src = mapping_to_lasm_consts(mapping);
+    src.push('\n');
+    src.push_str(source);
+
+    assemble(&src)
+}
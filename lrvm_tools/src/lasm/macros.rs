@@ -0,0 +1,309 @@
+use super::{is_identifier, PreprocessError};
+use std::collections::HashMap;
+
+/// Maximum nesting depth of macro invocations (beyond, the macro is considered as infinitely recursive)
+const MAX_MACRO_DEPTH: usize = 16;
+
+/// Origin of a line produced by [`expand_macros`]. Lines are counted from 1.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum LineOrigin {
+    /// Line of the source code
+    Source(usize),
+    /// Line generated by a macro, with the line of the macro's body it comes from
+    /// and the line of the source code invoking the macro
+    Macro {
+        name: String,
+        definition: usize,
+        invocation: usize,
+    },
+}
+
+impl LineOrigin {
+    /// Get the line of the source code the line comes from (the invocation's line for macro-generated lines)
+    pub fn source_line(&self) -> usize {
+        match self {
+            Self::Source(line) => *line,
+            Self::Macro { invocation, .. } => *invocation,
+        }
+    }
+}
+
+/// Macro defined with `#macro`
+#[derive(Clone)]
+struct Macro {
+    params: Vec<String>,
+    /// Body's lines, with their line number
+    body: Vec<(usize, String)>,
+}
+
+/// State of the expansion
+#[derive(Default)]
+struct Expander {
+    defines: HashMap<String, String>,
+    macros: HashMap<String, Macro>,
+    out: Vec<String>,
+    origins: Vec<LineOrigin>,
+}
+
+/// Expand the text-substitution directives of a LASM source code:
+///
+/// * `#define NAME value` replaces each following occurrence of the `NAME` identifier by `value` (which is not expanded itself)
+/// * `#macro name(a, b)` starts the definition of a parameterized macro, whose body ends with an `#endmacro` line.
+///   Writing `name(x, y)` alone on a line replaces it with the macro's body, where the `a` and `b` identifiers are replaced by `x` and `y`.
+///   Macros can invoke other macros, up to a nesting depth of 16.
+///
+/// Directives are replaced by empty lines. Returns the expanded source code, along with the origin of each of its lines.
+pub fn expand_macros(source: &str) -> Result<(String, Vec<LineOrigin>), PreprocessError> {
+    let mut exp = Expander::default();
+    let mut defining: Option<(usize, String, Macro)> = None;
+
+    for (i, line) in source.lines().enumerate() {
+        let line_nb = i + 1;
+        let mut words = line.split(';').next().unwrap().split_whitespace();
+
+        match words.next() {
+            Some("#macro") => {
+                if let Some((opened_at, _, _)) = defining {
+                    return Err(PreprocessError::NestedMacro {
+                        line: line_nb,
+                        opened_at,
+                    });
+                }
+
+                let header = line.trim_start()["#macro".len()..].trim();
+                let (name, params) = parse_invocation(header)
+                    .filter(|(_, params)| params.iter().all(|param| is_identifier(param)))
+                    .ok_or(PreprocessError::InvalidMacro { line: line_nb })?;
+
+                if exp.macros.contains_key(name) {
+                    return Err(PreprocessError::DuplicateMacro {
+                        line: line_nb,
+                        name: name.to_string(),
+                    });
+                }
+
+                let params = params.iter().map(|param| param.to_string()).collect();
+                defining = Some((
+                    line_nb,
+                    name.to_string(),
+                    Macro {
+                        params,
+                        body: vec![],
+                    },
+                ));
+            }
+
+            Some("#endmacro") => match defining.take() {
+                Some((_, name, mac)) => {
+                    exp.macros.insert(name, mac);
+                }
+                None => return Err(PreprocessError::UnexpectedEndmacro { line: line_nb }),
+            },
+
+            _ if defining.is_some() => {
+                if let Some((_, _, mac)) = &mut defining {
+                    mac.body.push((line_nb, line.to_string()));
+                }
+            }
+
+            Some("#define") => {
+                let name = words
+                    .next()
+                    .filter(|name| is_identifier(name))
+                    .ok_or(PreprocessError::InvalidDefine { line: line_nb })?;
+
+                let code = line.split(';').next().unwrap();
+                let value = code.trim_start()["#define".len()..].trim_start()[name.len()..].trim();
+
+                exp.defines.insert(name.to_string(), value.to_string());
+            }
+
+            _ => {
+                exp.expand_line(line, LineOrigin::Source(line_nb), line_nb, 0)?;
+                continue;
+            }
+        }
+
+        exp.out.push(String::new());
+        exp.origins.push(LineOrigin::Source(line_nb));
+    }
+
+    if let Some((opened_at, _, _)) = defining {
+        return Err(PreprocessError::UnterminatedMacro { line: opened_at });
+    }
+
+    Ok((exp.out.join("\n"), exp.origins))
+}
+
+impl Expander {
+    /// Expand a single line, which is either a macro invocation or a line where defined names are substituted.
+    /// `invocation` is the line of the source code the line comes from, and `depth` the number of macros it has been expanded from.
+    fn expand_line(
+        &mut self,
+        line: &str,
+        origin: LineOrigin,
+        invocation: usize,
+        depth: usize,
+    ) -> Result<(), PreprocessError> {
+        let code = line.split(';').next().unwrap().trim();
+
+        let (name, mac, args) = match parse_invocation(code)
+            .and_then(|(name, args)| Some((name, self.macros.get(name)?.clone(), args)))
+        {
+            Some(invoked) => invoked,
+            None => {
+                let defines = &self.defines;
+                self.out.push(substitute(line, |ident| {
+                    defines.get(ident).map(String::as_str)
+                }));
+                self.origins.push(origin);
+                return Ok(());
+            }
+        };
+
+        if depth == MAX_MACRO_DEPTH {
+            return Err(PreprocessError::MacroRecursionLimit {
+                line: invocation,
+                name: name.to_string(),
+            });
+        }
+
+        if args.len() != mac.params.len() {
+            return Err(PreprocessError::WrongArgCount {
+                line: invocation,
+                name: name.to_string(),
+                expected: mac.params.len(),
+                got: args.len(),
+            });
+        }
+
+        if mac.body.is_empty() {
+            self.out.push(String::new());
+            self.origins.push(origin);
+        }
+
+        let args: HashMap<&str, &str> = mac.params.iter().map(String::as_str).zip(args).collect();
+
+        for (definition, body_line) in &mac.body {
+            let origin = LineOrigin::Macro {
+                name: name.to_string(),
+                definition: *definition,
+                invocation,
+            };
+
+            let body_line = substitute(body_line, |ident| args.get(ident).copied());
+            self.expand_line(&body_line, origin, invocation, depth + 1)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Parse a `name(a, b)` expression into its name and arguments
+fn parse_invocation(code: &str) -> Option<(&str, Vec<&str>)> {
+    let (name, rest) = code.split_at(code.find('(')?);
+    let name = name.trim();
+
+    if !is_identifier(name) || !rest.ends_with(')') {
+        return None;
+    }
+
+    let args = rest[1..rest.len() - 1].trim();
+
+    if args.is_empty() {
+        return Some((name, vec![]));
+    }
+
+    Some((name, args.split(',').map(str::trim).collect()))
+}
+
+/// Replace the identifiers of a line (outside of its comment) using the provided function
+fn substitute<'a>(line: &str, replace: impl Fn(&str) -> Option<&'a str>) -> String {
+    let (code, comment) = line.split_at(line.find(';').unwrap_or(line.len()));
+
+    let mut out = String::new();
+    let mut word = String::new();
+
+    for c in code.chars().chain(std::iter::once('\0')) {
+        if c.is_ascii_alphanumeric() || c == '_' {
+            word.push(c);
+            continue;
+        }
+
+        if !word.is_empty() {
+            match replace(&word) {
+                Some(value) if is_identifier(&word) => out.push_str(value),
+                _ => out.push_str(&word),
+            }
+
+            word.clear();
+        }
+
+        if c != '\0' {
+            out.push(c);
+        }
+    }
+
+    out.push_str(comment);
+    out
+}
+
+/// Make the lines of the source code an assembler's error message refers to (as `src.lasm:<line>`) point to the lines of the source code
+/// before expansion, and add a note citing the macro definition's line for each macro-generated line.
+/// `header_lines` is the number of lines prepended to the expanded source code before it was assembled.
+pub(crate) fn remap_error_lines(err: &str, origins: &[LineOrigin], header_lines: usize) -> String {
+    const FILE: &str = "src.lasm:";
+
+    let mut out = String::new();
+    let mut notes = vec![];
+    let mut rest = err;
+
+    while let Some(pos) = rest.find(FILE) {
+        let (before, after) = rest.split_at(pos + FILE.len());
+        out.push_str(before);
+
+        let digits = after
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(after.len());
+
+        let origin = after[..digits]
+            .parse::<usize>()
+            .ok()
+            .and_then(|line| line.checked_sub(header_lines + 1))
+            .and_then(|index| origins.get(index));
+
+        match origin {
+            Some(origin) => {
+                out.push_str(&origin.source_line().to_string());
+
+                if let LineOrigin::Macro {
+                    name,
+                    definition,
+                    invocation,
+                } = origin
+                {
+                    let note = format!(
+                        "note: line {} invokes macro '{}', this code comes from line {} of its definition",
+                        invocation, name, definition
+                    );
+
+                    if !notes.contains(&note) {
+                        notes.push(note);
+                    }
+                }
+            }
+            None => out.push_str(&after[..digits]),
+        }
+
+        rest = &after[digits..];
+    }
+
+    out.push_str(rest);
+
+    for note in notes {
+        out.push('\n');
+        out.push_str(&note);
+    }
+
+    out
+}
@@ -1,17 +1,145 @@
 //! LRVM uses an assembly language called LASM (Lightweight Assembly).
 //! This module allows to assemble LASM source code through the [CustomAsm](https://github.com/hlorenzi/customasm) library.
 
-use crate::asm::{InstrDecodingError, Program};
+pub mod stdlib;
+
+mod cache;
+mod elf;
+mod machine;
+pub(crate) mod macros;
+mod options;
+mod preprocess;
+mod reach;
+mod report;
+
+pub use cache::AssembleCache;
+pub use elf::{assemble_to_elf, elf_from_binary, ELF_MACHINE};
+pub use machine::{assemble_for_machine, mapping_to_lasm_consts};
+pub use macros::{expand_macros, LineOrigin};
+pub use options::{AssembleError, AssembleOptions};
+pub use preprocess::{preprocess, PreprocessError};
+pub use reach::{check_branch_reach, ReachIssue};
+pub use report::{assemble_report, AlignmentGap, LabelRegion, SizeReport};
+
+use crate::asm::{InstrDecodingError, Program, ProgramTooLarge};
 use crate::bytes::{bytes_to_words, words_to_bytes};
 use customasm::asm::Assembler;
 use customasm::diagn::RcReport;
 use customasm::util::FileServerMock;
+use std::collections::HashMap;
+use std::convert::TryFrom;
 
 static CUSTOMASM_HEADER: &str = include_str!("customasm.def");
 
+/// LASM routine writing a table describing all the components connected to the motherboard.
+/// Put the table's address in `rr0`, then `call enumerate_devices`. Routine registers `rr0` to `rr3` are rewritten.
+/// See [`crate::asm::ExtInstr::EnumerateDevices`] for the table's format.
+pub static ENUMERATE_DEVICES_ROUTINE: &str = include_str!("enumerate.lasm");
+
+/// Get the version of the CustomAsm library used to assemble LASM source code, as found in the lockfile when LRVM was built
+/// (with the commit's hash as build metadata if it comes from a Git repository), or `"unknown"` if it could not be determined.
+/// Useful to report differences of behaviour between environments.
+pub fn assembler_version() -> &'static str {
+    env!("LRVM_CUSTOMASM_VERSION")
+}
+
 /// Assemble a LASM source code to machine code.
 /// Returns an error message in case of error.
 pub fn assemble(source: &str) -> Result<Vec<u8>, String> {
+    assemble_with_files(source, vec![])
+}
+
+/// Assemble a LASM source code to machine code with the provided options.
+/// The source code's conditional directives are evaluated first, using the options' defined symbols (see [`preprocess`]),
+/// then its text-substitution directives are expanded if the preprocessor is enabled (see [`expand_macros`]).
+/// Returns an error in case of invalid directives or assembly error, or if the assembled program doesn't respect
+/// the options' constraints.
+pub fn assemble_with_options(
+    source: &str,
+    options: &AssembleOptions,
+) -> Result<Vec<u8>, AssembleError> {
+    let source = preprocess(source, &options.defines).map_err(AssembleError::Preprocess)?;
+
+    let bytes = if options.preprocessor {
+        let (source, origins) = expand_macros(&source).map_err(AssembleError::Preprocess)?;
+
+        // Errors point to the expanded source code, which is preceded by the header's inclusion line
+        assemble(&source)
+            .map_err(|err| AssembleError::Assembly(macros::remap_error_lines(&err, &origins, 1)))?
+    } else {
+        assemble(&source).map_err(AssembleError::Assembly)?
+    };
+
+    if let Some(max_size) = options.max_size_bytes {
+        ProgramTooLarge::check(bytes.len(), max_size).map_err(AssembleError::TooLarge)?;
+    }
+
+    Ok(bytes)
+}
+
+/// Assemble a LASM source code to machine code, failing if the assembled program is larger than the provided size (in bytes).
+/// Shortcut for [`assemble_with_options`] with [`AssembleOptions::max_size_bytes`].
+pub fn assemble_checked(source: &str, max_bytes: usize) -> Result<Vec<u8>, AssembleError> {
+    assemble_with_options(
+        source,
+        &AssembleOptions::new().max_size_bytes(u32::try_from(max_bytes).unwrap_or(u32::MAX)),
+    )
+}
+
+/// Assemble a LASM source code to machine code, with a set of named binary blobs the source code can include with `#incbin "<name>"`.
+///
+/// Blobs are padded with zeros to a multiple of 4 bytes, so the instructions following them stay aligned.
+/// For each blob, a constant containing its original length (in bytes) is declared before the source code,
+/// named after the blob in uppercase with non-alphanumeric characters replaced by underscores and a `_LEN` suffix
+/// (e.g. `LOGO_BIN_LEN` for `logo.bin`).
+///
+/// Returns an error message if a blob's name is used twice or collides with an internal file name, if the name of its constant
+/// is not a valid identifier or is the same as another blob's one (e.g. for `a.bin` and `a_bin`), or in case of assembly error.
+pub fn assemble_with_blobs(source: &str, blobs: &[(&str, &[u8])]) -> Result<Vec<u8>, String> {
+    let mut src = String::new();
+    let mut files: Vec<(String, Vec<u8>)> = vec![];
+    let mut len_consts: HashMap<String, &str> = HashMap::new();
+
+    for (name, blob) in blobs {
+        if *name == "header.lasm" || *name == "src.lasm" {
+            return Err(format!("Blob name '{}' is reserved", name));
+        }
+
+        if files.iter().any(|(file, _)| file == name) {
+            return Err(format!("Blob name '{}' is used twice", name));
+        }
+
+        let len_const = format!("{}_LEN", to_const_name(name));
+
+        if !is_identifier(&len_const) {
+            return Err(format!(
+                "Blob name '{}' must start with a letter or an underscore",
+                name
+            ));
+        }
+
+        if let Some(other) = len_consts.insert(len_const.clone(), name) {
+            return Err(format!(
+                "Blob names '{}' and '{}' both result in constant '{}'",
+                other, name, len_const
+            ));
+        }
+
+        src.push_str(&format!("{} = {}\n", len_const, blob.len()));
+
+        let mut padded = blob.to_vec();
+        padded.resize(blob.len() + (4 - blob.len() % 4) % 4, 0);
+
+        files.push((name.to_string(), padded));
+    }
+
+    src.push_str(source);
+
+    assemble_with_files(&src, files)
+}
+
+/// (Internal) Assemble a LASM source code to machine code, with additional files available to include
+fn assemble_with_files(source: &str, files: Vec<(String, Vec<u8>)>) -> Result<Vec<u8>, String> {
     let mut src = String::from("#include \"header.lasm\"");
     src.push('\n');
     src.push_str(source);
@@ -20,6 +148,10 @@ pub fn assemble(source: &str) -> Result<Vec<u8>, String> {
     fileserver.add("header.lasm", CUSTOMASM_HEADER);
     fileserver.add("src.lasm", src);
 
+    for (name, content) in files {
+        fileserver.add(name, content);
+    }
+
     let assemble =
         |report: RcReport, fileserver: &FileServerMock, filename: &str| -> Result<Vec<u8>, ()> {
             let mut asm = Assembler::new();
@@ -38,12 +170,78 @@ pub fn assemble(source: &str) -> Result<Vec<u8>, String> {
     })
 }
 
+/// Assemble a LASM source code to machine code, with a set of constants declared before the source code.
+/// Constant names must be valid identifiers and cannot collide with a register, flag or instruction name.
+/// Colliding with a symbol declared in the source code results in an assembly error.
+/// Returns an error message in case of error.
+pub fn assemble_with_defines(
+    source: &str,
+    defines: &HashMap<String, i64>,
+) -> Result<Vec<u8>, String> {
+    let mut names: Vec<_> = defines.keys().collect();
+    names.sort();
+
+    let mut src = String::new();
+
+    for name in names {
+        if !is_identifier(name) {
+            return Err(format!("Invalid constant name: '{}'", name));
+        }
+
+        if is_reserved_name(name) {
+            return Err(format!("Constant name '{}' is reserved", name));
+        }
+
+        src.push_str(&format!("{} = {}\n", name, defines[name]));
+    }
+
+    src.push_str(source);
+
+    assemble(&src)
+}
+
+/// Convert a name to a constant name: uppercase, with non-alphanumeric characters replaced by underscores
+pub(crate) fn to_const_name(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_uppercase()
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+/// Check if a name is a valid identifier (a letter or an underscore, followed by alphanumeric characters or underscores)
+pub(crate) fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+
+    matches!(chars.next(), Some(c) if c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Check if a name is used by the LASM header (register, flag, mode or instruction name)
+fn is_reserved_name(name: &str) -> bool {
+    CUSTOMASM_HEADER
+        .lines()
+        .filter_map(|line| line.split_whitespace().next())
+        .any(|word| word == name)
+}
+
 /// Assemble a LASM source code to machine code and split it to words.
 /// Returns an error message in case of error.
 pub fn assemble_words(source: &str) -> Result<Vec<u32>, String> {
     assemble(source).map(bytes_to_words)
 }
 
+/// Count the number of words a LASM source code assembles to, e.g. for size budgeting.
+/// As CustomAsm does not provide a dry-run mode, the source code is fully assembled and the output is discarded.
+/// Returns an error message in case of error.
+pub fn count_instructions(source: &str) -> Result<usize, String> {
+    assemble(source).map(|bytes| bytes.len() / 4)
+}
+
 /// Convert a LASM source code to a strongly-typed program
 /// May fail because Program::decode() may fail if for instance there is raw data in the assembled program (strings for instance)
 pub fn assemble_prog(source: &str) -> Result<Result<Program, (usize, InstrDecodingError)>, String> {
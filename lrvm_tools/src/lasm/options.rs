@@ -0,0 +1,58 @@
+use super::PreprocessError;
+use crate::asm::ProgramTooLarge;
+use std::collections::BTreeSet;
+use std::fmt;
+
+/// Options for [`super::assemble_with_options`]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct AssembleOptions {
+    pub max_size_bytes: Option<u32>,
+    pub defines: BTreeSet<String>,
+    pub preprocessor: bool,
+}
+
+impl AssembleOptions {
+    /// Create default options (no size limit, no symbol defined, text-substitution preprocessor disabled)
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Fail if the assembled program is larger than the provided size, in bytes (e.g. the size of the ROM it will be loaded in)
+    pub fn max_size_bytes(mut self, max_size_bytes: u32) -> Self {
+        self.max_size_bytes = Some(max_size_bytes);
+        self
+    }
+
+    /// Define a symbol for the conditional directives of the source code (see [`super::preprocess`])
+    pub fn define(mut self, name: impl Into<String>) -> Self {
+        self.defines.insert(name.into());
+        self
+    }
+
+    /// Expand the `#define` and `#macro` directives of the source code (see [`super::expand_macros`])
+    pub fn enable_preprocessor(mut self, enable: bool) -> Self {
+        self.preprocessor = enable;
+        self
+    }
+}
+
+/// Error returned by [`super::assemble_with_options`]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum AssembleError {
+    /// The source code's conditional or text-substitution directives are invalid
+    Preprocess(PreprocessError),
+    /// The source code failed to assemble (contains the assembler's error message)
+    Assembly(String),
+    /// The assembled program is larger than the maximum allowed size
+    TooLarge(ProgramTooLarge),
+}
+
+impl fmt::Display for AssembleError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Preprocess(err) => write!(f, "{}", err),
+            Self::Assembly(err) => write!(f, "{}", err),
+            Self::TooLarge(err) => write!(f, "{}", err),
+        }
+    }
+}
@@ -0,0 +1,175 @@
+use std::collections::BTreeSet;
+use std::fmt;
+
+/// Error returned by [`preprocess`] and [`super::expand_macros`]. Lines are counted from 1.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum PreprocessError {
+    /// An `#ifdef` directive is not followed by a symbol name
+    MissingName { line: usize },
+    /// An `#ifdef` directive appears inside another conditional block, which is not supported
+    Nested { line: usize, opened_at: usize },
+    /// An `#ifdef` directive has no matching `#endif`
+    Unterminated { line: usize },
+    /// An `#else` directive appears outside of a conditional block, or twice in the same block
+    UnexpectedElse { line: usize },
+    /// An `#endif` directive appears outside of a conditional block
+    UnexpectedEndif { line: usize },
+    /// A `#define` directive is not followed by a valid identifier
+    InvalidDefine { line: usize },
+    /// A `#macro` directive is not followed by a `name(param1, param2, ...)` header
+    InvalidMacro { line: usize },
+    /// A macro is defined twice
+    DuplicateMacro { line: usize, name: String },
+    /// A `#macro` directive appears inside another macro's definition
+    NestedMacro { line: usize, opened_at: usize },
+    /// A `#macro` directive has no matching `#endmacro`
+    UnterminatedMacro { line: usize },
+    /// An `#endmacro` directive appears outside of a macro's definition
+    UnexpectedEndmacro { line: usize },
+    /// A macro is invoked with a wrong number of arguments
+    WrongArgCount {
+        line: usize,
+        name: String,
+        expected: usize,
+        got: usize,
+    },
+    /// Macro invocations are nested too deeply, which usually indicates a recursive macro
+    MacroRecursionLimit { line: usize, name: String },
+}
+
+impl fmt::Display for PreprocessError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::MissingName { line } => write!(f, "Line {}: '#ifdef' requires a symbol name", line),
+            Self::Nested { line, opened_at } => write!(
+                f,
+                "Line {}: nested '#ifdef' (inside the conditional block opened at line {}) is not supported",
+                line, opened_at
+            ),
+            Self::Unterminated { line } => {
+                write!(f, "Line {}: '#ifdef' is never closed by an '#endif'", line)
+            }
+            Self::UnexpectedElse { line } => write!(
+                f,
+                "Line {}: '#else' does not match any '#ifdef' without an '#else'",
+                line
+            ),
+            Self::UnexpectedEndif { line } => {
+                write!(f, "Line {}: '#endif' does not match any '#ifdef'", line)
+            }
+            Self::InvalidDefine { line } => {
+                write!(f, "Line {}: '#define' requires a valid identifier", line)
+            }
+            Self::InvalidMacro { line } => write!(
+                f,
+                "Line {}: '#macro' requires a 'name(param1, param2, ...)' header",
+                line
+            ),
+            Self::DuplicateMacro { line, name } => {
+                write!(f, "Line {}: macro '{}' is already defined", line, name)
+            }
+            Self::NestedMacro { line, opened_at } => write!(
+                f,
+                "Line {}: nested '#macro' (inside the macro definition opened at line {}) is not supported",
+                line, opened_at
+            ),
+            Self::UnterminatedMacro { line } => {
+                write!(f, "Line {}: '#macro' is never closed by an '#endmacro'", line)
+            }
+            Self::UnexpectedEndmacro { line } => {
+                write!(f, "Line {}: '#endmacro' does not match any '#macro'", line)
+            }
+            Self::WrongArgCount {
+                line,
+                name,
+                expected,
+                got,
+            } => write!(
+                f,
+                "Line {}: macro '{}' expects {} argument(s), got {}",
+                line, name, expected, got
+            ),
+            Self::MacroRecursionLimit { line, name } => write!(
+                f,
+                "Line {}: expanding macro '{}' exceeds the maximum nesting depth (is it recursive?)",
+                line, name
+            ),
+        }
+    }
+}
+
+/// State of the conditional block being processed
+struct Block {
+    opened_at: usize,
+    in_else: bool,
+    defined: bool,
+}
+
+/// Evaluate the conditional directives of a LASM source code: `#ifdef NAME`, `#else` and `#endif`.
+/// The lines of a `#ifdef` block are kept if the provided symbol is defined, the lines of its `#else` block otherwise.
+///
+/// Directives and removed lines are replaced by empty lines, so the assembler's errors still point to the right lines.
+/// Conditional blocks can't be nested.
+pub fn preprocess(source: &str, defines: &BTreeSet<String>) -> Result<String, PreprocessError> {
+    let mut out = vec![];
+    let mut block: Option<Block> = None;
+
+    for (i, line) in source.lines().enumerate() {
+        let line_nb = i + 1;
+        let mut words = line.split_whitespace();
+
+        match words.next() {
+            Some("#ifdef") => {
+                if let Some(block) = &block {
+                    return Err(PreprocessError::Nested {
+                        line: line_nb,
+                        opened_at: block.opened_at,
+                    });
+                }
+
+                let name = words
+                    .next()
+                    .ok_or(PreprocessError::MissingName { line: line_nb })?;
+
+                block = Some(Block {
+                    opened_at: line_nb,
+                    in_else: false,
+                    defined: defines.contains(name),
+                });
+            }
+
+            Some("#else") => match &mut block {
+                Some(block) if !block.in_else => block.in_else = true,
+                _ => return Err(PreprocessError::UnexpectedElse { line: line_nb }),
+            },
+
+            Some("#endif") => {
+                if block.take().is_none() {
+                    return Err(PreprocessError::UnexpectedEndif { line: line_nb });
+                }
+            }
+
+            _ => {
+                let kept = match &block {
+                    Some(block) => block.defined != block.in_else,
+                    None => true,
+                };
+
+                if kept {
+                    out.push(line);
+                    continue;
+                }
+            }
+        }
+
+        out.push("");
+    }
+
+    if let Some(block) = block {
+        return Err(PreprocessError::Unterminated {
+            line: block.opened_at,
+        });
+    }
+
+    Ok(out.join("\n"))
+}
@@ -0,0 +1,149 @@
+use super::{assemble, is_identifier};
+use crate::asm::Reg;
+use crate::bytes::bytes_be_to_u32;
+use std::fmt;
+
+/// Branch whose target is out of reach (see [`check_branch_reach`])
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ReachIssue {
+    /// Line of the branch in the source code (starting at 1)
+    pub line: usize,
+    /// Label targeted by the branch
+    pub label: String,
+    /// Distance to the target, in words: from the branch for relative jumps, from address `0x00000000` for absolute ones
+    pub distance: i64,
+    /// Maximum distance the branch can reach in the target's direction, in words
+    pub max_reach: i64,
+}
+
+impl fmt::Display for ReachIssue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Line {}: branch to '{}' spans {} words, which exceeds its maximum reach of {} words",
+            self.line, self.label, self.distance, self.max_reach
+        )
+    }
+}
+
+/// Branch found in the source code
+struct Branch {
+    label: String,
+    relative: bool,
+}
+
+/// Find the branches (conditional or not) of a LASM source code whose target label is too far to be encoded in their immediate operand:
+/// absolute targets (`jp label`, `call label` and `lsm label`) must fit in 16 bits, and relative ones (`jpr label - $`)
+/// must be within a signed 16-bit offset of the branch.
+///
+/// As CustomAsm fails on the first branch that overflows without naming its target, the source code is assembled with the branches
+/// replaced by placeholders of the same size to find their addresses and the addresses of their targets.
+/// Branches to registers, local labels or more complex expressions are not checked.
+///
+/// Returns an error message if the source code fails to assemble for another reason.
+pub fn check_branch_reach(source: &str) -> Result<Vec<ReachIssue>, String> {
+    let lines: Vec<&str> = source.lines().collect();
+    let branches: Vec<(usize, Branch)> = lines
+        .iter()
+        .enumerate()
+        .filter_map(|(i, line)| parse_branch(line).map(|branch| (i, branch)))
+        .collect();
+
+    if branches.is_empty() {
+        return Ok(vec![]);
+    }
+
+    // Replace the branches by a placeholder word, and append the targets' addresses
+    let probe = |placeholder: &str| -> Result<Vec<u8>, String> {
+        let mut probe = lines.clone();
+
+        for (i, _) in &branches {
+            probe[*i] = placeholder;
+        }
+
+        let mut probe = probe.join("\n");
+        probe.push('\n');
+
+        for (_, branch) in &branches {
+            probe.push_str(&format!("#d32 {}\n", branch.label));
+        }
+
+        assemble(&probe)
+    };
+
+    let zeros = probe("#d32 0x00000000")?;
+    let ones = probe("#d32 0xFFFFFFFF")?;
+
+    // Placeholders are the only bytes differing between the two probes
+    let mut addrs = vec![];
+    let mut offset = 0;
+
+    while offset < zeros.len() {
+        if zeros[offset] != ones[offset] {
+            addrs.push(offset as i64);
+            offset += 4;
+        } else {
+            offset += 1;
+        }
+    }
+
+    if addrs.len() != branches.len() {
+        return Err("Failed to locate the branches in the assembled program".to_string());
+    }
+
+    let targets = zeros[zeros.len() - branches.len() * 4..]
+        .chunks(4)
+        .map(|bytes| i64::from(bytes_be_to_u32([bytes[0], bytes[1], bytes[2], bytes[3]])));
+
+    let mut issues = vec![];
+
+    for (((i, branch), addr), target) in branches.into_iter().zip(addrs).zip(targets) {
+        let (distance, max_reach): (i64, i64) = if branch.relative {
+            let distance = (target - addr) / 4;
+            let max_reach = if distance < 0 { -0x2000 } else { 0x1FFF };
+            (distance, max_reach)
+        } else {
+            (target / 4, 0x3FFF)
+        };
+
+        if distance.abs() > max_reach.abs() {
+            issues.push(ReachIssue {
+                line: i + 1,
+                label: branch.label,
+                distance,
+                max_reach,
+            });
+        }
+    }
+
+    Ok(issues)
+}
+
+/// Parse a branch to a label
+fn parse_branch(line: &str) -> Option<Branch> {
+    let code = line.split(';').next().unwrap().trim();
+    let (instr, operand) = code.split_at(code.find(char::is_whitespace)?);
+    let operand = operand.trim();
+
+    let (label, relative) = match instr {
+        "jp" | "call" | "lsm" => (operand, false),
+        "jpr" => (
+            operand
+                .strip_suffix('$')?
+                .trim_end()
+                .strip_suffix('-')?
+                .trim_end(),
+            true,
+        ),
+        _ => return None,
+    };
+
+    if !is_identifier(label) || Reg::from_name(label).is_ok() {
+        return None;
+    }
+
+    Some(Branch {
+        label: label.to_string(),
+        relative,
+    })
+}
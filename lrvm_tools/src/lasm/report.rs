@@ -0,0 +1,186 @@
+use super::{assemble, is_identifier};
+use crate::bytes::bytes_be_to_u32;
+use std::fmt;
+
+/// Size report of a LASM program (see [`assemble_report`])
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SizeReport {
+    /// Total size of the assembled program, in bytes
+    pub total_bytes: usize,
+    /// Regions of the program, sorted by address
+    pub regions: Vec<LabelRegion>,
+    /// Padding introduced by `#align` directives, largest first
+    pub alignment_gaps: Vec<AlignmentGap>,
+}
+
+/// Region of a program starting at a top-level label and ending at the next one (or at the end of the program)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LabelRegion {
+    /// Label's name (`(start)` for the code preceding the first label)
+    pub label: String,
+    /// Label's address
+    pub addr: u32,
+    /// Region's size, in bytes
+    pub size: usize,
+}
+
+/// Padding introduced by an alignment directive
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AlignmentGap {
+    /// Line of the directive in the source code (starting at 1)
+    pub line: usize,
+    /// Size of the padding, in bytes
+    pub size: usize,
+}
+
+impl SizeReport {
+    /// Get the total size of the assembled program, in words
+    pub fn total_words(&self) -> usize {
+        self.total_bytes / 4 + usize::from(self.total_bytes % 4 != 0)
+    }
+}
+
+/// Assemble a LASM source code and report the size of each region of the program, e.g. to find what makes it too large.
+///
+/// As CustomAsm does not expose the symbols it resolved, the address of each top-level label is obtained by assembling
+/// the source code again with the labels' addresses appended as data. The padding introduced by each `#align` directive
+/// is computed by assembling the source code without the directive, so the source code is assembled several times.
+///
+/// Returns an error message in case of error.
+pub fn assemble_report(source: &str) -> Result<SizeReport, String> {
+    let total_bytes = assemble(source)?.len();
+
+    let labels = top_level_labels(source);
+
+    let mut probe = source.to_string();
+    probe.push('\n');
+
+    for label in &labels {
+        probe.push_str(&format!("#d32 {}\n", label));
+    }
+
+    let probe = assemble(&probe)?;
+    let addrs = probe[probe.len() - labels.len() * 4..]
+        .chunks(4)
+        .map(|bytes| bytes_be_to_u32([bytes[0], bytes[1], bytes[2], bytes[3]]));
+
+    let mut starts: Vec<(String, u32)> = labels.into_iter().zip(addrs).collect();
+    starts.sort_by_key(|(_, addr)| *addr);
+
+    if starts
+        .first()
+        .map_or(total_bytes > 0, |(_, addr)| *addr > 0)
+    {
+        starts.insert(0, ("(start)".to_string(), 0));
+    }
+
+    let regions = starts
+        .iter()
+        .enumerate()
+        .map(|(i, (label, addr))| LabelRegion {
+            label: label.clone(),
+            addr: *addr,
+            size: starts
+                .get(i + 1)
+                .map_or(total_bytes, |(_, next)| *next as usize)
+                .saturating_sub(*addr as usize),
+        })
+        .collect();
+
+    let mut alignment_gaps = vec![];
+
+    for (i, line) in source.lines().enumerate() {
+        if !line.trim_start().starts_with("#align") {
+            continue;
+        }
+
+        let without: Vec<_> = source
+            .lines()
+            .enumerate()
+            .map(|(j, line)| if i == j { "" } else { line })
+            .collect();
+
+        if let Ok(bytes) = assemble(&without.join("\n")) {
+            if bytes.len() < total_bytes {
+                alignment_gaps.push(AlignmentGap {
+                    line: i + 1,
+                    size: total_bytes - bytes.len(),
+                });
+            }
+        }
+    }
+
+    alignment_gaps.sort_by(|a, b| b.size.cmp(&a.size).then(a.line.cmp(&b.line)));
+
+    Ok(SizeReport {
+        total_bytes,
+        regions,
+        alignment_gaps,
+    })
+}
+
+/// (Internal) List the top-level labels declared in a source code
+fn top_level_labels(source: &str) -> Vec<String> {
+    source
+        .lines()
+        .filter_map(|line| {
+            let line = line.split(';').next().unwrap().trim();
+            let name = &line[..line.find(':')?];
+
+            if is_identifier(name) {
+                Some(name.to_string())
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+impl fmt::Display for SizeReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "Total size: {:#X} bytes ({} words)",
+            self.total_bytes,
+            self.total_words()
+        )?;
+
+        let width = self
+            .regions
+            .iter()
+            .map(|region| region.label.len())
+            .max()
+            .unwrap_or(0)
+            .max("Label".len());
+
+        writeln!(f)?;
+        writeln!(
+            f,
+            "{:width$} | Address    | Size (bytes)",
+            "Label",
+            width = width
+        )?;
+
+        for region in &self.regions {
+            writeln!(
+                f,
+                "{:width$} | {:#010X} | {:#X}",
+                region.label,
+                region.addr,
+                region.size,
+                width = width
+            )?;
+        }
+
+        if !self.alignment_gaps.is_empty() {
+            writeln!(f)?;
+            writeln!(f, "Alignment gaps:")?;
+
+            for gap in &self.alignment_gaps {
+                writeln!(f, "  line {}: {:#X} bytes", gap.line, gap.size)?;
+            }
+        }
+
+        Ok(())
+    }
+}
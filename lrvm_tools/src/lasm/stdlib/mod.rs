@@ -0,0 +1,33 @@
+//! Library of common LASM routines.
+//!
+//! Each routine is a LASM snippet starting with a label named after it, so it can be appended to a program's source code
+//! and used with `call <name>`. Routines take their arguments in the routine registers (`rr0`, `rr1`, ...),
+//! and only rewrite the routine registers documented for each of them. A stack must be set up as they are called with `call`.
+//!
+//! Strings are null-terminated and stored as big-endian words (the first character is the most significant byte of the first word),
+//! which is the format expected by the buffered display.
+
+/// Copy a memory area to another, word by word.
+/// Put the destination address in `rr0`, the source address in `rr1` and the size (in bytes, multiple of 4) in `rr2`, then `call memcpy`.
+/// Routine registers `rr0` to `rr3` are rewritten.
+pub static MEMCPY: &str = include_str!("memcpy.lasm");
+
+/// Fill a memory area with a word.
+/// Put the area's address in `rr0`, the word in `rr1` and the size (in bytes, multiple of 4) in `rr2`, then `call memset`.
+/// Routine registers `rr0` and `rr2` are rewritten.
+pub static MEMSET: &str = include_str!("memset.lasm");
+
+/// Compute the length of a null-terminated string.
+/// Put the string's address in `rr0`, then `call strlen`. The length is put in `rr0`.
+/// Routine registers `rr0` to `rr4` are rewritten.
+pub static STRLEN: &str = include_str!("strlen.lasm");
+
+/// Print a null-terminated string (padded with null characters up to the next word boundary) on a buffered display.
+/// Put the string's address in `rr0`, the display's address in `rr1` and its capacity (in bytes) in `rr2`, then `call print_str`.
+/// Routine registers `rr0` and `rr3` to `rr7` are rewritten.
+pub static PRINT_STR: &str = include_str!("print_str.lasm");
+
+/// Write the decimal representation of an unsigned integer as a null-terminated string (12 bytes are written).
+/// Put the integer in `rr0` and the string's address in `rr1`, then `call itoa`. The string's length is put in `rr0`.
+/// Routine registers `rr0` and `rr2` to `rr7` are rewritten.
+pub static ITOA: &str = include_str!("itoa.lasm");
@@ -15,6 +15,7 @@ pub mod debug;
 pub mod exceptions;
 pub mod lasm;
 pub mod metadata;
+pub mod testing;
 
 #[cfg(test)]
 mod tests;
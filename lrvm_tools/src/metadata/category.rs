@@ -1,7 +1,7 @@
 use super::types::*;
 use std::fmt;
 
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum DeviceCategory {
     Debug(DebugType),
     Clock(ClockType),
@@ -65,6 +65,21 @@ impl DeviceCategory {
     pub fn encode(self) -> u64 {
         ((self.category_code() as u64) << 32) + self.type_code() as u64
     }
+
+    /// Get the canonical size (in bytes) of devices from this category, if it is fixed.
+    /// This is only a hint: devices may still advertise a different size in their metadata.
+    pub fn size_hint(&self) -> Option<u32> {
+        match self {
+            Self::Debug(DebugType::Basic) => Some(32),
+            Self::Clock(ClockType::Realtime) => Some(24),
+            Self::Clock(ClockType::HighResolution) => Some(8),
+            Self::Display(DisplayType::Number) => Some(16),
+            Self::Display(DisplayType::Character) => Some(4),
+            Self::Keyboard(KeyboardType::ReadCharSynchronous) => Some(8),
+            Self::Keyboard(KeyboardType::Asynchronous) => Some(8),
+            _ => None,
+        }
+    }
 }
 
 impl fmt::Display for DeviceCategory {
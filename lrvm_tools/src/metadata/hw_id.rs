@@ -0,0 +1,82 @@
+use std::collections::BTreeSet;
+
+/// Compute a hardware identifier from a component's name and instance number (to distinguish several components with the same name).
+///
+/// The identifier is a 64-bit FNV-1a hash of the name and instance number, so it is stable across runs and platforms.
+/// Unlike with [`HwIdAllocator`], two different inputs are not guaranteed to give different identifiers, although collisions are very unlikely.
+pub fn hw_id_from(name: &str, instance: u32) -> u64 {
+    const FNV_OFFSET_BASIS: u64 = 0xCBF2_9CE4_8422_2325;
+    const FNV_PRIME: u64 = 0x0000_0100_0000_01B3;
+
+    // The 0xFF separator never appears in UTF-8 strings, so the name's end is not ambiguous
+    name.bytes()
+        .chain(std::iter::once(0xFF))
+        .chain(instance.to_be_bytes().iter().copied())
+        .fold(FNV_OFFSET_BASIS, |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(FNV_PRIME)
+        })
+}
+
+/// Allocator of deterministic hardware identifiers.
+///
+/// Identifiers are derived from a seed and an incrementing counter, so the same board description always yields
+/// the same identifiers. An allocator never hands out the same identifier twice, including identifiers that were
+/// manually chosen and registered through [`HwIdAllocator::reserve`].
+#[derive(Debug, Clone)]
+pub struct HwIdAllocator {
+    seed: u64,
+    counter: u64,
+    used: BTreeSet<u64>,
+}
+
+impl HwIdAllocator {
+    /// Create an allocator from a seed
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            counter: 0,
+            used: BTreeSet::new(),
+        }
+    }
+
+    /// Get a new unique identifier
+    pub fn allocate(&mut self) -> u64 {
+        loop {
+            self.counter = self.counter.wrapping_add(1);
+
+            // SplitMix64 output function: a bijection, so two different counter values never give the same identifier
+            let mut z = self
+                .seed
+                .wrapping_add(self.counter.wrapping_mul(0x9E37_79B9_7F4A_7C15));
+            z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+            z ^= z >> 31;
+
+            if self.used.insert(z) {
+                return z;
+            }
+        }
+    }
+
+    /// Change the seed the next identifiers are derived from.
+    /// Identifiers allocated or reserved so far are still never handed out.
+    pub fn reseed(&mut self, seed: u64) {
+        self.seed = seed;
+        self.counter = 0;
+    }
+
+    /// Reserve a manually chosen identifier so it is never handed out by the allocator.
+    /// Fails if the identifier was already allocated or reserved.
+    pub fn reserve(&mut self, hw_id: u64) -> Result<(), &'static str> {
+        if self.used.insert(hw_id) {
+            Ok(())
+        } else {
+            Err("This hardware identifier is already in use")
+        }
+    }
+
+    /// Check if an identifier was already allocated or reserved
+    pub fn is_used(&self, hw_id: u64) -> bool {
+        self.used.contains(&hw_id)
+    }
+}
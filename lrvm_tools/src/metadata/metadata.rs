@@ -1,5 +1,5 @@
 use super::DeviceCategory;
-use crate::bytes::bytes_to_words;
+use crate::bytes::{bytes_to_words, u32_to_bytes_be};
 
 pub struct DeviceMetadata {
     pub hw_id: u64,
@@ -26,6 +26,78 @@ impl DeviceMetadata {
         }
     }
 
+    /// Create throwaway metadata for tests: hardware identifier `0x1`, volatile memory (RAM) category, no model and no additional data.
+    /// The hardware identifier is not unique, so it must not be relied on to tell components apart.
+    #[cfg(any(test, feature = "test-utils"))]
+    pub fn for_test(size: u32) -> Self {
+        Self::new(0x1, size, super::MemoryType::Ram.into(), None, None)
+    }
+
+    /// Decode metadata from their encoded words
+    pub fn decode(words: [u32; 8]) -> Result<Self, ()> {
+        let category = DeviceCategory::decode(((words[3] as u64) << 32) + words[4] as u64)?;
+        let data = ((words[6] as u64) << 32) + words[7] as u64;
+
+        Ok(Self {
+            hw_id: ((words[0] as u64) << 32) + words[1] as u64,
+            size: words[2],
+            category,
+            model: if words[5] == 0 { None } else { Some(words[5]) },
+            data: if data == 0 { None } else { Some(data) },
+        })
+    }
+
+    /// Store an ASCII version tag (up to 8 bytes) in the additional data field.
+    /// Fails if the version is too long or is not ASCII.
+    pub fn with_version(mut self, version: &str) -> Result<Self, &'static str> {
+        if !version.is_ascii() {
+            return Err("Version must be an ASCII string");
+        }
+
+        if version.len() > 8 {
+            return Err("Version cannot be longer than 8 bytes");
+        }
+
+        let mut bytes = [0; 8];
+        bytes[..version.len()].copy_from_slice(version.as_bytes());
+
+        self.data = Some(u64::from_be_bytes(bytes));
+        Ok(self)
+    }
+
+    /// Get the version tag stored in the additional data field, if any (see [`DeviceMetadata::with_version`])
+    pub fn version(&self) -> Option<String> {
+        let bytes = self.data?.to_be_bytes();
+        let len = bytes
+            .iter()
+            .position(|byte| *byte == 0)
+            .unwrap_or(bytes.len());
+
+        if len == 0 || !bytes[len..].iter().all(|byte| *byte == 0) || !bytes[..len].is_ascii() {
+            return None;
+        }
+
+        Some(String::from_utf8_lossy(&bytes[..len]).to_string())
+    }
+
+    /// Store the dimensions of a display (in pixels) in the additional data field.
+    /// The width is packed in the high 16 bits of its lowest word, and the height in its low 16 bits.
+    pub fn with_dimensions(mut self, width: u16, height: u16) -> Self {
+        self.data = Some(((width as u64) << 16) + height as u64);
+        self
+    }
+
+    /// Get the dimensions stored in the additional data field, if any (see [`DeviceMetadata::with_dimensions`])
+    pub fn dimensions(&self) -> Option<(u16, u16)> {
+        let data = self.data?;
+
+        if data > 0xFFFF_FFFF {
+            return None;
+        }
+
+        Some(((data >> 16) as u16, (data & 0xFFFF) as u16))
+    }
+
     pub fn set_size(&mut self, new_size: u32) -> &mut Self {
         self.size = new_size;
         self
@@ -35,9 +107,9 @@ impl DeviceMetadata {
         let mut bytes = [0; 32];
 
         bytes[0..=7].copy_from_slice(&self.hw_id.to_be_bytes());
-        bytes[8..=11].copy_from_slice(&self.size.to_be_bytes());
+        bytes[8..=11].copy_from_slice(&u32_to_bytes_be(self.size));
         bytes[12..=19].copy_from_slice(&self.category.encode().to_be_bytes());
-        bytes[20..=23].copy_from_slice(&self.model.unwrap_or(0).to_be_bytes());
+        bytes[20..=23].copy_from_slice(&u32_to_bytes_be(self.model.unwrap_or(0)));
         bytes[24..=31].copy_from_slice(&self.data.unwrap_or(0).to_be_bytes());
 
         bytes
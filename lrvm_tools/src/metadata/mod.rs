@@ -1,7 +1,9 @@
 mod category;
+mod hw_id;
 mod metadata;
 mod types;
 
 pub use category::DeviceCategory;
+pub use hw_id::{hw_id_from, HwIdAllocator};
 pub use metadata::DeviceMetadata;
 pub use types::*;
@@ -2,24 +2,28 @@ use super::DeviceCategory;
 use std::fmt;
 
 macro_rules! impl_device_type {
-    ($type_name: ident, as $type_enum: ident => { $($dev_name: ident => $dev_code: expr),* }) => {
+    ($type_name: ident, as $type_enum: ident => { $($dev_name: ident => $dev_code: expr),* } $(, unknown => $unknown: ident)?) => {
         #[non_exhaustive]
-        #[derive(Copy, Clone, Debug)]
+        #[derive(Copy, Clone, Debug, PartialEq, Eq)]
         pub enum $type_enum {
-            $($dev_name),*
+            $($dev_name,)*
+            $($unknown(u32),)?
         }
 
         impl $type_enum {
+            #[allow(unreachable_patterns)]
             pub fn decode(code: u32) -> Result<Self, ()> {
                 match code {
-                    $($dev_code => Ok(Self::$dev_name)),*
-                    , _ => Err(())
+                    $($dev_code => Ok(Self::$dev_name),)*
+                    $(code => Ok(Self::$unknown(code)),)?
+                    _ => Err(())
                 }
             }
 
             pub fn code(self) -> u32 {
                 match self {
-                    $(Self::$dev_name => $dev_code),*
+                    $(Self::$dev_name => $dev_code,)*
+                    $(Self::$unknown(code) => code,)?
                 }
             }
 
@@ -40,13 +44,10 @@ macro_rules! impl_device_type {
 
         impl fmt::Display for $type_enum {
             fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-                write!(
-                    f,
-                    "{}",
-                    match self {
-                        $(Self::$dev_name => stringify!($dev_name)),*
-                    }
-                )
+                match self {
+                    $(Self::$dev_name => write!(f, stringify!($dev_name)),)*
+                    $(Self::$unknown(code) => write!(f, "{}(Code={:#010X})", stringify!($unknown), code),)?
+                }
             }
         }
     };
@@ -57,18 +58,21 @@ impl_device_type!(Debug, as DebugType => {
 });
 
 impl_device_type!(Clock, as ClockType => {
-    Realtime => 0x0000_0001
+    Realtime       => 0x0000_0001,
+    HighResolution => 0x0000_0002
 });
 
 impl_device_type!(Display, as DisplayType => {
     Number    => 0x0000_0001,
     Character => 0x0000_0010,
-    Buffered  => 0x0000_0100
+    Buffered  => 0x0000_0100,
+    Color     => 0x0000_1000
 });
 
 impl_device_type!(Keyboard, as KeyboardType => {
     ReadCharSynchronous => 0x0000_0100,
-    ReadLineSynchronous => 0x0000_1000
+    ReadLineSynchronous => 0x0000_1000,
+    Asynchronous        => 0x0001_0000
 });
 
 impl_device_type!(Memory, as MemoryType => {
@@ -79,4 +83,4 @@ impl_device_type!(Storage, as StorageType => {
     Readonly   => 0x0000_0100,
     Flash      => 0x0000_0011,
     Persistent => 0x0000_0021
-});
+}, unknown => Unknown);
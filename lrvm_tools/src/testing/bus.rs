@@ -0,0 +1,269 @@
+use crate::metadata::DeviceCategory;
+use lrvm::board::Bus;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+/// Maximum number of addresses read and written by [`check_bus_component`]
+/// For larger components, the checked addresses are evenly spread over the whole component.
+const MAX_CHECKED_WORDS: u32 = 4096;
+
+/// Expectations about a component, checked by [`check_bus_component`]
+#[derive(Debug, Clone, Copy)]
+pub struct BusExpectations {
+    pub size: Option<u32>,
+    pub category: Option<DeviceCategory>,
+    pub readable: bool,
+    pub writable: bool,
+    pub out_of_range_exceptions: bool,
+    pub stable_reset_state: bool,
+}
+
+impl BusExpectations {
+    /// Create default expectations.
+    /// The component is expected to be readable and writable, to raise exceptions on out-of-range accesses,
+    /// and to always be in the same state after a reset.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the size the component is expected to have, in bytes
+    pub fn with_size(mut self, size: u32) -> Self {
+        self.size = Some(size);
+        self
+    }
+
+    /// Set the category the component is expected to have
+    pub fn with_category(mut self, category: impl Into<DeviceCategory>) -> Self {
+        self.category = Some(category.into());
+        self
+    }
+
+    /// Set if reading the component is expected to succeed (without exception)
+    pub fn with_readable(mut self, readable: bool) -> Self {
+        self.readable = readable;
+        self
+    }
+
+    /// Set if writing the component is expected to succeed (without exception)
+    pub fn with_writable(mut self, writable: bool) -> Self {
+        self.writable = writable;
+        self
+    }
+
+    /// Set if out-of-range accesses are expected to raise an exception.
+    /// The motherboard never performs such accesses, so components are allowed to panic in this case.
+    pub fn with_out_of_range_exceptions(mut self, raise: bool) -> Self {
+        self.out_of_range_exceptions = raise;
+        self
+    }
+
+    /// Set if the component is expected to always be in the same state after a reset.
+    /// Should be disabled for components whose readable values change over time (e.g. clocks).
+    pub fn with_stable_reset_state(mut self, stable: bool) -> Self {
+        self.stable_reset_state = stable;
+        self
+    }
+}
+
+impl Default for BusExpectations {
+    fn default() -> Self {
+        Self {
+            size: None,
+            category: None,
+            readable: true,
+            writable: true,
+            out_of_range_exceptions: true,
+            stable_reset_state: true,
+        }
+    }
+}
+
+/// Exercise a component the way the motherboard would and check it follows the [`Bus`] contract.
+/// The factory is called to create fresh instances of the component.
+///
+/// Note that this function writes to the component, which may trigger side effects (e.g. displaying something).
+/// Returns an error message describing the first contract violation found.
+pub fn check_bus_component(
+    factory: impl Fn() -> Box<dyn Bus>,
+    expectations: BusExpectations,
+) -> Result<(), String> {
+    let mut component = factory();
+    let name = component.name();
+
+    // Fail with a message prefixed by the component's name
+    macro_rules! fail {
+        ($($arg: tt)*) => {
+            Err(format!("Component '{}': {}", name, format!($($arg)*)))
+        };
+    }
+
+    // Check the metadata
+    let metadata = component.metadata();
+
+    if component.metadata() != metadata {
+        return fail!("metadata differ between two calls");
+    }
+
+    let size = metadata[2];
+
+    if size == 0 || size % 4 != 0 {
+        return fail!(
+            "declared size {:#010X} is not a non-zero multiple of 4 bytes",
+            size
+        );
+    }
+
+    if let Some(expected) = expectations.size {
+        if size != expected {
+            return fail!(
+                "declared size is {:#010X}, expected {:#010X}",
+                size,
+                expected
+            );
+        }
+    }
+
+    let category_code = (u64::from(metadata[3]) << 32) + u64::from(metadata[4]);
+
+    let category = match DeviceCategory::decode(category_code) {
+        Ok(category) => category,
+        Err(()) => {
+            return fail!(
+                "declared category {:#018X} cannot be decoded",
+                category_code
+            )
+        }
+    };
+
+    if let Some(expected) = expectations.category {
+        if category != expected {
+            return fail!("declared category is {}, expected {}", category, expected);
+        }
+    }
+
+    // Get the addresses to check
+    let words = size / 4;
+    let step = (words / MAX_CHECKED_WORDS).max(1);
+    let mut addresses: Vec<u32> = (0..words).step_by(step as usize).map(|i| i * 4).collect();
+
+    if addresses.last() != Some(&(size - 4)) {
+        addresses.push(size - 4);
+    }
+
+    // Check reads and writes in range
+    for addr in &addresses {
+        let (word, ex) = match read(&mut component, *addr) {
+            Ok(read) => read,
+            Err(()) => return fail!("read at address {:#010X} panicked", addr),
+        };
+
+        if expectations.readable && ex != 0 {
+            return fail!(
+                "read at address {:#010X} raised exception {:#06X}",
+                addr,
+                ex
+            );
+        }
+
+        let ex = match write(&mut component, *addr, word) {
+            Ok(ex) => ex,
+            Err(()) => return fail!("write at address {:#010X} panicked", addr),
+        };
+
+        match (expectations.writable, ex) {
+            (true, 0) | (false, 1..=0xFFFF) => {}
+            (true, ex) => {
+                return fail!(
+                    "write at address {:#010X} raised exception {:#06X}",
+                    addr,
+                    ex
+                )
+            }
+            (false, _) => {
+                return fail!("write at address {:#010X} did not raise an exception", addr)
+            }
+        }
+    }
+
+    if component.metadata() != metadata {
+        return fail!("metadata changed after reads and writes");
+    }
+
+    // Check out-of-range accesses
+    if expectations.out_of_range_exceptions {
+        for addr in &[size, size.wrapping_add(4), u32::MAX - 3] {
+            if *addr < size {
+                continue;
+            }
+
+            match read(&mut component, *addr) {
+                Ok((_, 0)) => {
+                    return fail!(
+                        "out-of-range read at address {:#010X} did not raise an exception",
+                        addr
+                    )
+                }
+                Ok(_) => {}
+                Err(()) => return fail!("out-of-range read at address {:#010X} panicked", addr),
+            }
+
+            match write(&mut component, *addr, 0) {
+                Ok(0) => {
+                    return fail!(
+                        "out-of-range write at address {:#010X} did not raise an exception",
+                        addr
+                    )
+                }
+                Ok(_) => {}
+                Err(()) => return fail!("out-of-range write at address {:#010X} panicked", addr),
+            }
+        }
+    }
+
+    // Check resets
+    if catch_unwind(AssertUnwindSafe(|| component.reset())).is_err() {
+        return fail!("reset panicked");
+    }
+
+    if component.metadata() != metadata {
+        return fail!("metadata changed after a reset");
+    }
+
+    if expectations.stable_reset_state && expectations.readable {
+        let mut fresh = factory();
+        fresh.reset();
+
+        for addr in &addresses {
+            let expected = read(&mut fresh, *addr).map(|(word, _)| word);
+            let got = read(&mut component, *addr).map(|(word, _)| word);
+
+            if got != expected {
+                return fail!(
+                    "state after a reset differs from a fresh component's one at address {:#010X}",
+                    addr
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Read a component's word, catching panics
+fn read(component: &mut Box<dyn Bus>, addr: u32) -> Result<(u32, u16), ()> {
+    catch_unwind(AssertUnwindSafe(|| {
+        let mut ex = 0;
+        let word = component.read(addr, &mut ex);
+        (word, ex)
+    }))
+    .map_err(|_| ())
+}
+
+/// Write a component's word, catching panics
+fn write(component: &mut Box<dyn Bus>, addr: u32, word: u32) -> Result<u16, ()> {
+    catch_unwind(AssertUnwindSafe(|| {
+        let mut ex = 0;
+        component.write(addr, word, &mut ex);
+        ex
+    }))
+    .map_err(|_| ())
+}
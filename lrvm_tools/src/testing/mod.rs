@@ -0,0 +1,5 @@
+//! Tools to test components outside of a virtual machine.
+
+mod bus;
+
+pub use bus::*;
@@ -46,6 +46,43 @@ fn decoding() {
     );
 }
 
+#[test]
+fn instr_display() {
+    let prog = prog();
+    let lines = prog.to_lasm_lines();
+
+    for (pword, line) in prog.prog_words().zip(&lines) {
+        if let ProgramWord::Instr(instr) = pword {
+            assert_eq!(&format!("{}", instr), line);
+        }
+    }
+}
+
+#[test]
+fn decoding_with_progress() {
+    let big = Program::from_instr(vec![Instr::Halt(); 10_000]);
+
+    let mut reports = vec![];
+    let decoded = Program::decode_with_progress(big.encode(), true, |decoded, total| {
+        reports.push((decoded, total))
+    })
+    .unwrap();
+
+    assert_eq!(decoded, big);
+    assert_eq!(
+        reports,
+        vec![(4096, 10_000), (8192, 10_000), (10_000, 10_000)]
+    );
+
+    let mut last = None;
+    Program::decode_with_progress(encoded(), false, |decoded, total| {
+        last = Some((decoded, total))
+    })
+    .unwrap();
+
+    assert_eq!(last, Some((prog().size(), prog().size())));
+}
+
 #[test]
 fn asm_conversion() {
     let lasm = prog().to_lasm_lines();
@@ -70,3 +107,906 @@ fn asm_conversion() {
         }
     }
 }
+
+#[test]
+fn checked_encoding() {
+    for pword in prog().prog_words() {
+        if let ProgramWord::Instr(instr) = pword {
+            assert_eq!(instr.try_encode(), Ok(instr.encode()));
+        }
+    }
+
+    assert_eq!(
+        Instr::Cpy(Reg::af, 0u16.into()).try_encode(),
+        Err(InstrEncodingError::ReadOnlyRegister {
+            param: 0,
+            reg: Reg::af
+        })
+    );
+
+    assert_eq!(
+        Instr::Div(Reg::a0, Reg::a1.into(), 0b1111_1111u8.into()).try_encode(),
+        Err(InstrEncodingError::InvalidDivMode { mode: 0b1111_1111 })
+    );
+
+    assert_eq!(
+        Instr::If2(cst::ZF.into(), cst::CF.into(), 0u8.into()).try_encode(),
+        Err(InstrEncodingError::InvalidIf2Cond { cond: 0 })
+    );
+}
+
+#[test]
+fn ext_instr_appending() {
+    let ext = ExtInstr::SetReg(Reg::a0, 0x01234567);
+
+    let mut manual = prog();
+    manual.append_all(
+        ext.to_instr()
+            .into_iter()
+            .map(ProgramWord::Instr)
+            .collect::<Vec<_>>(),
+    );
+
+    let mut expanded = prog();
+    expanded.append_ext(ext);
+
+    assert_eq!(expanded, manual);
+    assert_eq!(ext.to_program(), Program::from_instr(ext.to_instr()));
+}
+
+#[test]
+fn ext_instr_prepending() {
+    let ext = ExtInstr::SetReg(Reg::a0, 0x01234567);
+
+    let mut prepended = Program::from_instr(vec![Instr::Halt()]);
+    prepended.prepend_ext(ext);
+
+    let mut expected = ext.to_program();
+    expected.append(Instr::Halt().into());
+    assert_eq!(prepended, expected);
+
+    let mut batch = prog();
+    batch.append_ext_all(&[ext, ExtInstr::SetReg(Reg::a1, 0x10)]);
+
+    let mut manual = prog();
+    manual
+        .append_ext(ext)
+        .append_ext(ExtInstr::SetReg(Reg::a1, 0x10));
+    assert_eq!(batch, manual);
+}
+
+#[test]
+fn prepend_all_order() {
+    let mut prog = Program::from_instr(vec![Instr::Halt(), Instr::Halt(), Instr::Halt()]);
+    prog.prepend_all(&[
+        Instr::Cpy(Reg::a1, 2u16.into()).into(),
+        Instr::Cpy(Reg::a0, 1u16.into()).into(),
+    ]);
+
+    assert_eq!(
+        prog,
+        Program::from_instr(vec![
+            Instr::Cpy(Reg::a1, 2u16.into()),
+            Instr::Cpy(Reg::a0, 1u16.into()),
+            Instr::Halt(),
+            Instr::Halt(),
+            Instr::Halt(),
+        ])
+    );
+}
+
+#[test]
+fn dot_graph() {
+    let prog = Program::from_instr(vec![
+        Instr::Cpy(Reg::a0, 3u16.into()),
+        Instr::Sub(Reg::a0, 1u16.into()),
+        Instr::Cmp(Reg::a0, 0u16.into()),
+        Instr::IfN(cst::ZF.into()),
+        Instr::Jpr((-12i16).into()),
+        Instr::Halt(),
+    ]);
+
+    assert_eq!(
+        prog.to_dot_graph(),
+        [
+            "digraph program {",
+            "    node [shape=box, fontname=\"monospace\"];",
+            "    b0 [label=\"0x00000000: cpy\"];",
+            "    b1 [label=\"0x00000004: dec\"];",
+            "    b2 [label=\"0x00000010: jpr\"];",
+            "    b3 [label=\"0x00000014: halt\"];",
+            "    b0 -> b1;",
+            "    b1 -> b2 [label=\"true\"];",
+            "    b1 -> b3 [label=\"false\"];",
+            "    b2 -> b1;",
+            "}",
+        ]
+        .join("\n")
+    );
+}
+
+#[test]
+fn ext_instr_zeroing() {
+    assert_eq!(
+        ExtInstr::ZeroReg(Reg::a3).to_instr(),
+        vec![Instr::Cpy(Reg::a3, 0u16.into())]
+    );
+
+    assert!(ExtInstr::ZeroMem(0x1000, 0).to_instr().is_empty());
+
+    let setup = ExtInstr::SetReg(Reg::rr0, 0x1000).to_instr();
+
+    let small = ExtInstr::ZeroMem(0x1000, 3).to_instr();
+    assert_eq!(small.len(), setup.len() + 3);
+    assert_eq!(small[..setup.len()], setup[..]);
+    assert_eq!(
+        small[setup.len() + 2],
+        Instr::Wsa(Reg::rr0.into(), 8u8.into(), 0u8.into())
+    );
+
+    for count in &[64, 65, 1000, 100_000] {
+        assert!(ExtInstr::ZeroMem(0x1000, *count).to_instr().len() <= setup.len() + count);
+    }
+}
+
+#[test]
+fn cached_decoding() {
+    let mut decoder = CachedDecoder::new();
+    let halt = Instr::Halt().encode_word();
+
+    assert_eq!(decoder.cached(), 0);
+    assert_eq!(decoder.decode_word(halt), Ok(Instr::Halt()));
+    assert_eq!(decoder.decode_word(halt), Ok(Instr::Halt()));
+    assert_eq!(decoder.cached(), 1);
+
+    assert_eq!(
+        decoder.decode_word(0xFFFF_FFFF),
+        Instr::decode([0xFF, 0xFF, 0xFF, 0xFF])
+    );
+    assert_eq!(decoder.cached(), 2);
+
+    for pword in prog().prog_words() {
+        assert_eq!(
+            decoder.decode(pword.encode()),
+            Instr::decode(pword.encode())
+        );
+    }
+
+    assert_eq!(decoder.cached(), 2 + prog().size());
+
+    decoder.clear();
+    assert_eq!(decoder.cached(), 0);
+}
+
+#[test]
+fn relocation() {
+    let mut prog = Program::from_instr(vec![
+        Instr::Call(8u16.into()),
+        Instr::Cpy(Reg::pc, 0x1000u16.into()),
+        Instr::Lsm(4u16.into()),
+        Instr::Jpr((-8i16).into()),
+    ]);
+
+    prog.relocate(0x100);
+
+    assert_eq!(
+        prog,
+        Program::from_instr(vec![
+            Instr::Call(0x108u16.into()),
+            Instr::Cpy(Reg::pc, 0x1000u16.into()),
+            Instr::Lsm(0x104u16.into()),
+            Instr::Jpr((-8i16).into()),
+        ])
+    );
+
+    let mut prog = Program::from_instr(vec![Instr::Halt(), Instr::Lsm(8u16.into()), Instr::Halt()]);
+
+    prog.with_entry_jump(1);
+
+    assert_eq!(
+        prog,
+        Program::from_instr(vec![
+            Instr::Jpr(8u16.into()),
+            Instr::Halt(),
+            Instr::Lsm(12u16.into()),
+            Instr::Halt(),
+        ])
+    );
+}
+
+#[test]
+fn entry_jump_reach() {
+    // The farthest entry point is 0x7FFC bytes after the jump
+    let mut prog = Program::from_instr(vec![Instr::Halt(); 0x2000]);
+    prog.with_entry_jump(0x1FFE);
+    assert_eq!(prog.0[0], ProgramWord::Instr(Instr::Jpr(0x7FFCi16.into())));
+}
+
+#[test]
+#[should_panic]
+fn entry_jump_too_far() {
+    Program::from_instr(vec![Instr::Halt(); 0x2000]).with_entry_jump(0x1FFF);
+}
+
+#[test]
+fn checksums() {
+    let words = prog().encode_words();
+
+    assert_eq!(prog().xor_words(), words.iter().fold(0, |acc, w| acc ^ w));
+    assert_eq!(
+        prog().sum_words(),
+        words.iter().fold(0u32, |acc, w| acc.wrapping_add(*w))
+    );
+
+    assert_eq!(Program::new().xor_words(), 0);
+    assert_eq!(Program::new().sum_words(), 0);
+
+    let twice = Program::from_instr(vec![Instr::Halt(), Instr::Halt()]);
+    assert_eq!(twice.xor_words(), 0);
+    assert_eq!(
+        twice.sum_words(),
+        Instr::Halt().encode_word().wrapping_mul(2)
+    );
+}
+
+#[test]
+fn splitting() {
+    let (code, data) = prog().split_at(2);
+
+    assert_eq!(code.size(), 2);
+    assert_eq!(data.size(), prog().size() - 2);
+
+    let mut joined = code;
+    joined.append_all(&data.0);
+    assert_eq!(joined, prog());
+
+    let (empty, full) = prog().split_at(0);
+    assert_eq!(empty, Program::new());
+    assert_eq!(full, prog());
+}
+
+#[test]
+fn special_writes() {
+    assert!(Reg::a0.is_general());
+    assert!(Reg::rr7.is_general());
+    assert!(!Reg::a0.is_special());
+    assert!(Reg::pc.is_special());
+    assert!(Reg::ssp.is_special());
+    assert!(!Reg::avr.is_general());
+
+    let prog = Program::from_instr(vec![
+        Instr::Cpy(Reg::a0, 0x10u16.into()),
+        Instr::Cpy(Reg::pc, 0x20u16.into()),
+        Instr::Push(Reg::a0.into()),
+        Instr::Pop(Reg::usp),
+    ]);
+
+    assert_eq!(
+        prog.check_special_writes(),
+        vec![(1, Reg::pc), (3, Reg::usp)]
+    );
+}
+
+#[test]
+fn container() {
+    let prog = prog();
+    let meta = ContainerMeta { entry_point: 0x10 };
+
+    let container = prog.to_container(meta).unwrap();
+    assert_eq!(container.len(), CONTAINER_HEADER_SIZE + prog.size() * 4);
+
+    let (decoded, decoded_meta) = Program::from_container(&container).unwrap();
+    assert_eq!(decoded, prog);
+    assert_eq!(decoded_meta, meta);
+
+    let mut bad_magic = container.clone();
+    bad_magic[0] = b'X';
+    assert_eq!(
+        Program::from_container(&bad_magic),
+        Err(ContainerError::BadMagic)
+    );
+
+    assert_eq!(
+        Program::from_container(&container[..container.len() - 4]),
+        Err(ContainerError::SizeMismatch {
+            expected: prog.size() as u32 * 4,
+            found: prog.size() * 4 - 4
+        })
+    );
+
+    assert_eq!(
+        Program::from_container(&container[..8]),
+        Err(ContainerError::TooShort)
+    );
+}
+
+#[test]
+fn reversing_and_rotating() {
+    let words = |prog: &Program| prog.prog_words().copied().collect::<Vec<_>>();
+
+    let original = words(&prog());
+
+    let mut reversed = prog();
+    reversed.reverse();
+    assert_eq!(
+        words(&reversed),
+        original.iter().rev().copied().collect::<Vec<_>>()
+    );
+
+    let mut rotated = prog();
+    rotated.rotate_left(2);
+    assert_eq!(words(&rotated)[..original.len() - 2], original[2..]);
+    assert_eq!(words(&rotated)[original.len() - 2..], original[..2]);
+
+    let mut wrapped = prog();
+    wrapped.rotate_left(original.len() + 2);
+    assert_eq!(wrapped, rotated);
+
+    assert_eq!(Program::new().rotate_left(3), &Program::new());
+}
+
+#[test]
+fn ext_instr_copy_aliases() {
+    assert_eq!(
+        ExtInstr::CopyRegToMem(Reg::a3, 0x1234_5678).to_instr(),
+        ExtInstr::WriteAddr(0x1234_5678, Reg::a3).to_instr()
+    );
+    assert_eq!(
+        ExtInstr::CopyMemToReg(Reg::a3, 0x1234_5678).to_instr(),
+        ExtInstr::ReadAddrTo(Reg::a3, 0x1234_5678).to_instr()
+    );
+}
+
+#[test]
+fn ext_instr_stack() {
+    let push = ExtInstr::PushReg {
+        reg: Reg::a3,
+        sp: Reg::ac2,
+    };
+
+    let pop = ExtInstr::PopReg {
+        reg: Reg::a4,
+        sp: Reg::ac2,
+    };
+
+    assert_eq!(
+        push.to_instr(),
+        vec![
+            Instr::Sub(Reg::ac2, 4u16.into()),
+            Instr::Wsa(Reg::ac2.into(), 0u8.into(), Reg::a3.into())
+        ]
+    );
+
+    assert_eq!(
+        pop.to_instr(),
+        vec![
+            Instr::Lsa(Reg::a4, Reg::ac2.into(), 0u8.into()),
+            Instr::Add(Reg::ac2, 4u16.into())
+        ]
+    );
+
+    // No other register than the value's and the stack pointer's is used
+    for (ext, reg) in &[(push, Reg::a3), (pop, Reg::a4)] {
+        for instr in ext.to_instr() {
+            for operand in instr.operands() {
+                if let InstrOperand::Reg(used) = operand {
+                    assert!(used == *reg || used == Reg::ac2);
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn program_byte_addressing() {
+    let mut prog = prog();
+
+    assert_eq!(prog.instruction_at_byte_addr(0), prog.0.first());
+    assert_eq!(prog.instruction_at_byte_addr(8), Some(&prog.0[2]));
+    assert_eq!(prog.instruction_at_byte_addr(6), None);
+    assert_eq!(prog.instruction_at_byte_addr(prog.size() as u32 * 4), None);
+    assert_eq!(prog.instruction_at_byte_addr(u32::MAX - 3), None);
+
+    *prog.instruction_at_byte_addr_mut(4).unwrap() = Instr::Halt().into();
+    assert_eq!(prog.0[1], Instr::Halt().into());
+    assert!(prog.instruction_at_byte_addr_mut(5).is_none());
+}
+
+#[test]
+fn program_checksum() {
+    let mut prog = prog();
+    prog.append_checksum();
+    assert_eq!(prog.size(), self::prog().size() + 1);
+
+    let bytes = prog.encode();
+    assert_eq!(Program::decode_verified(&bytes), Ok(self::prog()));
+
+    let mut corrupted = bytes.clone();
+    corrupted[5] ^= 0x01;
+
+    match Program::decode_verified(&corrupted) {
+        Err(ChecksumError::Mismatch { expected, actual }) => {
+            assert_eq!(expected, self::prog().sum_words());
+            assert_ne!(actual, expected);
+        }
+        result => panic!("Expected a checksum mismatch, got: {:?}", result),
+    }
+
+    assert_eq!(
+        Program::decode_verified([0x00, 0x00]),
+        Err(ChecksumError::Missing)
+    );
+}
+
+#[test]
+fn program_visitor() {
+    #[derive(Default)]
+    struct Counter {
+        instr: usize,
+        halts: Vec<usize>,
+        raw: Vec<usize>,
+    }
+
+    impl ProgramVisitor for Counter {
+        fn visit_instr(&mut self, index: usize, instr: &Instr) {
+            self.instr += 1;
+
+            if *instr == Instr::Halt() {
+                self.halts.push(index);
+            }
+        }
+
+        fn visit_raw(&mut self, index: usize, _raw: &[u8; 4]) {
+            self.raw.push(index);
+        }
+    }
+
+    let mut prog = prog();
+    prog.append(ProgramWord::Raw([0xFF; 4]));
+    prog.append(Instr::Halt().into());
+
+    let mut counter = Counter::default();
+    prog.visit(&mut counter);
+
+    assert_eq!(counter.instr, prog.size() - 1);
+    assert_eq!(counter.halts.last(), Some(&(prog.size() - 1)));
+    assert_eq!(counter.raw, vec![prog.size() - 2]);
+}
+
+#[test]
+fn register_operands_encoding() {
+    let instr = Instr::Cpy(Reg::a1, Reg::a2.into());
+
+    assert_eq!(instr.encode()[2..], [Reg::a2.code(), 0x00]);
+    assert_eq!(Instr::decode(instr.encode()), Ok(instr));
+}
+
+#[test]
+fn raw_inspection() {
+    let prog = Program::from(vec![
+        Instr::Halt().into(),
+        ProgramWord::Raw([0x01, 0x02, 0x03, 0x04]),
+        ProgramWord::Raw([0x05, 0x06, 0x07, 0x08]),
+        Instr::Halt().into(),
+        Instr::Halt().into(),
+        ProgramWord::Raw([0x09, 0x0A, 0x0B, 0x0C]),
+    ]);
+
+    assert_eq!(prog.count_raw(), 3);
+    assert_eq!(prog.raw_ranges(), vec![1..3, 5..6]);
+    assert!(Program::from_instr(vec![Instr::Halt()])
+        .raw_ranges()
+        .is_empty());
+}
+
+#[test]
+fn lint_unreachable_after_jump() {
+    let prog = Program::from_instr(vec![
+        Instr::Cpy(Reg::a0, 1u16.into()),
+        Instr::Jpr(8i16.into()),
+        Instr::Add(Reg::a0, 1u16.into()),
+        Instr::Halt(),
+    ]);
+
+    assert_eq!(
+        lint(&prog),
+        vec![Lint {
+            index: 2,
+            kind: LintKind::UnreachableAfterJump
+        }]
+    );
+
+    let prog = Program::from_instr(vec![Instr::Jpr(0i16.into()), Instr::Halt()]);
+
+    assert_eq!(
+        lint(&prog),
+        vec![Lint {
+            index: 1,
+            kind: LintKind::UnreachableAfterJump
+        }]
+    );
+
+    let prog = Program::from_instr(vec![
+        Instr::Halt(),
+        Instr::Cpy(Reg::a0, 1u16.into()),
+        Instr::Halt(),
+    ]);
+
+    assert_eq!(
+        lint(&prog),
+        vec![Lint {
+            index: 2,
+            kind: LintKind::UnreachableHalt
+        }]
+    );
+
+    // Jumps to unknown locations disable reachability checks
+    let prog = Program::from_instr(vec![Instr::Jpr(Reg::a0.into()), Instr::Halt()]);
+    assert_eq!(lint(&prog), vec![]);
+}
+
+#[test]
+fn lint_dead_store() {
+    let prog = Program::from_instr(vec![
+        Instr::Cpy(Reg::a0, 1u16.into()),
+        Instr::Cpy(Reg::a0, 2u16.into()),
+        Instr::Add(Reg::a0, 1u16.into()),
+        Instr::Cpy(Reg::a1, Reg::a0.into()),
+        Instr::Halt(),
+    ]);
+
+    assert_eq!(
+        lint(&prog),
+        vec![Lint {
+            index: 0,
+            kind: LintKind::DeadStore(Reg::a0)
+        }]
+    );
+
+    assert_eq!(
+        lint(&prog)[0].to_string(),
+        "Word 0 (offset 0x00000000): value written to register 'a0' is overwritten before being read"
+    );
+
+    // Conditional instructions start a new basic block
+    let prog = Program::from_instr(vec![
+        Instr::Cpy(Reg::a0, 1u16.into()),
+        Instr::If(Reg::a1.into()),
+        Instr::Cpy(Reg::a0, 2u16.into()),
+        Instr::Halt(),
+    ]);
+
+    assert_eq!(lint(&prog), vec![]);
+}
+
+#[test]
+fn program_size_check() {
+    let prog = Program::from_instr(vec![Instr::Cpy(Reg::a0, 1u16.into()), Instr::Halt()]);
+
+    assert_eq!(prog.check_size(8), Ok(()));
+
+    let err = prog.check_size(6).unwrap_err();
+
+    assert_eq!(
+        err,
+        ProgramTooLarge {
+            size: 8,
+            max_size: 6
+        }
+    );
+    assert_eq!(err.overflow(), 2);
+    assert_eq!(
+        err.to_string(),
+        "Program is 8 bytes long, which exceeds the maximum size of 6 bytes by 2 bytes"
+    );
+}
+
+#[test]
+fn isa_table_entries() {
+    let table = isa_table();
+
+    assert_eq!(table.len(), 31);
+    assert!(table
+        .iter()
+        .enumerate()
+        .all(|(i, entry)| usize::from(entry.opcode) == i + 1));
+
+    let cpy = &table[0];
+    assert_eq!(cpy.mnemonic, "cpy");
+    assert_eq!(
+        cpy.operands,
+        vec![
+            IsaOperand {
+                name: "dest",
+                ty: OperandType::Reg,
+                bits: 16..24,
+                reg_flag_bit: 26
+            },
+            IsaOperand {
+                name: "value",
+                ty: OperandType::RegOrLit2,
+                bits: 0..16,
+                reg_flag_bit: 25
+            }
+        ]
+    );
+
+    // Operands must fit in the instruction's word
+    for entry in &table {
+        assert!(entry.operands.len() <= 3);
+        assert!(entry.operands.iter().all(|operand| operand.bits.start < 24));
+    }
+
+    let markdown = isa_table_markdown();
+
+    assert!(markdown.starts_with("# Instruction set\n"));
+    assert!(markdown.contains("| `0x1E` | `halt` | - | Halt the processor |"));
+    assert!(markdown.contains(
+        "| `0x1A` | `pop` | `dest` (reg, bits 23-16) | Pop a value from the stack into a register |"
+    ));
+}
+
+#[test]
+fn infinite_loops() {
+    // Jump to self
+    let looping = Program::from_instr(vec![
+        Instr::Cpy(Reg::a0, 1u16.into()),
+        Instr::Jpr(0i16.into()),
+    ]);
+    assert!(looping.contains_infinite_loop());
+
+    // Unconditional backward jump
+    let looping = Program::from_instr(vec![
+        Instr::Cpy(Reg::a0, 1u16.into()),
+        Instr::Add(Reg::a0, 1u16.into()),
+        Instr::Jpr((-4i16).into()),
+        Instr::Halt(),
+    ]);
+    assert!(looping.contains_infinite_loop());
+
+    // Absolute jump to the program's start
+    let looping = Program::from_instr(vec![
+        Instr::Add(Reg::a0, 1u16.into()),
+        Instr::Cpy(Reg::pc, 0u16.into()),
+    ]);
+    assert!(looping.contains_infinite_loop());
+
+    // Loop with a conditional exit
+    let looping = Program::from_instr(vec![
+        Instr::Add(Reg::a0, 1u16.into()),
+        Instr::Cmp(Reg::a0, 10u16.into()),
+        Instr::If(cst::ZF.into()),
+        Instr::Halt(),
+        Instr::Jpr((-16i16).into()),
+    ]);
+    assert!(!looping.contains_infinite_loop());
+
+    // Unreachable loop
+    let looping = Program::from_instr(vec![Instr::Halt(), Instr::Jpr(0i16.into())]);
+    assert!(!looping.contains_infinite_loop());
+
+    // Jump to an unknown location
+    let looping = Program::from_instr(vec![Instr::Jpr(Reg::a0.into())]);
+    assert!(!looping.contains_infinite_loop());
+
+    assert!(!prog().contains_infinite_loop());
+    assert!(!Program::new().contains_infinite_loop());
+}
+
+#[test]
+fn nop() {
+    assert!(Instr::nop().is_nop());
+    assert!(!Instr::Cpy(Reg::a1, Reg::a1.into()).is_nop());
+    assert!(!Instr::Add(Reg::a0, 0u16.into()).is_nop());
+
+    assert_eq!(Instr::nop().encode(), [0x0E, 0x00, 0x00, 0x00]);
+    assert_eq!(Instr::nop().to_lasm(), "nop");
+
+    let mut prog = Program::from_instr(vec![
+        Instr::nop(),
+        Instr::Cpy(Reg::a0, 1u16.into()),
+        Instr::nop(),
+        Instr::Halt(),
+    ]);
+
+    prog.strip_nops();
+
+    assert_eq!(
+        prog,
+        Program::from_instr(vec![Instr::Cpy(Reg::a0, 1u16.into()), Instr::Halt()])
+    );
+}
+
+#[test]
+fn disassemble_golden() {
+    let mut prog = ExtInstr::SetReg(Reg::a0, 0x1234_5678).to_program();
+    prog.append_ext(ExtInstr::WriteAddrLit(0x2000, 0xAA));
+    prog.append_all(&[
+        Instr::Call(0x1044_u16.into()).into(),
+        Instr::Cmp(Reg::a0, 0_u16.into()).into(),
+        Instr::If(cst::ZF.into()).into(),
+        Instr::Jpr((-8_i16).into()).into(),
+        Instr::Halt().into(),
+        ProgramWord::Raw([0x00, 0x00, 0x00, 0x00]),
+        Instr::Add(Reg::a1, 2_u16.into()).into(),
+        Instr::Add(Reg::a0, Reg::a1.into()).into(),
+        Instr::Pop(Reg::pc).into(),
+    ]);
+
+    let bytes = prog.encode();
+
+    let listing = disassemble_to_string(
+        &bytes,
+        DisasmOptions::new()
+            .base_addr(0x1000)
+            .raw_data(RawData::Infer)
+            .fold_ext(true),
+    )
+    .unwrap();
+
+    assert_eq!(listing, include_str!("golden/disasm.lasm").trim_end());
+
+    let plain = disassemble_to_string(&bytes, DisasmOptions::new().labels(false)).unwrap();
+    assert_eq!(plain.lines().count(), prog.size());
+    assert!(plain.contains("#d32 0x00_00_00_00"));
+    assert!(plain.contains("call 0x1044"));
+
+    let err = disassemble_to_string(&bytes, DisasmOptions::new().raw_data(RawData::Forbid));
+    assert_eq!(err.unwrap_err().index, 15);
+}
+
+#[test]
+fn instr_operands() {
+    assert_eq!(Instr::Halt().operand_count(), 0);
+    assert_eq!(Instr::Halt().operands(), vec![]);
+
+    let instr = Instr::Cpy(Reg::a0, 0x1234_u16.into());
+    assert_eq!(instr.operand_count(), 2);
+    assert_eq!(
+        instr.operands(),
+        vec![InstrOperand::Reg(Reg::a0), InstrOperand::Imm16(0x1234)]
+    );
+
+    let instr = Instr::Div(Reg::a1, Reg::a2.into(), cst::DIV_ZRO_MIN.into());
+    assert_eq!(instr.operand_count(), 3);
+    assert_eq!(
+        instr.operands(),
+        vec![
+            InstrOperand::Reg(Reg::a1),
+            InstrOperand::Reg(Reg::a2),
+            InstrOperand::Imm8(cst::DIV_ZRO_MIN)
+        ]
+    );
+
+    for entry in isa_table() {
+        let word = u32::from(entry.opcode) << 27;
+        let instr = Instr::decode(word.to_be_bytes()).unwrap();
+        assert_eq!(instr.operand_count(), entry.operands.len());
+        assert_eq!(instr.operands().len(), entry.operands.len());
+    }
+}
+
+#[test]
+fn concat_rebased() {
+    let prelude = Program::from_instr(vec![Instr::Add(Reg::a0, 1_u16.into()), Instr::Pop(Reg::pc)]);
+
+    let main = Program::from_instr(vec![
+        Instr::Call(8_u16.into()),
+        Instr::Jpr((-4_i16).into()),
+        Instr::Cpy(Reg::pc, 0_u16.into()),
+    ]);
+
+    let merged = Program::concat_rebased(&prelude, &main).unwrap();
+
+    assert_eq!(
+        merged,
+        Program::from_instr(vec![
+            Instr::Add(Reg::a0, 1_u16.into()),
+            Instr::Pop(Reg::pc),
+            Instr::Call(0x10_u16.into()),
+            Instr::Jpr((-4_i16).into()),
+            Instr::Cpy(Reg::pc, 0x08_u16.into()),
+        ])
+    );
+
+    let outside = Program::from_instr(vec![Instr::Lsm(0x100_u16.into())]);
+    assert_eq!(
+        Program::concat_rebased(&prelude, &outside),
+        Err(RebaseError::TargetOutsideProgram {
+            index: 0,
+            addr: 0x100
+        })
+    );
+
+    let register = Program::from_instr(vec![Instr::Halt(), Instr::Call(Reg::ac0.into())]);
+    assert_eq!(
+        Program::concat_rebased(&prelude, &register),
+        Err(RebaseError::RegisterTarget {
+            index: 1,
+            reg: Reg::ac0
+        })
+    );
+}
+
+/// Multiply two 16.16 fixed-point registers (the result is truncated)
+struct FixedMul(Reg, Reg);
+
+impl PseudoInstr for FixedMul {
+    fn expand(&self) -> Vec<Instr> {
+        vec![
+            Instr::Shr(self.0, 8_u8.into()),
+            Instr::Cpy(Reg::rr0, self.1.into()),
+            Instr::Shr(Reg::rr0, 8_u8.into()),
+            Instr::Mul(self.0, Reg::rr0.into()),
+        ]
+    }
+
+    fn len_words(&self) -> usize {
+        4
+    }
+}
+
+#[test]
+fn pseudo_instr() {
+    let pseudo: Vec<Box<dyn PseudoInstr>> = vec![
+        Box::new(ExtInstr::SetReg(Reg::a0, 0x0002_0000)),
+        Box::new(FixedMul(Reg::a0, Reg::a1)),
+    ];
+
+    // Labels can be computed before expanding the program
+    let halt_addr = pseudo.iter().map(|p| p.len_words()).sum::<usize>() * 4;
+    assert_eq!(halt_addr, 0x1C);
+
+    let mut prog = Program::new();
+    prog.append_all_pseudo(&pseudo);
+    prog.append(Instr::Halt().into());
+
+    assert_eq!(prog.size(), 8);
+    assert_eq!(prog.0[halt_addr / 4], ProgramWord::Instr(Instr::Halt()));
+    assert_eq!(
+        prog.split_at(3).0,
+        ExtInstr::SetReg(Reg::a0, 0x0002_0000).to_program()
+    );
+}
+
+#[test]
+fn disassemble_from_file() {
+    let mut prog = Program::from_instr(vec![Instr::Cpy(Reg::a0, 0x10_u16.into()), Instr::Halt()]);
+    prog.append(ProgramWord::Raw([0x00, 0x00, 0x00, 0x00]));
+
+    let path = std::env::temp_dir().join(format!("lrvm_disasm_{}.bin", std::process::id()));
+    std::fs::write(&path, prog.encode()).unwrap();
+
+    let lasm = disassemble_file(&path, false);
+    let annotated = disassemble_file(&path, true);
+
+    std::fs::write(&path, [0x00, 0x00]).unwrap();
+    let unaligned = disassemble_file(&path, false);
+
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(lasm.unwrap(), "cpy a0, 0x10\nhalt\n#d32 0x00_00_00_00");
+    assert!(annotated.unwrap().contains("0x00000004: halt"));
+    assert_eq!(
+        unaligned.unwrap_err().kind(),
+        std::io::ErrorKind::InvalidData
+    );
+    assert!(disassemble_file(&path, false).is_err());
+}
+
+#[test]
+fn map_instrs() {
+    let mut prog = Program::from_instr(vec![Instr::Cpy(Reg::a0, 0x10_u16.into()), Instr::Halt()]);
+    prog.append(ProgramWord::Raw([0x01, 0x02, 0x03, 0x04]));
+    prog.append(ProgramWord::Instr(Instr::Add(Reg::a1, Reg::a0.into())));
+
+    assert_eq!(
+        prog.map_instrs(|_| Instr::nop()),
+        Program::from(vec![
+            ProgramWord::Instr(Instr::nop()),
+            ProgramWord::Instr(Instr::nop()),
+            ProgramWord::Raw([0x01, 0x02, 0x03, 0x04]),
+            ProgramWord::Instr(Instr::nop()),
+        ])
+    );
+}
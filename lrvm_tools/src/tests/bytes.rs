@@ -0,0 +1,82 @@
+use crate::bytes::*;
+
+#[test]
+fn read_word() {
+    let buf = [0x01, 0x23, 0x45, 0x67, 0x89, 0xAB];
+
+    assert_eq!(read_word_at(&buf, 0), Some(0x0123_4567));
+    assert_eq!(read_word_at(&buf, 2), Some(0x4567_89AB));
+    assert_eq!(read_word_at(&buf, 3), None);
+    assert_eq!(read_word_at(&buf, 6), None);
+    assert_eq!(read_word_at(&buf, usize::MAX), None);
+}
+
+#[test]
+fn write_word() {
+    let mut buf = [0; 6];
+
+    assert_eq!(write_word_at(&mut buf, 1, 0xDEAD_BEEF), Ok(()));
+    assert_eq!(buf, [0x00, 0xDE, 0xAD, 0xBE, 0xEF, 0x00]);
+
+    assert_eq!(write_word_at(&mut buf, 3, 0x0123_4567), Err(()));
+    assert_eq!(write_word_at(&mut buf, usize::MAX, 0x0123_4567), Err(()));
+    assert_eq!(buf, [0x00, 0xDE, 0xAD, 0xBE, 0xEF, 0x00]);
+
+    assert_eq!(read_word_at(&buf, 1), Some(0xDEAD_BEEF));
+}
+
+#[test]
+fn pack_unpack() {
+    assert_eq!(pack_u16_be(0x0123, 0x4567), 0x0123_4567);
+    assert_eq!(unpack_u16_be(0x89AB_CDEF), (0x89AB, 0xCDEF));
+
+    assert_eq!(pack_u8x4(0x01, 0x23, 0x45, 0x67), 0x0123_4567);
+    assert_eq!(unpack_u8x4(0x89AB_CDEF), (0x89, 0xAB, 0xCD, 0xEF));
+
+    let (high, low) = unpack_u16_be(0xDEAD_BEEF);
+    assert_eq!(pack_u16_be(high, low), 0xDEAD_BEEF);
+
+    let (b0, b1, b2, b3) = unpack_u8x4(0xDEAD_BEEF);
+    assert_eq!(pack_u8x4(b0, b1, b2, b3), 0xDEAD_BEEF);
+}
+
+#[test]
+fn u32_bytes_be() {
+    assert_eq!(u32_to_bytes_be(0x0123_4567), [0x01, 0x23, 0x45, 0x67]);
+    assert_eq!(bytes_be_to_u32([0x89, 0xAB, 0xCD, 0xEF]), 0x89AB_CDEF);
+    assert_eq!(bytes_be_to_u32(u32_to_bytes_be(0xDEAD_BEEF)), 0xDEAD_BEEF);
+
+    assert_eq!(
+        bytes_to_words([0x01, 0x23, 0x45, 0x67, 0x89]),
+        vec![0x0123_4567, 0x8900_0000]
+    );
+}
+
+#[test]
+fn bytes_to_words_lengths() {
+    assert_eq!(bytes_to_words([]), vec![]);
+    assert_eq!(bytes_to_words([0x01, 0x23, 0x45, 0x67]), vec![0x0123_4567]);
+    assert_eq!(
+        bytes_to_words([0x01, 0x23, 0x45, 0x67, 0x89]),
+        vec![0x0123_4567, 0x8900_0000]
+    );
+    assert_eq!(
+        bytes_to_words([0x01, 0x23, 0x45, 0x67, 0x89, 0xAB, 0xCD, 0xEF]),
+        vec![0x0123_4567, 0x89AB_CDEF]
+    );
+}
+
+#[test]
+fn utf8_words() {
+    assert_eq!(encode_utf8_words("Hi!"), vec![0x4869_2100]);
+    assert_eq!(encode_utf8_words("Hey!"), vec![0x4865_7921, 0x0000_0000]);
+    assert_eq!(encode_utf8_words(""), vec![0x0000_0000]);
+
+    for s in &["", "Hi!", "Hey!", "Hello, world!", "Ünïcödé ✓"] {
+        assert_eq!(decode_utf8_words(&encode_utf8_words(s)).unwrap(), *s);
+    }
+
+    assert_eq!(decode_utf8_words(&[0x4869_0021]).unwrap(), "Hi");
+    assert_eq!(decode_utf8_words(&[0x4865_7921]).unwrap(), "Hey!");
+    assert!(decode_utf8_words(&[0x48FF_0000]).is_err());
+}
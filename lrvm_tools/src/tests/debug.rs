@@ -0,0 +1,56 @@
+use crate::asm::{Instr, Reg};
+use crate::debug::{describe_map, disassemble_at};
+use crate::metadata::{MemoryType, StorageType};
+
+#[test]
+fn map_contiguous() {
+    let map = describe_map(&[
+        ("RAM", MemoryType::Ram.into(), 0x1000, 0x1000),
+        ("BootROM", StorageType::Readonly.into(), 0x0000, 0x1000),
+    ]);
+
+    assert_eq!(
+        map,
+        "0x00000000 -> 0x00000FFF | 0x00001000 | BootROM (Storage:Readonly)\n\
+         0x00001000 -> 0x00001FFF | 0x00001000 | RAM (Memory:Ram)\n\
+         => No overlap or gap detected"
+    );
+}
+
+#[test]
+fn map_overlapping() {
+    let map = describe_map(&[
+        ("BootROM", StorageType::Readonly.into(), 0x0000, 0x1000),
+        ("RAM", MemoryType::Ram.into(), 0x0800, 0x1000),
+    ]);
+
+    assert!(map.contains("RAM (Memory:Ram) ✗ overlaps with 'BootROM'"));
+    assert!(map.ends_with("=> 1 overlap and 0 gaps detected"));
+}
+
+#[test]
+fn map_gapped() {
+    let map = describe_map(&[
+        ("BootROM", StorageType::Readonly.into(), 0x0000, 0x1000),
+        ("RAM", MemoryType::Ram.into(), 0x2000, 0x1000),
+    ]);
+
+    assert!(map.contains("0x00001000 -> 0x00001FFF | 0x00001000 | <gap>"));
+    assert!(map.ends_with("=> 0 overlaps and 1 gap detected"));
+}
+
+#[test]
+fn disassembling_at() {
+    let words = [
+        Instr::Cpy(Reg::a0, 0x1234u16.into()).encode_word(),
+        0xFFFF_FFFF,
+        Instr::Halt().encode_word(),
+    ];
+
+    assert_eq!(
+        disassemble_at(&words, 0x1000, 2),
+        "0x00001000: cpy a0, 0x1234\n0x00001004: #d32 0xFF_FF_FF_FF"
+    );
+    assert_eq!(disassemble_at(&words, 0x1000, 10).lines().count(), 3);
+    assert_eq!(disassemble_at(&words, 0x1000, 0), "");
+}
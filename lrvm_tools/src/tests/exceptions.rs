@@ -0,0 +1,36 @@
+use crate::exceptions::{AuxHwException, ExceptionCategory, ExceptionSink, NativeException};
+
+#[test]
+fn exception_categories() {
+    let div_by_zero = NativeException::DivisionOrModByZero;
+    assert_eq!(div_by_zero.category(), ExceptionCategory::Arithmetic);
+    assert!(!div_by_zero.is_fatal());
+
+    let illegal = NativeException::UnknownOpCode(0x00);
+    assert_eq!(illegal.category(), ExceptionCategory::Illegal);
+    assert!(illegal.is_fatal());
+
+    let hw_ex = NativeException::HardwareException(AuxHwException::MemoryNotReadable);
+    assert_eq!(hw_ex.category(), ExceptionCategory::Device);
+    assert!(!hw_ex.is_fatal());
+
+    assert_eq!(
+        NativeException::MmuRefusedWrite(0x1000).category(),
+        ExceptionCategory::Memory
+    );
+    assert_eq!(
+        NativeException::Interruption(0x01).category(),
+        ExceptionCategory::Interruption
+    );
+}
+
+#[test]
+fn exception_sink() {
+    let mut ex = 0;
+    let mut sink = ExceptionSink::new(&mut ex);
+    assert!(!sink.is_raised());
+
+    sink.raise(AuxHwException::UnknownOperation(0x42));
+    assert!(sink.is_raised());
+    assert_eq!(ex, 0x1042);
+}
@@ -1,7 +1,24 @@
 use crate::lasm;
+use lrvm::mem::{AuxMappingStatus, ContiguousMappingResult, MappingError, MappingRange};
+use std::collections::{BTreeSet, HashMap};
 
 static DEMO_ASM: &str = include_str!("demo.lasm");
 
+#[test]
+fn lasm_register_operands() {
+    use crate::asm::{Instr, Reg};
+
+    // Register codes are put in the first byte of 2-byte operands
+    assert_eq!(
+        lasm::assemble("cpy a0, a1").unwrap(),
+        Instr::Cpy(Reg::a0, Reg::a1.into()).encode().to_vec()
+    );
+    assert_eq!(
+        Instr::Cpy(Reg::a0, Reg::a1.into()).encode(),
+        [0x0E, 0x00, 0x01, 0x00]
+    );
+}
+
 #[test]
 fn lasm_test() {
     let asm_bytes = lasm::assemble(DEMO_ASM)
@@ -18,3 +35,488 @@ fn lasm_test() {
         "Bad assembly output"
     );
 }
+
+#[test]
+fn lasm_assembler_version() {
+    let version = lasm::assembler_version();
+
+    assert_ne!(version, "unknown", "CustomAsm version was not found");
+    assert!(
+        version.split('.').count() >= 3,
+        "Invalid CustomAsm version: {}",
+        version
+    );
+}
+
+#[test]
+fn lasm_count_instructions() {
+    let count = lasm::count_instructions(DEMO_ASM)
+        .unwrap_or_else(|r| panic!("Failed to assemble demo program: {}", r));
+
+    assert_eq!(count, 5, "Bad instructions count");
+}
+
+#[test]
+fn lasm_defines() {
+    let mut defines = HashMap::new();
+    defines.insert("VALUE".to_string(), 0x1234);
+
+    assert_eq!(
+        lasm::assemble_with_defines("cpy a0, VALUE\nhalt", &defines),
+        lasm::assemble("cpy a0, 0x1234\nhalt")
+    );
+
+    defines.insert("a0".to_string(), 0);
+    assert!(lasm::assemble_with_defines("halt", &defines).is_err());
+
+    defines.remove("a0");
+    defines.insert("0VALUE".to_string(), 0);
+    assert!(lasm::assemble_with_defines("halt", &defines).is_err());
+}
+
+#[test]
+fn lasm_enumerate_devices() {
+    let source = format!(
+        "cpy rr0, 0x1000\ncall enumerate_devices\nhalt\n{}",
+        lasm::ENUMERATE_DEVICES_ROUTINE
+    );
+
+    lasm::assemble(&source)
+        .unwrap_or_else(|r| panic!("Failed to assemble the enumeration routine: {}", r));
+}
+
+#[test]
+fn lasm_blobs() {
+    let blob: &[u8] = &[0x01, 0x02, 0x03, 0x04, 0x05];
+
+    assert!(lasm::assemble_with_blobs("halt", &[("src.lasm", blob)]).is_err());
+    assert!(lasm::assemble_with_blobs("halt", &[("logo.bin", blob), ("logo.bin", blob)]).is_err());
+
+    assert_eq!(
+        lasm::assemble_with_blobs(
+            "cpy a0, LOGO_BIN_LEN\nhalt\nlogo:\n#incbin \"logo.bin\"\nhalt",
+            &[("logo.bin", blob)]
+        ),
+        lasm::assemble("cpy a0, 5\nhalt\n#d32 0x01020304\n#d32 0x05000000\nhalt")
+    );
+}
+
+#[test]
+fn lasm_blob_names() {
+    let blob: &[u8] = &[0x01, 0x02, 0x03, 0x04];
+
+    let err = lasm::assemble_with_blobs("halt", &[("1.bin", blob)]).unwrap_err();
+    assert!(err.contains("'1.bin'"), "Unexpected error: {}", err);
+
+    let err = lasm::assemble_with_blobs("halt", &[("a.bin", blob), ("a_bin", blob)]).unwrap_err();
+    assert_eq!(
+        err,
+        "Blob names 'a.bin' and 'a_bin' both result in constant 'A_BIN_LEN'"
+    );
+}
+
+#[test]
+fn lasm_report() {
+    let report =
+        lasm::assemble_report("cpy a0, 1\nstart:\nhalt\ndata:\n#d32 0x1\n#align 128\nend:\nhalt")
+            .unwrap_or_else(|r| panic!("Failed to assemble the program: {}", r));
+
+    assert_eq!(report.total_bytes, 20);
+    assert_eq!(report.total_words(), 5);
+
+    assert_eq!(
+        report
+            .regions
+            .iter()
+            .map(|region| (region.label.as_str(), region.addr, region.size))
+            .collect::<Vec<_>>(),
+        vec![
+            ("(start)", 0x00, 4),
+            ("start", 0x04, 4),
+            ("data", 0x08, 8),
+            ("end", 0x10, 4)
+        ]
+    );
+
+    assert_eq!(
+        report.alignment_gaps,
+        vec![lasm::AlignmentGap { line: 6, size: 4 }]
+    );
+}
+
+#[test]
+fn lasm_max_size() {
+    let source = "cpy a0, 1\ncpy a1, 2\nhalt";
+
+    assert_eq!(
+        lasm::assemble_with_options(source, &lasm::AssembleOptions::new().max_size_bytes(12)),
+        lasm::assemble(source).map_err(lasm::AssembleError::Assembly)
+    );
+
+    match lasm::assemble_with_options(source, &lasm::AssembleOptions::new().max_size_bytes(8)) {
+        Err(lasm::AssembleError::TooLarge(err)) => assert_eq!(err.overflow(), 4),
+        result => panic!("Expected a size overflow error, got: {:?}", result),
+    }
+
+    assert_eq!(
+        lasm::assemble_checked(source, 12),
+        lasm::assemble(source).map_err(lasm::AssembleError::Assembly)
+    );
+
+    match lasm::assemble_checked(source, 11) {
+        Err(lasm::AssembleError::TooLarge(err)) => {
+            assert_eq!((err.size, err.max_size), (12, 11));
+            assert_eq!(
+                err.to_string(),
+                "Program is 12 bytes long, which exceeds the maximum size of 11 bytes by 1 bytes"
+            );
+        }
+        result => panic!("Expected a size overflow error, got: {:?}", result),
+    }
+}
+
+#[test]
+fn lasm_preprocess() {
+    let source = "cpy a0, 1\n#ifdef DEBUG\ncpy a1, 2\n#else\ncpy a1, 3\n#endif\nhalt";
+    let mut defines = BTreeSet::new();
+
+    assert_eq!(
+        lasm::preprocess(source, &defines),
+        Ok("cpy a0, 1\n\n\n\ncpy a1, 3\n\nhalt".to_string())
+    );
+
+    defines.insert("DEBUG".to_string());
+
+    assert_eq!(
+        lasm::preprocess(source, &defines),
+        Ok("cpy a0, 1\n\ncpy a1, 2\n\n\n\nhalt".to_string())
+    );
+
+    assert_eq!(
+        lasm::preprocess("halt\n#ifdef A\n#ifdef B\n#endif\n#endif", &defines),
+        Err(lasm::PreprocessError::Nested {
+            line: 3,
+            opened_at: 2
+        })
+    );
+
+    assert_eq!(
+        lasm::preprocess("halt\n#ifdef A\nhalt", &defines),
+        Err(lasm::PreprocessError::Unterminated { line: 2 })
+    );
+
+    assert_eq!(
+        lasm::preprocess("#ifdef A\n#else\n#else\n#endif", &defines),
+        Err(lasm::PreprocessError::UnexpectedElse { line: 3 })
+    );
+
+    assert_eq!(
+        lasm::preprocess("halt\n#endif", &defines),
+        Err(lasm::PreprocessError::UnexpectedEndif { line: 2 })
+    );
+
+    assert_eq!(
+        lasm::preprocess("#ifdef\n#endif", &defines),
+        Err(lasm::PreprocessError::MissingName { line: 1 })
+    );
+}
+
+#[test]
+fn lasm_conditional_assembly() {
+    let source = "cpy a0, 1\n#ifdef LOGGING\ncpy a1, 2\n#endif\nhalt";
+
+    assert_eq!(
+        lasm::assemble_with_options(source, &lasm::AssembleOptions::new()),
+        lasm::assemble("cpy a0, 1\nhalt").map_err(lasm::AssembleError::Assembly)
+    );
+
+    assert_eq!(
+        lasm::assemble_with_options(source, &lasm::AssembleOptions::new().define("LOGGING")),
+        lasm::assemble("cpy a0, 1\ncpy a1, 2\nhalt").map_err(lasm::AssembleError::Assembly)
+    );
+
+    assert_eq!(
+        lasm::assemble_with_options("#ifdef LOGGING\nhalt", &lasm::AssembleOptions::new()),
+        Err(lasm::AssembleError::Preprocess(
+            lasm::PreprocessError::Unterminated { line: 1 }
+        ))
+    );
+}
+
+#[test]
+fn lasm_macros() {
+    let source = [
+        "#define VALUE 0x10",
+        "#macro double(reg)",
+        "add reg, reg ; reg is doubled",
+        "#endmacro",
+        "#macro set_double(reg, value)",
+        "cpy reg, value",
+        "double(reg)",
+        "#endmacro",
+        "set_double(a0, VALUE)",
+        "halt",
+    ]
+    .join("\n");
+
+    let (expanded, origins) = lasm::expand_macros(&source).unwrap();
+
+    assert_eq!(
+        expanded.lines().collect::<Vec<_>>(),
+        vec![
+            "",
+            "",
+            "",
+            "",
+            "",
+            "",
+            "",
+            "",
+            "cpy a0, 0x10",
+            "add a0, a0 ; reg is doubled",
+            "halt"
+        ]
+    );
+
+    assert_eq!(origins[7], lasm::LineOrigin::Source(8));
+    assert_eq!(
+        origins[9],
+        lasm::LineOrigin::Macro {
+            name: "double".to_string(),
+            definition: 3,
+            invocation: 9
+        }
+    );
+    assert_eq!(origins[10], lasm::LineOrigin::Source(10));
+
+    // Errors point to the source code's lines and cite the macro's definition
+    assert_eq!(
+        lasm::macros::remap_error_lines("error: bad operand\n --> src.lasm:11:5:", &origins, 1),
+        "error: bad operand\n --> src.lasm:9:5:\nnote: line 9 invokes macro 'double', this code comes from line 3 of its definition"
+    );
+
+    assert_eq!(
+        lasm::expand_macros("#macro loop()\nloop()\n#endmacro\nloop()"),
+        Err(lasm::PreprocessError::MacroRecursionLimit {
+            line: 4,
+            name: "loop".to_string()
+        })
+    );
+
+    assert_eq!(
+        lasm::expand_macros("#macro m(a)\n#endmacro\nm(1, 2)"),
+        Err(lasm::PreprocessError::WrongArgCount {
+            line: 3,
+            name: "m".to_string(),
+            expected: 1,
+            got: 2
+        })
+    );
+
+    assert_eq!(
+        lasm::expand_macros("halt\n#macro m()\nhalt"),
+        Err(lasm::PreprocessError::UnterminatedMacro { line: 2 })
+    );
+}
+
+#[test]
+fn lasm_macro_device_write() {
+    let source = [
+        "#define DISPLAY 0x1000",
+        "#macro write_dev(addr, value)",
+        "cpy rr0, addr",
+        "cpy avr, value",
+        "wea rr0, 0, 0",
+        "#endmacro",
+        "write_dev(DISPLAY, 0xAA)",
+        "halt",
+    ]
+    .join("\n");
+
+    assert_eq!(
+        lasm::assemble_with_options(
+            &source,
+            &lasm::AssembleOptions::new().enable_preprocessor(true)
+        ),
+        lasm::assemble("cpy rr0, 0x1000\ncpy avr, 0xAA\nwea rr0, 0, 0\nhalt")
+            .map_err(lasm::AssembleError::Assembly)
+    );
+}
+
+#[test]
+fn lasm_define_comment() {
+    let (expanded, _) =
+        lasm::expand_macros("#define PORT 0x10 ; display\nwsa PORT, 0, a0").unwrap();
+    assert_eq!(
+        expanded.lines().collect::<Vec<_>>(),
+        vec!["", "wsa 0x10, 0, a0"]
+    );
+
+    assert_eq!(
+        lasm::expand_macros("#define ; PORT 0x10"),
+        Err(lasm::PreprocessError::InvalidDefine { line: 1 })
+    );
+}
+
+#[test]
+fn lasm_elf() {
+    let code = [0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08];
+    let elf = lasm::elf_from_binary(&code, 0x1000);
+
+    let u16_at = |offset: usize| u16::from_be_bytes([elf[offset], elf[offset + 1]]);
+    let u32_at = |offset: usize| {
+        u32::from_be_bytes([
+            elf[offset],
+            elf[offset + 1],
+            elf[offset + 2],
+            elf[offset + 3],
+        ])
+    };
+
+    // Identification: 32-bit, big-endian
+    assert_eq!(elf[..6], [0x7F, b'E', b'L', b'F', 1, 2]);
+    assert_eq!(u16_at(16), 1);
+    assert_eq!(u16_at(18), lasm::ELF_MACHINE);
+    assert_eq!(u32_at(24), 0x1000);
+
+    let sh_offset = u32_at(32) as usize;
+    assert_eq!(sh_offset % 4, 0);
+    assert_eq!(elf.len(), sh_offset + 3 * 40);
+    assert_eq!(u16_at(48), 3);
+
+    // '.text' section
+    let text = sh_offset + 40;
+    let (addr, offset, size) = (u32_at(text + 12), u32_at(text + 16), u32_at(text + 20));
+    assert_eq!(addr, 0x1000);
+    assert_eq!(elf[offset as usize..(offset + size) as usize], code);
+
+    // Section names
+    let names = sh_offset + 80;
+    let names_offset = u32_at(names + 16) as usize;
+    let name_of = |header: usize| {
+        let start = names_offset + u32_at(header) as usize;
+        let len = elf[start..].iter().position(|c| *c == 0).unwrap();
+        String::from_utf8(elf[start..start + len].to_vec()).unwrap()
+    };
+
+    assert_eq!(name_of(text), ".text");
+    assert_eq!(name_of(names), ".shstrtab");
+
+    assert_eq!(
+        lasm::assemble_to_elf("halt", 0x1000),
+        lasm::assemble("halt").map(|code| lasm::elf_from_binary(&code, 0x1000))
+    );
+}
+
+#[test]
+fn lasm_branch_reach() {
+    let source = [
+        "start:",
+        "    cmp a0, 0",
+        "    ifeq",
+        "    jp far ; out of reach",
+        "    jp near",
+        "    jpr far - $",
+        "near:",
+        "    jp a0",
+        "#align 0x80000",
+        "far:",
+        "    halt",
+    ]
+    .join("\n");
+
+    assert_eq!(
+        lasm::check_branch_reach(&source),
+        Ok(vec![
+            lasm::ReachIssue {
+                line: 4,
+                label: "far".to_string(),
+                distance: 0x4000,
+                max_reach: 0x3FFF
+            },
+            lasm::ReachIssue {
+                line: 6,
+                label: "far".to_string(),
+                distance: 0x3FFC,
+                max_reach: 0x1FFF
+            }
+        ])
+    );
+
+    assert_eq!(lasm::check_branch_reach("jp a0\nhalt"), Ok(vec![]));
+}
+
+#[test]
+fn lasm_cache() {
+    let mut cache = lasm::AssembleCache::new();
+
+    let first = cache
+        .get_or_assemble(DEMO_ASM)
+        .unwrap_or_else(|r| panic!("Failed to assemble demo program: {}", r));
+
+    assert_eq!(cache.get_or_assemble(DEMO_ASM), Ok(first));
+    assert_eq!(cache.assembled(), 1);
+
+    cache.get_or_assemble("halt").unwrap();
+    assert_eq!(cache.assembled(), 2);
+    assert_eq!(cache.len(), 2);
+
+    // Errors are not cached
+    assert!(cache.get_or_assemble("unknown_instr").is_err());
+    assert!(cache.get_or_assemble("unknown_instr").is_err());
+    assert_eq!(cache.assembled(), 4);
+}
+
+fn machine_mapping() -> ContiguousMappingResult {
+    let aux = |aux_id: usize, name: &str, start_addr: u32, end_addr: u32| AuxMappingStatus {
+        aux_id,
+        aux_hw_id: aux_id as u64,
+        aux_name: name.to_string(),
+        aux_mapping: Ok(MappingRange {
+            start_addr,
+            end_addr,
+        }),
+    };
+
+    ContiguousMappingResult {
+        mapping: Ok(MappingRange {
+            start_addr: 0x0000,
+            end_addr: 0x2FFF,
+        }),
+        aux_mapping: vec![
+            aux(0, "BootROM", 0x0000, 0x0FFF),
+            aux(1, "RAM", 0x1000, 0x1FFF),
+            aux(2, "RAM", 0x2000, 0x2FFF),
+            aux(3, "Buffered Display", 0x3000, 0x30FF),
+            aux(4, "RAM 2", 0x3100, 0x31FF),
+            aux(5, "div", 0x3200, 0x32FF),
+            AuxMappingStatus {
+                aux_id: 6,
+                aux_hw_id: 6,
+                aux_name: "Unmapped".to_string(),
+                aux_mapping: Err(MappingError::NullBusSize),
+            },
+        ],
+    }
+}
+
+#[test]
+fn lasm_machine_consts() {
+    assert_eq!(
+        lasm::mapping_to_lasm_consts(&machine_mapping()),
+        "BOOTROM = 0x00000000\n\
+         RAM = 0x00001000\n\
+         RAM_2 = 0x00002000\n\
+         BUFFERED_DISPLAY = 0x00003000\n\
+         RAM_2_2 = 0x00003100\n\
+         DIV = 0x00003200"
+    );
+}
+
+#[test]
+fn lasm_assemble_for_machine() {
+    assert_eq!(
+        lasm::assemble_for_machine("cpy a0, BUFFERED_DISPLAY\nhalt", &machine_mapping()),
+        lasm::assemble("cpy a0, 0x3000\nhalt")
+    );
+}
@@ -0,0 +1,142 @@
+use crate::metadata::{
+    hw_id_from, ClockType, DeviceCategory, DeviceMetadata, DisplayType, HwIdAllocator, MemoryType,
+    StorageType,
+};
+
+#[test]
+fn hw_id_allocation() {
+    let mut alloc = HwIdAllocator::new(0);
+    let ids: Vec<u64> = (0..100).map(|_| alloc.allocate()).collect();
+
+    let mut same_seed = HwIdAllocator::new(0);
+    assert_eq!(
+        ids,
+        (0..100).map(|_| same_seed.allocate()).collect::<Vec<_>>()
+    );
+
+    let mut other_seed = HwIdAllocator::new(1);
+    assert_ne!(
+        ids,
+        (0..100).map(|_| other_seed.allocate()).collect::<Vec<_>>()
+    );
+
+    let mut sorted = ids.clone();
+    sorted.sort_unstable();
+    sorted.dedup();
+    assert_eq!(
+        sorted.len(),
+        ids.len(),
+        "Allocated identifiers are not unique"
+    );
+}
+
+#[test]
+fn hw_id_from_name() {
+    assert_eq!(hw_id_from("BootROM", 0), hw_id_from("BootROM", 0));
+    assert_eq!(hw_id_from("BootROM", 0), 0x05DF_5833_B087_5882);
+
+    assert_ne!(hw_id_from("BootROM", 0), hw_id_from("BootROM", 1));
+    assert_ne!(hw_id_from("BootROM", 0), hw_id_from("RAM", 0));
+    assert_ne!(hw_id_from("RAM", 1), hw_id_from("RAM1", 0));
+}
+
+#[test]
+fn hw_id_reservation() {
+    let mut alloc = HwIdAllocator::new(0);
+    let first = HwIdAllocator::new(0).allocate();
+
+    assert!(alloc.reserve(first).is_ok());
+    assert!(alloc.reserve(first).is_err());
+    assert!(alloc.is_used(first));
+
+    let allocated = alloc.allocate();
+    assert_ne!(allocated, first);
+    assert!(alloc.reserve(allocated).is_err());
+}
+
+#[test]
+fn unknown_storage_type() {
+    let code = StorageType::Readonly.encode() + 0xFF;
+
+    assert_eq!(
+        DeviceCategory::decode(code),
+        Ok(DeviceCategory::Storage(StorageType::Unknown(0x0000_01FF)))
+    );
+    assert_eq!(StorageType::Unknown(0x0000_01FF).encode(), code);
+    assert_eq!(
+        DeviceCategory::decode(StorageType::Flash.encode()),
+        Ok(StorageType::Flash.wrap())
+    );
+    assert_eq!(
+        format!("{}", StorageType::Unknown(0x0000_01FF).wrap()),
+        "Storage:Unknown(Code=0x000001FF)"
+    );
+}
+
+#[test]
+fn metadata_for_test() {
+    let metadata = DeviceMetadata::for_test(0x100);
+
+    assert_eq!(metadata.hw_id, 0x1);
+    assert_eq!(metadata.size, 0x100);
+    assert_eq!(metadata.category, MemoryType::Ram.into());
+    assert_eq!(metadata.model, None);
+    assert_eq!(metadata.data, None);
+}
+
+#[test]
+fn metadata_version() {
+    let metadata = DeviceMetadata::new(0x1234, 0x100, MemoryType::Ram.into(), None, None)
+        .with_version("1.2.0")
+        .unwrap();
+
+    let decoded = DeviceMetadata::decode(metadata.encode()).unwrap();
+
+    assert_eq!(decoded.version(), Some("1.2.0".to_string()));
+    assert_eq!(decoded.hw_id, 0x1234);
+    assert_eq!(decoded.size, 0x100);
+
+    let full = DeviceMetadata::new(0, 4, MemoryType::Ram.into(), None, None)
+        .with_version("12345678")
+        .unwrap();
+    assert_eq!(full.version(), Some("12345678".to_string()));
+
+    let empty = || DeviceMetadata::new(0, 4, MemoryType::Ram.into(), None, None);
+    assert_eq!(empty().version(), None);
+
+    assert!(empty().with_version("123456789").is_err());
+    assert!(empty().with_version("1.0-é").is_err());
+}
+
+#[test]
+fn metadata_dimensions() {
+    let metadata = DeviceMetadata::new(0x1234, 0x100, DisplayType::Color.into(), None, None)
+        .with_dimensions(0x0140, 0x00C8);
+
+    let decoded = DeviceMetadata::decode(metadata.encode()).unwrap();
+
+    assert_eq!(
+        decoded.category,
+        DeviceCategory::Display(DisplayType::Color)
+    );
+    assert_eq!(decoded.data, Some(0x0140_00C8));
+    assert_eq!(decoded.dimensions(), Some((0x0140, 0x00C8)));
+
+    let versioned = DeviceMetadata::new(0, 4, DisplayType::Color.into(), None, None)
+        .with_version("1.0")
+        .unwrap();
+    assert_eq!(versioned.dimensions(), None);
+}
+
+#[test]
+fn category_size_hint() {
+    assert_eq!(
+        DeviceCategory::Clock(ClockType::Realtime).size_hint(),
+        Some(24)
+    );
+    assert_eq!(ClockType::HighResolution.wrap().size_hint(), Some(8));
+    assert_eq!(DisplayType::Number.wrap().size_hint(), Some(16));
+    assert_eq!(DeviceCategory::Memory(MemoryType::Ram).size_hint(), None);
+    assert_eq!(DisplayType::Buffered.wrap().size_hint(), None);
+    assert_eq!(DeviceCategory::Uncategorized().size_hint(), None);
+}
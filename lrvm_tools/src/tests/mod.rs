@@ -1,2 +1,6 @@
 mod asm;
+mod bytes;
+mod debug;
+mod exceptions;
 mod lasm;
+mod metadata;